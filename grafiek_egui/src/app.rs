@@ -53,14 +53,23 @@ impl GrafiekApp {
         let device = render_state.device.clone();
         let queue = render_state.queue.clone();
 
-        let engine = Engine::init(EngineDescriptor {
+        let mut engine = Engine::init(EngineDescriptor {
             device,
             queue,
+            default_format: None,
             on_message: Some(Box::new(move |msg| {
                 let _ = tx.send(msg);
             })),
         })?;
 
+        // Keep the UI responsive while shaders are edited, wasm has no threads to compile on
+        #[cfg(not(target_arch = "wasm32"))]
+        engine.set_async_compile(true);
+
+        if let Err(e) = engine.set_user_library(crate::consts::USER_LIBRARY_DIR) {
+            log::error!("Failed to load user library: {e}");
+        }
+
         let app = Self {
             engine,
             view_state: Default::default(),
@@ -84,12 +93,8 @@ impl GrafiekApp {
         ctx: &egui::Context,
         handle: &grafiek_engine::TextureHandle,
     ) -> Option<egui::TextureId> {
-        let engine_id = handle.id()?;
-        let wgpu_texture = self.engine.get_texture(handle)?;
-        Some(
-            self.texture_cache
-                .get_or_register(ctx, &self.render_state, engine_id, wgpu_texture),
-        )
+        self.texture_cache
+            .get_or_register(ctx, &self.render_state, &self.engine, handle)
     }
 
     pub fn save_project(&mut self) {
@@ -265,6 +270,9 @@ impl eframe::App for GrafiekApp {
 
         self.view_state.notifications.show(ctx);
 
+        // Pick up shader files edited outside of the app
+        self.engine.poll();
+
         let dirty = self.process_messages();
 
         if dirty {
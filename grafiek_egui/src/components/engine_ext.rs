@@ -106,13 +106,14 @@ impl EngineExt for Engine {
         };
 
         engine_node.configs().any(|(slot_def, _)| {
-            matches!(
-                slot_def.extended(),
-                ExtendedMetadata::String(StringMeta {
-                    kind: StringKind::Glsl | StringKind::Rune,
-                    ..
-                })
-            )
+            slot_def.is_visible()
+                && matches!(
+                    slot_def.extended(),
+                    ExtendedMetadata::String(StringMeta {
+                        kind: StringKind::Glsl | StringKind::Rune,
+                        ..
+                    })
+                )
         })
     }
 
@@ -1,37 +1,39 @@
-use grafiek_engine::{Engine, NodeIndex};
+use grafiek_engine::{Engine, ImageData, NodeIndex};
 
 #[cfg(not(target_arch = "wasm32"))]
 pub fn pick_and_load_image(engine: &mut Engine, node_idx: NodeIndex, slot: usize) {
     let Some(path) = rfd::FileDialog::new()
-        .add_filter("Images", &["png", "jpg", "jpeg", "bmp", "gif", "webp"])
+        .add_filter(
+            "Images",
+            &[
+                "png", "jpg", "jpeg", "bmp", "gif", "webp", "tif", "tiff", "exr", "hdr",
+            ],
+        )
         .pick_file()
     else {
         return;
     };
 
-    let img = match image::open(&path) {
-        Ok(img) => img.into_rgba8(),
+    let image = match ImageData::open(&path) {
+        Ok(image) => image,
         Err(e) => return log::error!("Failed to load image {path:?}: {e}"),
     };
 
-    let (w, h) = img.dimensions();
-    if let Err(e) = engine.upload_texture(node_idx, slot, w, h, &img.into_raw()) {
+    if let Err(e) = engine.upload_image(node_idx, slot, &image) {
         log::error!("Failed to upload texture: {e}");
     }
 }
 
 #[cfg(target_arch = "wasm32")]
 mod web {
-    use grafiek_engine::NodeIndex;
+    use grafiek_engine::{ImageData, NodeIndex};
     use std::cell::RefCell;
     use wasm_bindgen::prelude::*;
 
     pub struct PendingUpload {
         pub node_idx: NodeIndex,
         pub slot: usize,
-        pub width: u32,
-        pub height: u32,
-        pub data: Vec<u8>,
+        pub image: ImageData,
     }
 
     thread_local! {
@@ -49,7 +51,8 @@ mod web {
             .expect("not an input element");
 
         input.set_type("file");
-        input.set_accept("image/png,image/jpeg,image/gif,image/webp,image/bmp");
+        input
+            .set_accept("image/png,image/jpeg,image/gif,image/webp,image/bmp,image/tiff,.exr,.hdr");
 
         let input_clone = input.clone();
         let onchange = Closure::once(Box::new(move || {
@@ -68,21 +71,17 @@ mod web {
                 let array = js_sys::Uint8Array::new(&result);
                 let bytes = array.to_vec();
 
-                match image::load_from_memory(&bytes) {
-                    Ok(img) => {
-                        let rgba = img.into_rgba8();
-                        let (w, h) = rgba.dimensions();
+                match ImageData::decode(&bytes) {
+                    Ok(image) => {
                         PENDING_UPLOADS.with(|uploads| {
                             uploads.borrow_mut().push(PendingUpload {
                                 node_idx,
                                 slot,
-                                width: w,
-                                height: h,
-                                data: rgba.into_raw(),
+                                image,
                             });
                         });
                     }
-                    Err(e) => log::error!("Failed to decode image: {e}"),
+                    Err(e) => log::error!("{e}"),
                 }
             }));
 
@@ -110,13 +109,7 @@ pub fn pick_and_load_image(_engine: &mut Engine, node_idx: NodeIndex, slot: usiz
 pub fn process_pending_uploads(engine: &mut Engine) {
     web::PENDING_UPLOADS.with(|uploads| {
         for upload in uploads.borrow_mut().drain(..) {
-            if let Err(e) = engine.upload_texture(
-                upload.node_idx,
-                upload.slot,
-                upload.width,
-                upload.height,
-                &upload.data,
-            ) {
+            if let Err(e) = engine.upload_image(upload.node_idx, upload.slot, &upload.image) {
                 log::error!("Failed to upload texture: {e}");
             }
         }
@@ -1,4 +1,5 @@
 use egui::{Context, RichText, ScrollArea, TextEdit};
+use grafiek_engine::error::LocatedError;
 use grafiek_engine::{Engine, NodeIndex};
 
 use crate::components::engine_ext::EngineExt;
@@ -95,6 +96,9 @@ impl BottomPanel {
         };
 
         let script_slot = node.configs().enumerate().find_map(|(i, (slot_def, _))| {
+            if !slot_def.is_visible() {
+                return None;
+            }
             let ExtendedMetadata::String(StringMeta { kind, .. }) = slot_def.extended() else {
                 return None;
             };
@@ -129,9 +133,11 @@ impl BottomPanel {
         let has_pending_changes = pending_source != current_source;
 
         let script_errors = Self::collect_script_errors(engine, idx);
+        // Errors inside included files have no line in this editor
         let lints: Vec<_> = script_errors
             .iter()
-            .map(|(line, _, msg)| egui_code_editor::lint::Lint::error(*line as usize, msg.clone()))
+            .filter(|e| e.file.is_none())
+            .map(|e| egui_code_editor::lint::Lint::error(e.line as usize, e.message.clone()))
             .collect();
 
         ui.vertical(|ui| {
@@ -227,18 +233,18 @@ impl BottomPanel {
         }
     }
 
-    fn collect_script_errors(engine: &Engine, idx: NodeIndex) -> Vec<(u32, u32, String)> {
+    fn collect_script_errors(engine: &Engine, idx: NodeIndex) -> Vec<LocatedError> {
         engine
             .node_errors(idx)
             .into_iter()
             .flatten()
             .filter_map(|e| e.as_script_error())
             .flat_map(|se| se.errors.iter())
-            .map(|e| (e.line, e.column, e.message.clone()))
+            .cloned()
             .collect()
     }
 
-    fn show_error_count(ui: &mut egui::Ui, errors: &[(u32, u32, String)]) {
+    fn show_error_count(ui: &mut egui::Ui, errors: &[LocatedError]) {
         if !errors.is_empty() {
             ui.label(
                 RichText::new(format!("{} error(s)", errors.len()))
@@ -15,6 +15,7 @@ pub fn node_color(lib: &str) -> Color32 {
     match lib {
         "core" => Color32::from_rgb(50, 88, 80),
         "math" => Color32::from_rgb(60, 82, 130),
+        "user" => Color32::from_rgb(110, 70, 110),
         _ => Color32::from_rgb(60, 80, 100),
     }
 }
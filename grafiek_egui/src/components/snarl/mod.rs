@@ -58,6 +58,37 @@ impl SnarlState {
     }
 }
 
+impl SnarlView<'_> {
+    /// Name field and button for saving a shader node into the user library.
+    fn show_save_operator_menu(&mut self, ui: &mut egui::Ui, idx: NodeIndex) {
+        let name_id = egui::Id::new(("save_operator_name", idx));
+        let mut name = ui
+            .data(|d| d.get_temp::<String>(name_id))
+            .unwrap_or_default();
+
+        ui.horizontal(|ui| {
+            ui.add(egui::TextEdit::singleline(&mut name).hint_text("operator name"));
+            if ui.button("Save as Operator").clicked() {
+                match self.engine.save_shader_operator(idx, &name) {
+                    Ok(path) => {
+                        let msg = format!("Saved {}/{}", path.library, path.operator);
+                        log::info!("{}", msg);
+                        self.view.notifications.success(msg);
+                        ui.close();
+                    }
+                    Err(e) => {
+                        let msg = format!("Failed to save operator: {}", e);
+                        log::error!("{}", msg);
+                        self.view.notifications.error(msg);
+                    }
+                }
+            }
+        });
+
+        ui.data_mut(|d| d.insert_temp(name_id, name));
+    }
+}
+
 impl<'a> SnarlViewer<NodeData> for SnarlView<'a> {
     fn draw_background(
         &mut self,
@@ -97,6 +128,14 @@ impl<'a> SnarlViewer<NodeData> for SnarlView<'a> {
         ui.horizontal(|ui| {
             ui.label(title);
 
+            if self
+                .engine
+                .get_node(data.engine_node)
+                .is_some_and(|n| n.is_compiling())
+            {
+                ui.spinner().on_hover_text("compiling");
+            }
+
             let Some(errors) = self.engine.node_errors(data.engine_node) else {
                 return;
             };
@@ -112,10 +151,15 @@ impl<'a> SnarlViewer<NodeData> for SnarlView<'a> {
                             for loc_err in &script_err.errors {
                                 ui.horizontal(|ui| {
                                     ui.label(
-                                        egui::RichText::new(format!(
-                                            "{}:{}",
-                                            loc_err.line, loc_err.column
-                                        ))
+                                        egui::RichText::new(match &loc_err.file {
+                                            Some(file) => format!(
+                                                "{file}:{}:{}",
+                                                loc_err.line, loc_err.column
+                                            ),
+                                            None => {
+                                                format!("{}:{}", loc_err.line, loc_err.column)
+                                            }
+                                        })
                                         .color(egui::Color32::LIGHT_GRAY)
                                         .monospace(),
                                     );
@@ -343,6 +387,10 @@ impl<'a> SnarlViewer<NodeData> for SnarlView<'a> {
             ui.close();
         }
 
+        if self.engine.has_script(data.engine_node) {
+            self.show_save_operator_menu(ui, data.engine_node);
+        }
+
         ui.separator();
 
         if ui.button("Delete").clicked() {
@@ -372,14 +420,14 @@ impl<'a> SnarlViewer<NodeData> for SnarlView<'a> {
                 for operator in operators {
                     if ui.button(operator).clicked() {
                         ui.close();
-                        picked = Some((pos, category, operator));
+                        picked = Some((pos, category.to_owned(), operator.to_owned()));
                     }
                 }
             });
         }
 
         if let Some((pos, library, name)) = picked {
-            match self.engine.instance_node(library, name) {
+            match self.engine.instance_node(&library, &name) {
                 Ok(idx) => {
                     let _ = self.engine.set_node_position(idx, (pos.x, pos.y));
                 }
@@ -2,13 +2,14 @@ use std::collections::HashMap;
 use std::sync::Arc;
 
 use egui::{Color32, TextureId as EguiTextureId, Vec2};
-use grafiek_engine::{Engine, TextureHandle, TextureId};
+use grafiek_engine::{ColorSpace, Engine, TextureHandle, TextureId};
 
 use crate::consts::preview::BOX_SIZE;
 
 struct CachedTexture {
     egui_id: EguiTextureId,
     generation: u64,
+    color_space: ColorSpace,
 }
 
 #[derive(Default)]
@@ -23,15 +24,11 @@ pub fn show_texture_preview(
     render_state: &Arc<eframe::egui_wgpu::RenderState>,
     handle: &TextureHandle,
 ) -> bool {
-    let Some(tex_id) = handle.id() else {
-        return false;
-    };
-    let Some(wgpu_tex) = engine.get_texture(handle) else {
+    let Some(egui_tex) = texture_cache.get_or_register(ui.ctx(), render_state, engine, handle)
+    else {
         return false;
     };
 
-    let egui_tex = texture_cache.get_or_register(ui.ctx(), render_state, tex_id, wgpu_tex);
-
     // Calculate image size to fit within letterbox while preserving aspect ratio
     let img_w = handle.width() as f32;
     let img_h = handle.height() as f32;
@@ -64,25 +61,32 @@ impl TextureCache {
         &mut self,
         _ctx: &egui::Context,
         render_state: &eframe::egui_wgpu::RenderState,
-        engine_id: TextureId,
-        wgpu_texture: &wgpu::Texture,
-    ) -> EguiTextureId {
-        self.get_or_register_without_ctx(render_state, engine_id, wgpu_texture)
+        engine: &Engine,
+        handle: &TextureHandle,
+    ) -> Option<EguiTextureId> {
+        self.get_or_register_without_ctx(render_state, engine, handle)
     }
 
+    /// egui expects textures to sample as linear light, so sRGB encoded
+    /// textures are registered through the engine's display view.
     pub fn get_or_register_without_ctx(
         &mut self,
         render_state: &eframe::egui_wgpu::RenderState,
-        engine_id: TextureId,
-        wgpu_texture: &wgpu::Texture,
-    ) -> EguiTextureId {
+        engine: &Engine,
+        handle: &TextureHandle,
+    ) -> Option<EguiTextureId> {
+        let engine_id = handle.id()?;
+        let color_space = handle.color_space();
+
         // Check if we have a cached entry
         if let Some(cached) = self.cache.get_mut(&engine_id.stable_id) {
-            if cached.generation == engine_id.generation {
-                return cached.egui_id;
+            if cached.generation == engine_id.generation && cached.color_space == color_space {
+                return Some(cached.egui_id);
             }
 
-            let view = wgpu_texture.create_view(&wgpu::TextureViewDescriptor::default());
+            let view = engine.display_view(handle)?;
+            cached.generation = engine_id.generation;
+            cached.color_space = color_space;
 
             let mut renderer = render_state.renderer.write();
             renderer.update_egui_texture_from_wgpu_texture(
@@ -91,9 +95,9 @@ impl TextureCache {
                 wgpu::FilterMode::Linear,
                 cached.egui_id,
             );
-            return cached.egui_id;
+            return Some(cached.egui_id);
         } else {
-            let view = wgpu_texture.create_view(&wgpu::TextureViewDescriptor::default());
+            let view = engine.display_view(handle)?;
             let mut renderer = render_state.renderer.write();
 
             let egui_id = renderer.register_native_texture(
@@ -107,9 +111,10 @@ impl TextureCache {
                 CachedTexture {
                     generation: engine_id.generation,
                     egui_id,
+                    color_space,
                 },
             );
-            egui_id
+            Some(egui_id)
         }
     }
 
@@ -6,6 +6,9 @@ pub mod preview {
     pub const BOX_SIZE: f32 = 150.0;
 }
 
+/// Directory user shader operators are saved to, relative to the working directory
+pub const USER_LIBRARY_DIR: &str = "grafiek_library";
+
 /// UI color palette
 pub mod colors {
     use super::Color32;
@@ -0,0 +1,160 @@
+use std::collections::HashMap;
+
+use wgpu::util::DeviceExt;
+use wgpu::{BindGroupLayout, CommandEncoder, Device, RenderPipeline, Texture, TextureView};
+
+use crate::ColorSpace;
+use crate::gpu_pool::mip_view;
+
+const SHADER: &str = include_str!("color_convert.wgsl");
+
+impl ColorSpace {
+    /// Id of the space in `color_convert.wgsl`
+    fn shader_id(self) -> u32 {
+        match self {
+            ColorSpace::Srgb => 0,
+            ColorSpace::Linear => 1,
+            ColorSpace::DisplayP3 => 2,
+            ColorSpace::LinearRec2020 => 3,
+        }
+    }
+}
+
+/// Render passes converting textures between colour spaces, one pipeline per
+/// target format built on first use. Source and target must be the same size.
+pub(crate) struct ColorConverter {
+    layout: BindGroupLayout,
+    module: wgpu::ShaderModule,
+    pipelines: HashMap<wgpu::TextureFormat, RenderPipeline>,
+}
+
+impl std::fmt::Debug for ColorConverter {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_list().entries(self.pipelines.keys()).finish()
+    }
+}
+
+impl ColorConverter {
+    pub fn new(device: &Device) -> Self {
+        let layout = device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+            label: Some("color convert"),
+            entries: &[
+                wgpu::BindGroupLayoutEntry {
+                    binding: 0,
+                    visibility: wgpu::ShaderStages::FRAGMENT,
+                    ty: wgpu::BindingType::Texture {
+                        // Loaded rather than sampled, so float formats work without filtering
+                        sample_type: wgpu::TextureSampleType::Float { filterable: false },
+                        view_dimension: wgpu::TextureViewDimension::D2,
+                        multisampled: false,
+                    },
+                    count: None,
+                },
+                wgpu::BindGroupLayoutEntry {
+                    binding: 1,
+                    visibility: wgpu::ShaderStages::FRAGMENT,
+                    ty: wgpu::BindingType::Buffer {
+                        ty: wgpu::BufferBindingType::Uniform,
+                        has_dynamic_offset: false,
+                        min_binding_size: None,
+                    },
+                    count: None,
+                },
+            ],
+        });
+
+        let module = device.create_shader_module(wgpu::ShaderModuleDescriptor {
+            label: Some("color convert"),
+            source: wgpu::ShaderSource::Wgsl(SHADER.into()),
+        });
+
+        Self {
+            layout,
+            module,
+            pipelines: HashMap::new(),
+        }
+    }
+
+    fn pipeline(&mut self, device: &Device, format: wgpu::TextureFormat) -> &RenderPipeline {
+        self.pipelines.entry(format).or_insert_with(|| {
+            let layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+                label: Some("color convert"),
+                bind_group_layouts: &[&self.layout],
+                push_constant_ranges: &[],
+            });
+            device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+                label: Some("color convert"),
+                layout: Some(&layout),
+                vertex: wgpu::VertexState {
+                    module: &self.module,
+                    entry_point: Some("vs_main"),
+                    compilation_options: Default::default(),
+                    buffers: &[],
+                },
+                fragment: Some(wgpu::FragmentState {
+                    module: &self.module,
+                    entry_point: Some("fs_main"),
+                    compilation_options: Default::default(),
+                    targets: &[Some(format.into())],
+                }),
+                primitive: Default::default(),
+                depth_stencil: None,
+                multisample: Default::default(),
+                multiview: None,
+                cache: None,
+            })
+        })
+    }
+
+    /// Record a pass re-encoding `src` from one colour space to another into
+    /// the first mip level of `dst`.
+    pub fn convert(
+        &mut self,
+        device: &Device,
+        encoder: &mut CommandEncoder,
+        src: &TextureView,
+        dst: &Texture,
+        (src_space, dst_space): (ColorSpace, ColorSpace),
+    ) {
+        let spaces = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+            label: Some("color convert spaces"),
+            contents: &[src_space.shader_id(), dst_space.shader_id(), 0, 0]
+                .map(u32::to_ne_bytes)
+                .concat(),
+            usage: wgpu::BufferUsages::UNIFORM,
+        });
+        let bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
+            label: Some("color convert"),
+            layout: &self.layout,
+            entries: &[
+                wgpu::BindGroupEntry {
+                    binding: 0,
+                    resource: wgpu::BindingResource::TextureView(src),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 1,
+                    resource: spaces.as_entire_binding(),
+                },
+            ],
+        });
+
+        let view = mip_view(dst, 0);
+        let pipeline = self.pipeline(device, dst.format());
+        let mut pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+            label: Some("color convert"),
+            color_attachments: &[Some(wgpu::RenderPassColorAttachment {
+                view: &view,
+                depth_slice: None,
+                resolve_target: None,
+                ops: wgpu::Operations {
+                    load: wgpu::LoadOp::Clear(wgpu::Color::TRANSPARENT),
+                    store: wgpu::StoreOp::Store,
+                },
+            })],
+            ..Default::default()
+        });
+        pass.set_pipeline(pipeline);
+        pass.set_bind_group(0, &bind_group, &[]);
+        pass.draw(0..3, 0..1);
+    }
+}
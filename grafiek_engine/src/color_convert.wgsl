@@ -0,0 +1,103 @@
+// Converts a texture between colour spaces by way of linear Rec. 709.
+// Space ids match the order of ColorSpace.
+
+struct Spaces {
+    src: u32,
+    dst: u32,
+    // Uniforms are padded to 16 bytes on some backends
+    _pad: vec2<u32>,
+}
+
+@group(0) @binding(0) var src_tex: texture_2d<f32>;
+@group(0) @binding(1) var<uniform> spaces: Spaces;
+
+const SRGB: u32 = 0u;
+const LINEAR: u32 = 1u;
+const DISPLAY_P3: u32 = 2u;
+const LINEAR_REC2020: u32 = 3u;
+
+@vertex
+fn vs_main(@builtin(vertex_index) i: u32) -> @builtin(position) vec4<f32> {
+    // One triangle covering the whole target
+    let uv = vec2<f32>(f32((i << 1u) & 2u), f32(i & 2u));
+    return vec4<f32>(uv * 2.0 - 1.0, 0.0, 1.0);
+}
+
+fn srgb_to_linear(c: vec3<f32>) -> vec3<f32> {
+    let lo = c / 12.92;
+    let hi = pow((c + 0.055) / 1.055, vec3<f32>(2.4));
+    return select(hi, lo, c <= vec3<f32>(0.04045));
+}
+
+fn linear_to_srgb(c: vec3<f32>) -> vec3<f32> {
+    let lo = c * 12.92;
+    let hi = 1.055 * pow(max(c, vec3<f32>(0.0)), vec3<f32>(1.0 / 2.4)) - 0.055;
+    return select(hi, lo, c <= vec3<f32>(0.0031308));
+}
+
+// Rows of every matrix sum to 1 so white stays white
+fn mul_rows(r0: vec3<f32>, r1: vec3<f32>, r2: vec3<f32>, c: vec3<f32>) -> vec3<f32> {
+    return vec3<f32>(dot(r0, c), dot(r1, c), dot(r2, c));
+}
+
+// To linear Rec. 709
+fn decode(space: u32, c: vec3<f32>) -> vec3<f32> {
+    switch space {
+        case SRGB: {
+            return srgb_to_linear(c);
+        }
+        case DISPLAY_P3: {
+            return mul_rows(
+                vec3<f32>(1.2249, -0.2249, 0.0),
+                vec3<f32>(-0.0420, 1.0420, 0.0),
+                vec3<f32>(-0.0197, -0.0786, 1.0983),
+                srgb_to_linear(c),
+            );
+        }
+        case LINEAR_REC2020: {
+            return mul_rows(
+                vec3<f32>(1.6605, -0.5876, -0.0729),
+                vec3<f32>(-0.1246, 1.1329, -0.0083),
+                vec3<f32>(-0.0182, -0.1006, 1.1188),
+                c,
+            );
+        }
+        default: {
+            return c;
+        }
+    }
+}
+
+// From linear Rec. 709
+fn encode(space: u32, c: vec3<f32>) -> vec3<f32> {
+    switch space {
+        case SRGB: {
+            return linear_to_srgb(c);
+        }
+        case DISPLAY_P3: {
+            return linear_to_srgb(mul_rows(
+                vec3<f32>(0.8225, 0.1775, 0.0),
+                vec3<f32>(0.0332, 0.9668, 0.0),
+                vec3<f32>(0.0171, 0.0724, 0.9105),
+                c,
+            ));
+        }
+        case LINEAR_REC2020: {
+            return mul_rows(
+                vec3<f32>(0.6274, 0.3293, 0.0433),
+                vec3<f32>(0.0691, 0.9195, 0.0114),
+                vec3<f32>(0.0164, 0.0880, 0.8956),
+                c,
+            );
+        }
+        default: {
+            return c;
+        }
+    }
+}
+
+@fragment
+fn fs_main(@builtin(position) pos: vec4<f32>) -> @location(0) vec4<f32> {
+    let texel = textureLoad(src_tex, vec2<i32>(pos.xy), 0);
+    return vec4<f32>(encode(spaces.dst, decode(spaces.src, texel.rgb)), texel.a);
+}
@@ -1,18 +1,24 @@
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
 
+use crate::color::ColorConverter;
 use crate::error::Error;
 use crate::execution_context::ExecutionState;
-use crate::gpu_pool::GPUResourcePool;
+use crate::gpu_pool::{GPUResourcePool, TextureOwner, TexturePoolStats};
 use crate::history::{Event, History, Message, Mutation};
+use crate::image_data::ImageData;
 use crate::node::{ConnectionProbe, Node, NodeId};
-use crate::ops::{self, Input, Output};
+use crate::ops::{self, Input, Output, USER_LIBRARY, UserShader};
 use crate::registry::consts::{CHECK, CHECK_DATA, FLECK, SPECK, TRANSPARENT_SPECK};
-use crate::traits::{Operation, OperationFactory, OperationFactoryEntry};
-use crate::value::TextureHandle;
-use crate::{ExecutionContext, SlotDef, Value, ValueMut};
+use crate::traits::{OpPath, Operation, OperationFactory, OperationFactoryEntry};
+use crate::value::{ColorSpace, TextureFormat, TextureHandle};
+use crate::{
+    ExecutionContext, ExtendedMetadata, PipelineCacheStats, SlotDef, StringKind, StringMeta, Value,
+    ValueMut,
+};
 use petgraph::prelude::*;
 use petgraph::visit::Topo;
-use wgpu::{Device, Queue, Texture};
+use wgpu::{Device, Queue, Texture, TextureView};
 
 #[derive(Debug, Clone)]
 pub struct Edge {
@@ -20,18 +26,21 @@ pub struct Edge {
     pub sink_slot: usize,
 }
 
-type OpRegistry = HashMap<&'static str, HashMap<&'static str, OperationFactoryEntry>>;
+type OpRegistry = HashMap<String, HashMap<String, OperationFactoryEntry>>;
 type MessageHandler = Box<dyn FnMut(Message) + Send>;
 
 /// Descriptor for initializing the engine
 pub struct EngineDescriptor {
     pub device: Device,
     pub queue: Queue,
-    //pub default_format: TextureFormat,
+    /// Format of textures that don't ask for a specific one, [TextureFormat::RGBAu8] if None
+    pub default_format: Option<TextureFormat>,
     pub on_message: Option<MessageHandler>,
 }
 
 /// The main entry point into the library
+///
+/// Shader nodes share compiled pipelines through `Rc<RefCell<_>>`, which makes the engine `!Send`.
 pub struct Engine {
     errors: HashMap<NodeIndex, Vec<Error>>,
     // The underlying graph model
@@ -46,11 +55,22 @@ pub struct Engine {
     on_message: Option<MessageHandler>,
     // The last issued NodeId
     last_id: NodeId,
+    // Directory user shader operators are loaded from and saved to
+    user_library: Option<PathBuf>,
 }
 
 // Initialization
 impl Engine {
     pub fn init(desc: EngineDescriptor) -> Result<Self, Error> {
+        let default_format = desc.default_format.unwrap_or(TextureFormat::RGBAu8);
+        if !desc
+            .device
+            .features()
+            .contains(default_format.to_wgpu().required_features())
+        {
+            return Err(Error::UnsupportedTextureFormat(default_format));
+        }
+
         let mut textures = GPUResourcePool::new();
 
         log::info!("loading initial textures");
@@ -59,6 +79,8 @@ impl Engine {
         textures.insert_texture(&desc.device, &desc.queue, TRANSPARENT_SPECK, &[0; 4]);
         textures.insert_texture(&desc.device, &desc.queue, CHECK, &CHECK_DATA);
 
+        let color = ColorConverter::new(&desc.device);
+
         let mut out = Self {
             graph: StableDiGraph::default(),
             registry: OpRegistry::default(),
@@ -68,26 +90,68 @@ impl Engine {
                 queue: desc.queue,
                 state: ExecutionState::default(),
                 textures,
+                owner: TextureOwner::Engine,
+                shader_include_paths: vec![],
+                pipelines: Default::default(),
+                async_compile: false,
+                texture_debug: false,
+                default_format,
+                converter: Default::default(),
+                color,
             },
             on_message: desc.on_message,
             last_id: NodeId(0),
             errors: HashMap::default(),
+            user_library: None,
         };
 
         log::info!("loading grafiek::core operators");
         out.register_op::<ops::Input>()?;
         out.register_op::<ops::Output>()?;
+        out.register_op::<ops::ImageFile>()?;
+        out.register_op::<ops::ImageSequence>()?;
+        out.register_op::<ops::FileOutput>()?;
+        out.register_op::<ops::Constant>()?;
         out.register_op::<ops::Arithmetic>()?;
+        out.register_op::<ops::Integer>()?;
+        out.register_op::<ops::Trig>()?;
+        out.register_op::<ops::Expression>()?;
+        out.register_op::<ops::Remap>()?;
+        out.register_op::<ops::Mix>()?;
+        out.register_op::<ops::Smoothstep>()?;
+        out.register_op::<ops::Step>()?;
+        out.register_op::<ops::Ease>()?;
+        out.register_op::<ops::Compare>()?;
+        out.register_op::<ops::Gate>()?;
+        out.register_op::<ops::Switch>()?;
+        out.register_op::<ops::Clock>()?;
+        out.register_op::<ops::Lfo>()?;
+        out.register_op::<ops::Accumulator>()?;
+        out.register_op::<ops::Counter>()?;
+        out.register_op::<ops::SampleHold>()?;
+        out.register_op::<ops::EdgeDetect>()?;
+        out.register_op::<ops::Smooth>()?;
+        out.register_op::<ops::RandomValue>()?;
+        out.register_op::<ops::ConvertColorSpace>()?;
         out.register_op::<ops::Grayscale>()?;
+        out.register_op::<ops::Custom>()?;
+        out.register_op::<ops::ValueNoise>()?;
+        out.register_op::<ops::PerlinNoise>()?;
+        out.register_op::<ops::SimplexNoise>()?;
+        out.register_op::<ops::WorleyNoise>()?;
+        out.register_op::<ops::Fbm>()?;
         Ok(out)
     }
 
     pub fn register_op<T: OperationFactory>(&mut self) -> Result<(), Error> {
-        let lib = self.registry.entry(T::LIBRARY).or_default();
+        let lib = self.registry.entry(T::LIBRARY.to_owned()).or_default();
         if lib.contains_key(T::OPERATOR) {
-            return Err(Error::DuplicateOperationType(T::LIBRARY, T::OPERATOR));
+            return Err(Error::DuplicateOperationType(
+                T::LIBRARY.to_owned(),
+                T::OPERATOR.to_owned(),
+            ));
         }
-        lib.insert(T::OPERATOR, OperationFactoryEntry::new::<T>());
+        lib.insert(T::OPERATOR.to_owned(), OperationFactoryEntry::new::<T>());
         Ok(())
     }
 
@@ -152,6 +216,7 @@ impl Engine {
         self.ctx.textures.release_node_textures(index);
 
         let node = self.graph.remove_node(index);
+        self.ctx.pipelines().trim();
 
         if let Some(node) = node {
             self.emit(Mutation::DeleteNode {
@@ -284,6 +349,9 @@ impl Engine {
             to_slot,
         });
 
+        // The target may have changed its types to follow the new connection
+        self.disconnect_invalid_edges(to);
+
         Ok(())
     }
 
@@ -542,6 +610,28 @@ impl Engine {
         self.ctx.timing()
     }
 
+    /// Set the seed random operators and shader inputs marked with
+    /// `#pragma engine_seed` draw from, the same seed and timing always give
+    /// the same render.
+    pub fn set_seed(&mut self, seed: u64) {
+        self.ctx.set_seed(seed);
+    }
+
+    pub fn seed(&self) -> u64 {
+        self.ctx.seed()
+    }
+
+    /// Reset every stateful node as if the graph had just been loaded, such
+    /// as when restarting an animation from its first frame.
+    pub fn reset(&mut self) {
+        for node in self.graph.node_weights_mut() {
+            if node.is_stateful() {
+                node.reset();
+            }
+        }
+        self.emit(Event::GraphDirtied);
+    }
+
     /// Get errors for a specific node, if any.
     pub fn node_errors(&self, index: NodeIndex) -> Option<&[Error]> {
         self.errors.get(&index).map(|v| v.as_slice())
@@ -574,41 +664,156 @@ impl Engine {
         });
     }
 
-    /// Execute the graph in topological order.
+    /// Reconfigure any node whose external resources changed, such as shader
+    /// files on disk. Hosts that only execute on [Event::GraphDirtied] should
+    /// call this once per frame, [Engine::execute] calls it as well.
+    ///
+    /// Returns true if any node was reconfigured.
+    pub fn poll(&mut self) -> bool {
+        let indices: Vec<_> = self.graph.node_indices().collect();
+        let changed: Vec<_> = indices
+            .into_iter()
+            .filter(|&idx| self.graph[idx].poll_reconfigure())
+            .collect();
+
+        for &index in &changed {
+            self.clear_node_errors(index);
+            if let Err(e) = self.reconfigure_node(index) {
+                self.push_node_error(index, e);
+            }
+            self.graph[index].set_dirty();
+        }
+
+        if !changed.is_empty() {
+            self.emit(Event::GraphDirtied);
+        }
+
+        !changed.is_empty()
+    }
+
+    /// Execute the graph, each node after the nodes it reads from.
     /// Each node's outputs are pushed to downstream nodes before they execute.
+    ///
+    /// Nodes that only feed inputs a conditional node doesn't select this
+    /// frame, see [Operation::selected_inputs], are skipped. Stateful nodes
+    /// are the exception, they run every frame wherever they sit so
+    /// accumulators and the like don't stall while their branch is untaken.
     pub fn execute(&mut self) {
+        self.poll();
+
         self.emit(Event::ExecutionStarted);
 
         // Note: We no longer clear errors here - errors are cleared when
         // a node is reconfigured (edit_node_config). This preserves compile
         // errors across execute() calls.
 
+        // Evaluation is pulled from the nodes nothing depends on, so nodes
+        // that only feed untaken branches of a conditional are skipped, then
+        // from any stateful node that was skipped
         let mut topo = Topo::new(&self.graph);
-        while let Some(node) = topo.next(&self.graph) {
-            if let Err(e) = self.graph[node].execute(&mut self.ctx) {
-                log::error!("Node execution failed: {e}");
-                self.push_node_error(node, e);
-            }
+        let order: Vec<_> = std::iter::from_fn(|| topo.next(&self.graph)).collect();
+        let sinks = order.iter().copied().filter(|&n| {
+            self.graph
+                .neighbors_directed(n, Direction::Outgoing)
+                .next()
+                .is_none()
+        });
+        let stateful = order
+            .iter()
+            .copied()
+            .filter(|&n| self.graph[n].is_stateful());
+        let roots: Vec<_> = sinks.chain(stateful).collect();
+
+        let mut executed = HashSet::new();
+        for root in roots {
+            self.evaluate(root, &mut executed);
+        }
 
-            self.emit(Event::NodeExecuted { node });
+        self.emit(Event::ExecutionCompleted);
+    }
 
-            let mut dependants = self
-                .graph
-                .neighbors_directed(node, Direction::Outgoing)
-                .detach();
+    /// Execute `root` after everything upstream of it that it reads this frame.
+    fn evaluate(&mut self, root: NodeIndex, executed: &mut HashSet<NodeIndex>) {
+        enum Step {
+            /// Evaluate the inputs that decide which other inputs are read
+            Conditions(NodeIndex),
+            /// Evaluate the inputs that will be read
+            Selected(NodeIndex),
+            Execute(NodeIndex),
+        }
 
-            while let Some((edge, dep)) = dependants.next(&self.graph) {
-                let edge = self.graph[edge].clone();
-                let value = self.graph[node]
-                    .output(edge.source_slot)
-                    .map(|(_, v)| v.clone());
-                if let Some(value) = value {
-                    self.graph[dep].push_incoming(edge.sink_slot, value, edge.source_slot);
+        // The stack runs each node's upstream before the node itself, without
+        // recursing on long chains
+        let mut stack = vec![Step::Conditions(root)];
+        while let Some(step) = stack.pop() {
+            match step {
+                Step::Conditions(node) if !executed.contains(&node) => {
+                    let conditions = self.graph[node].condition_inputs();
+                    stack.push(Step::Selected(node));
+                    stack.extend(
+                        self.upstream(node, |slot| conditions.contains(&slot))
+                            .into_iter()
+                            .map(Step::Conditions),
+                    );
+                }
+                Step::Selected(node) if !executed.contains(&node) => {
+                    let conditions = self.graph[node].condition_inputs();
+                    let selected = self.graph[node].selected_inputs();
+                    stack.push(Step::Execute(node));
+                    stack.extend(
+                        self.upstream(node, |slot| {
+                            !conditions.contains(&slot)
+                                && selected.as_ref().is_none_or(|s| s.contains(&slot))
+                        })
+                        .into_iter()
+                        .map(Step::Conditions),
+                    );
                 }
+                Step::Execute(node) if executed.insert(node) => self.execute_node(node),
+                _ => {}
             }
         }
+    }
 
-        self.emit(Event::ExecutionCompleted);
+    /// Nodes connected to the inputs of `node` picked by `slots`.
+    fn upstream(&self, node: NodeIndex, slots: impl Fn(usize) -> bool) -> Vec<NodeIndex> {
+        self.graph
+            .edges_directed(node, Direction::Incoming)
+            .filter(|e| slots(e.weight().sink_slot))
+            .map(|e| e.source())
+            .collect()
+    }
+
+    /// Execute a single node and hand its outputs to the nodes downstream.
+    fn execute_node(&mut self, node: NodeIndex) {
+        if self.ctx.texture_debug {
+            self.report_stale_textures(node);
+        }
+
+        self.ctx.owner = TextureOwner::Node(node);
+        let result = self.graph[node].execute(&mut self.ctx);
+        self.ctx.owner = TextureOwner::Engine;
+        if let Err(e) = result {
+            log::error!("Node execution failed: {e}");
+            self.push_node_error(node, e);
+        }
+
+        self.emit(Event::NodeExecuted { node });
+
+        let mut dependants = self
+            .graph
+            .neighbors_directed(node, Direction::Outgoing)
+            .detach();
+
+        while let Some((edge, dep)) = dependants.next(&self.graph) {
+            let edge = self.graph[edge].clone();
+            let value = self.graph[node]
+                .output(edge.source_slot)
+                .map(|(_, v)| v.clone());
+            if let Some(value) = value {
+                self.graph[dep].push_incoming(edge.sink_slot, value, edge.source_slot);
+            }
+        }
     }
 }
 
@@ -676,24 +881,182 @@ impl Engine {
 
 // Discovery
 impl Engine {
-    pub fn node_categories(&self) -> impl Iterator<Item = &'static str> + '_ {
-        self.registry.keys().copied()
+    pub fn node_categories(&self) -> impl Iterator<Item = &str> + '_ {
+        self.registry.keys().map(String::as_str)
     }
 
-    pub fn iter_category(&self, category: &str) -> impl Iterator<Item = &'static str> + '_ {
+    pub fn iter_category(&self, category: &str) -> impl Iterator<Item = &str> + '_ {
         self.registry
             .get(category)
             .into_iter()
-            .flat_map(|m| m.keys().copied())
+            .flat_map(|m| m.keys().map(String::as_str))
     }
 }
 
+// User library
+impl Engine {
+    /// Set the directory user shader operators are loaded from and saved to.
+    /// Every `.glsl` file in it is registered as `user/<file stem>`, replacing
+    /// the operators of the previous directory. A missing directory is treated
+    /// as empty and created on the first save.
+    ///
+    /// Returns the number of operators loaded. If the directory can't be read
+    /// the previous operators are left in place.
+    pub fn set_user_library(&mut self, dir: impl Into<PathBuf>) -> Result<usize, Error> {
+        let dir = dir.into();
+
+        // Read everything before touching the registry, so a failure part way
+        // through doesn't leave the library half replaced
+        let mut sources = Vec::new();
+        if dir.is_dir() {
+            for entry in std::fs::read_dir(&dir)? {
+                let path = entry?.path();
+                if path.extension().is_none_or(|ext| ext != "glsl") {
+                    continue;
+                }
+                let Some(name) = path.file_stem().and_then(|s| s.to_str()) else {
+                    continue;
+                };
+                if !is_valid_operator_name(name) {
+                    log::warn!("skipping user shader with invalid name: {path:?}");
+                    continue;
+                }
+                sources.push((name.to_owned(), std::fs::read_to_string(&path)?));
+            }
+        }
+
+        // Nodes already instanced from the old operators keep running
+        self.registry.remove(USER_LIBRARY);
+        let loaded = sources.len();
+        for (name, source) in sources {
+            self.register_user_shader(&name, source);
+        }
+
+        log::info!("loaded {loaded} user operators from {dir:?}");
+        self.user_library = Some(dir);
+        Ok(loaded)
+    }
+
+    /// The directory set by [Engine::set_user_library], if any.
+    pub fn user_library(&self) -> Option<&Path> {
+        self.user_library.as_deref()
+    }
+
+    /// Add a directory searched for shader `#include`s, after the built in
+    /// snippets and any previously added directories. Nodes pick it up the
+    /// next time they compile.
+    pub fn add_shader_include_path(&mut self, dir: impl Into<PathBuf>) {
+        self.ctx.shader_include_paths.push(dir.into());
+    }
+
+    /// Compile edited shaders on a worker thread instead of blocking the caller.
+    /// Nodes keep running their previous pipeline until the new one is ready,
+    /// see [Event::CompileStarted] and [Event::CompileFinished].
+    /// Ignored on wasm32, where shaders always compile in place.
+    pub fn set_async_compile(&mut self, enabled: bool) {
+        self.ctx.async_compile = enabled;
+    }
+
+    /// Hit and miss counts of the shader pipeline cache, for profiling.
+    pub fn pipeline_stats(&self) -> PipelineCacheStats {
+        self.ctx.pipeline_stats()
+    }
+
+    /// Save the current source of a shader node into the user library as a new
+    /// operator named `name`, overwriting any previous operator of that name.
+    /// Nodes reading their source from a file save the file's contents.
+    /// The operator is registered immediately and can be instanced by the returned path.
+    pub fn save_shader_operator(&mut self, index: NodeIndex, name: &str) -> Result<OpPath, Error> {
+        if !is_valid_operator_name(name) {
+            return Err(Error::InvalidOperatorName(name.to_owned()));
+        }
+
+        let dir = self.user_library.clone().ok_or(Error::NoUserLibrary)?;
+
+        let node = self
+            .graph
+            .node_weight(index)
+            .ok_or(Error::NodeNotFound(format!("Node not found: {index:?}")))?;
+
+        let source = node
+            .configs()
+            .find_map(|(def, value)| match (def.extended(), value) {
+                (
+                    ExtendedMetadata::String(StringMeta {
+                        kind: StringKind::Glsl,
+                        ..
+                    }),
+                    Value::String(source),
+                ) => Some(source.clone()),
+                _ => None,
+            })
+            .ok_or(Error::NotShaderNode)?;
+
+        // A node reading its source from a file runs that file, not its `source` config
+        let file = node.configs().find_map(|(def, value)| match value {
+            Value::String(path) if def.name() == "file" && !path.is_empty() => {
+                Some(PathBuf::from(path))
+            }
+            _ => None,
+        });
+        let source = match file {
+            Some(path) => {
+                std::fs::read_to_string(&path).map_err(|source| Error::ReadFile { path, source })?
+            }
+            None => source,
+        };
+
+        std::fs::create_dir_all(&dir)?;
+        std::fs::write(dir.join(format!("{name}.glsl")), &source)?;
+
+        self.register_user_shader(name, source);
+
+        Ok(OpPath {
+            library: USER_LIBRARY.to_owned(),
+            operator: name.to_owned(),
+        })
+    }
+
+    fn register_user_shader(&mut self, name: &str, source: String) {
+        let op_name = name.to_owned();
+        let entry = OperationFactoryEntry::from_fn(move || {
+            Ok(Box::new(UserShader::new(op_name.clone(), source.clone())))
+        });
+
+        self.registry
+            .entry(USER_LIBRARY.to_owned())
+            .or_default()
+            .insert(name.to_owned(), entry);
+    }
+}
+
+/// Operator names double as file names in the user library.
+fn is_valid_operator_name(name: &str) -> bool {
+    !name.is_empty()
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-')
+}
+
 // Validation
 impl Engine {
     /// Reconfigure a node and disconnect any edges invalidated by the new signature.
     fn reconfigure_node(&mut self, index: NodeIndex) -> Result<(), Error> {
         let old_outputs = self.graph[index].snapshot_outputs();
-        self.graph[index].configure(&self.ctx)?;
+        let was_compiling = self.graph[index].is_compiling();
+        let result = self.graph[index].configure(&self.ctx);
+        // Drop pipelines orphaned by a source change
+        self.ctx.pipelines().trim();
+
+        match (was_compiling, self.graph[index].is_compiling()) {
+            (false, true) => self.emit(Event::CompileStarted { node: index }),
+            (true, false) => self.emit(Event::CompileFinished {
+                node: index,
+                success: result.is_ok(),
+            }),
+            _ => {}
+        }
+        result?;
         self.disconnect_invalid_edges(index);
         self.sync_output_textures(index, &old_outputs);
         Ok(())
@@ -703,7 +1066,8 @@ impl Engine {
     fn disconnect_invalid_edges(&mut self, index: NodeIndex) {
         let edges: Vec<_> = self
             .graph
-            .edges(index)
+            .edges_directed(index, Direction::Outgoing)
+            .chain(self.graph.edges_directed(index, Direction::Incoming))
             .map(|e| (e.id(), e.source(), e.target(), e.weight().clone()))
             .collect();
 
@@ -717,6 +1081,20 @@ impl Engine {
             if !is_valid {
                 self.graph.remove_edge(edge_id);
                 self.graph[to].clear_incoming(weight.sink_slot);
+
+                let connected_type = self.graph[from]
+                    .signature()
+                    .output(weight.source_slot)
+                    .map(|s| s.value_type)
+                    .unwrap_or(crate::ValueType::Any);
+                let old_outputs = self.graph[to].snapshot_outputs();
+                if let Err(e) =
+                    self.graph[to].on_edge_disconnected(weight.sink_slot, connected_type)
+                {
+                    log::error!("on_edge_disconnected failed: {e}");
+                }
+                self.sync_output_textures(to, &old_outputs);
+
                 self.emit(Mutation::Disconnect {
                     from_node: from,
                     from_slot: weight.source_slot,
@@ -730,12 +1108,86 @@ impl Engine {
 
 // Textures
 impl Engine {
+    /// Format of textures that don't ask for a specific one, see [EngineDescriptor::default_format].
+    pub fn default_format(&self) -> TextureFormat {
+        self.ctx.default_format()
+    }
+
+    /// Check the texture handles every node receives before it executes,
+    /// emitting [Event::StaleTexture] for any that outlived a resize.
+    pub fn set_texture_debug(&mut self, enabled: bool) {
+        self.ctx.texture_debug = enabled;
+    }
+
+    fn report_stale_textures(&mut self, index: NodeIndex) {
+        let stale: Vec<_> = self.graph[index]
+            .inputs()
+            .enumerate()
+            .filter_map(|(slot, (_, value))| match value {
+                Value::Texture(handle) => Some((slot, handle.id?)),
+                _ => None,
+            })
+            .filter(|(_, id)| {
+                matches!(
+                    self.ctx.textures.try_get_texture(*id),
+                    Err(Error::StaleTexture { .. })
+                )
+            })
+            .collect();
+
+        for (slot, id) in stale {
+            let label = self.graph[index].label().to_owned();
+            log::warn!(
+                "Node {label} ({index:?}) holds a stale handle to texture {id:?} on input {slot}"
+            );
+            self.emit(Event::StaleTexture {
+                node: index,
+                slot,
+                id,
+            });
+        }
+    }
+
+    /// Cap the bytes of texture memory kept around for reuse, None for no limit.
+    /// Released textures are evicted least recently released first once the
+    /// pool, including textures in use, holds more than this.
+    pub fn set_texture_budget(&mut self, bytes: Option<u64>) {
+        self.ctx.textures.set_budget(bytes);
+    }
+
+    /// Texture memory in use and on the free list, broken down by owner.
+    pub fn texture_stats(&self) -> TexturePoolStats {
+        self.ctx.textures.stats()
+    }
+
     /// Get the GPU texture for a handle.
     pub fn get_texture(&self, handle: &TextureHandle) -> Option<&Texture> {
         self.ctx.textures.get_texture(handle.id?)
     }
 
-    /// Upload pixel data to a texture output slot. Updates handle dimensions and allocates GPU texture.
+    /// Like [Engine::get_texture] but reports why a texture is missing,
+    /// [Error::StaleTexture] points at a handle that missed a resize.
+    pub fn try_get_texture(&self, handle: &TextureHandle) -> Result<&Texture, Error> {
+        self.ctx.try_texture(handle)
+    }
+
+    /// A view of the texture that yields linear light when sampled, for display.
+    /// sRGB encoded 8 bit textures are viewed through their `Srgb` format, other
+    /// formats are viewed as stored.
+    pub fn display_view(&self, handle: &TextureHandle) -> Option<TextureView> {
+        let texture = self.get_texture(handle)?;
+        let format = match handle.color_space.is_srgb_encoded() {
+            true => texture.format().add_srgb_suffix(),
+            false => texture.format(),
+        };
+        Some(texture.create_view(&wgpu::TextureViewDescriptor {
+            format: Some(format),
+            ..Default::default()
+        }))
+    }
+
+    /// Upload 8 bit RGBA pixel data to a texture output slot. Updates handle dimensions and allocates GPU texture.
+    /// The data is taken as [ColorSpace::Linear], see [Engine::upload_texture_in] for other encodings.
     pub fn upload_texture(
         &mut self,
         index: NodeIndex,
@@ -743,6 +1195,37 @@ impl Engine {
         width: u32,
         height: u32,
         data: &[u8],
+    ) -> Result<(), Error> {
+        self.upload_texture_in(index, slot, width, height, ColorSpace::Linear, data)
+    }
+
+    /// [Engine::upload_texture] with `color_space` tagging how the data is encoded,
+    /// pixels from image files are usually [ColorSpace::Srgb].
+    pub fn upload_texture_in(
+        &mut self,
+        index: NodeIndex,
+        slot: usize,
+        width: u32,
+        height: u32,
+        color_space: ColorSpace,
+        data: &[u8],
+    ) -> Result<(), Error> {
+        let image = ImageData::new(
+            width,
+            height,
+            TextureFormat::RGBAu8,
+            color_space,
+            data.to_vec(),
+        )?;
+        self.upload_image(index, slot, &image)
+    }
+
+    /// Upload a decoded image to a texture output slot, in the texture format matching its bit depth.
+    pub fn upload_image(
+        &mut self,
+        index: NodeIndex,
+        slot: usize,
+        image: &ImageData,
     ) -> Result<(), Error> {
         let node = self
             .graph
@@ -758,22 +1241,10 @@ impl Engine {
             )));
         };
 
-        if let Some(old_id) = handle.id {
-            self.ctx.textures.release_texture(old_id);
-        }
-
-        handle.width = width;
-        handle.height = height;
-
-        let id = self.ctx.textures.alloc_texture_with_data(
-            &self.ctx.device,
-            &self.ctx.queue,
-            index,
-            handle,
-            data,
-        );
-
-        handle.id = Some(id);
+        self.ctx.owner = TextureOwner::Node(index);
+        let uploaded = self.ctx.upload_image(handle, image);
+        self.ctx.owner = TextureOwner::Engine;
+        uploaded?;
 
         self.emit(Event::GraphDirtied);
         Ok(())
@@ -782,6 +1253,7 @@ impl Engine {
     /// Sync texture allocations after configure. Preserves IDs where possible.
     fn sync_output_textures(&mut self, index: NodeIndex, old_outputs: &[Value]) {
         let new_len = self.graph[index].output_values_mut().len();
+        self.ctx.owner = TextureOwner::Node(index);
 
         for (slot, output) in self.graph[index].output_values_mut().iter_mut().enumerate() {
             let Value::Texture(handle) = output else {
@@ -795,6 +1267,7 @@ impl Engine {
             }
             self.ctx.ensure_texture(handle);
         }
+        self.ctx.owner = TextureOwner::Engine;
 
         // Release orphaned textures from removed slots
         for old in old_outputs.iter().skip(new_len) {
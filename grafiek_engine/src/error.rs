@@ -5,12 +5,17 @@ pub type Result<T> = std::result::Result<T, Error>;
 #[derive(Debug, Clone)]
 pub struct LocatedError {
     pub message: String,
+    /// The included file the error is in, None for the script itself
+    pub file: Option<String>,
     pub line: u32,
     pub column: u32,
 }
 
 impl std::fmt::Display for LocatedError {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if let Some(file) = &self.file {
+            write!(f, "{file}:")?;
+        }
         write!(f, "{}:{}: {}", self.line, self.column, self.message)
     }
 }
@@ -25,6 +30,7 @@ impl ScriptError {
         Self {
             errors: vec![LocatedError {
                 message: message.into(),
+                file: None,
                 line: 0,
                 column: 0,
             }],
@@ -53,6 +59,7 @@ impl ScriptError {
                     .into_iter()
                     .map(|e| LocatedError {
                         message: format!("{:?}", e.kind),
+                        file: None,
                         line: e.location.line,
                         column: e.location.column,
                     })
@@ -69,11 +76,55 @@ pub enum Error {
     #[error("Unknown operation type: {0}")]
     UnknownOperationType(String),
 
+    #[error("Texture format {0:?} is not supported by this device.")]
+    UnsupportedTextureFormat(crate::TextureFormat),
+
+    #[error("Texture has not been allocated yet.")]
+    TextureNotAllocated,
+
+    #[error("Texture {0:?} has been released.")]
+    TextureNotFound(crate::TextureId),
+
+    #[error(
+        "Stale handle to texture {}, generation {} but the texture is on generation {current}.",
+        id.stable_id,
+        id.generation
+    )]
+    StaleTexture { id: crate::TextureId, current: u64 },
+
+    #[error("Texture data is {actual} bytes but {expected} were expected.")]
+    TextureDataSize { expected: usize, actual: usize },
+
+    #[error("Invalid image layout: {width}x{height} with {bytes_per_row} bytes per row.")]
+    InvalidImageLayout {
+        width: u32,
+        height: u32,
+        bytes_per_row: u32,
+    },
+
+    #[error("Failed to decode image: {0}")]
+    DecodeImage(String),
+
+    #[error("Failed to encode image: {0}")]
+    EncodeImage(String),
+
+    #[error("Failed to read texture back from the GPU: {0}")]
+    TextureReadback(String),
+
+    #[error("Frame pattern {0:?} has no run of # for the frame number.")]
+    InvalidFramePattern(String),
+
+    #[error("No files match the frame pattern {0:?}.")]
+    NoMatchingFrames(String),
+
+    #[error("Can't write a {value} value as {format}.")]
+    CannotWrite { value: String, format: String },
+
     #[error("Node was configured with two slots named {0} on its {1}.")]
     DuplicateSlotName(String, String),
 
     #[error("Duplicate operation type: {0}/{1}")]
-    DuplicateOperationType(&'static str, &'static str),
+    DuplicateOperationType(String, String),
 
     #[error("Node not found: {0}")]
     NodeNotFound(String),
@@ -111,6 +162,12 @@ pub enum Error {
     #[error("IO error: {0}")]
     Io(#[from] std::io::Error),
 
+    #[error("Failed to read {path:?}: {source}")]
+    ReadFile {
+        path: std::path::PathBuf,
+        source: std::io::Error,
+    },
+
     #[error("Value error: {0}")]
     Value(#[from] crate::value::ValueError),
 
@@ -119,6 +176,18 @@ pub enum Error {
 
     #[error("{0}")]
     Script(ScriptError),
+
+    #[error("No user library directory has been set")]
+    NoUserLibrary,
+
+    #[error("Invalid operator name: {0:?}")]
+    InvalidOperatorName(String),
+
+    #[error("Node has no shader source to save")]
+    NotShaderNode,
+
+    #[error("Integer division by zero")]
+    DivisionByZero,
 }
 
 impl Error {
@@ -1,8 +1,17 @@
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::path::PathBuf;
+
+use wgpu::util::{TextureBlitter, TextureBlitterBuilder};
 use wgpu::{Device, Queue, Texture};
 
 use crate::{
-    TextureHandle,
-    gpu_pool::{GPUResourcePool, create_gpu_texture_empty},
+    Rng, TextureFormat, TextureHandle,
+    color::ColorConverter,
+    error::Error,
+    gpu_pool::{GPUResourcePool, TextureOwner, mip_view},
+    image_data::ImageData,
+    pipeline_cache::{PipelineCache, PipelineCacheStats},
 };
 
 /// Timing information for graph execution, set by the application.
@@ -19,6 +28,22 @@ pub struct TimeInfo {
 #[derive(Debug, Default)]
 pub struct ExecutionState {
     pub timing: TimeInfo,
+    /// Seeds every random number drawn while executing, set by the application
+    pub seed: u64,
+}
+
+/// Render passes converting textures into each format, built on first use.
+#[derive(Default)]
+pub(crate) struct FormatConverter {
+    blitters: HashMap<TextureFormat, TextureBlitter>,
+    /// Filtering blitters for downsampling mip levels
+    downsamplers: HashMap<TextureFormat, TextureBlitter>,
+}
+
+impl std::fmt::Debug for FormatConverter {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_list().entries(self.blitters.keys()).finish()
+    }
 }
 
 #[derive(Debug)]
@@ -26,7 +51,22 @@ pub struct ExecutionContext {
     pub device: Device,
     pub queue: Queue,
     pub(crate) textures: GPUResourcePool,
+    /// Charged for textures allocated through [ExecutionContext::ensure_texture],
+    /// the node being configured or executed
+    pub(crate) owner: TextureOwner,
     pub(crate) state: ExecutionState,
+    /// Directories searched for shader `#include`s after the built in snippets
+    pub(crate) shader_include_paths: Vec<PathBuf>,
+    /// Behind a RefCell as shaders compile from [crate::traits::Operation::configure]
+    pub(crate) pipelines: RefCell<PipelineCache>,
+    /// Compile shaders on a worker thread when a node already has a pipeline
+    pub(crate) async_compile: bool,
+    /// Check every texture handle a node receives before it executes
+    pub(crate) texture_debug: bool,
+    /// Format of textures that don't ask for a specific one
+    pub(crate) default_format: TextureFormat,
+    pub(crate) converter: FormatConverter,
+    pub(crate) color: ColorConverter,
 }
 
 impl ExecutionContext {
@@ -34,6 +74,13 @@ impl ExecutionContext {
         self.textures.get_texture(handle.id?)
     }
 
+    /// Like [ExecutionContext::texture] but reports why a texture is missing,
+    /// [Error::StaleTexture] points at a handle that missed a resize.
+    pub fn try_texture(&self, handle: &TextureHandle) -> Result<&Texture, Error> {
+        let id = handle.id.ok_or(Error::TextureNotAllocated)?;
+        self.textures.try_get_texture(id)
+    }
+
     pub fn time(&self) -> f32 {
         self.state.timing.time
     }
@@ -42,27 +89,265 @@ impl ExecutionContext {
         &self.state.timing
     }
 
+    pub fn seed(&self) -> u64 {
+        self.state.seed
+    }
+
+    /// A generator for `key` under the engine's seed. Operations ask for one
+    /// each execution rather than sharing a generator, so their values don't
+    /// depend on which other nodes ran first.
+    pub fn rng(&self, key: u64) -> Rng {
+        Rng::new(self.state.seed).derive(key)
+    }
+
+    pub fn shader_include_paths(&self) -> &[PathBuf] {
+        &self.shader_include_paths
+    }
+
+    pub(crate) fn pipelines(&self) -> std::cell::RefMut<'_, PipelineCache> {
+        self.pipelines.borrow_mut()
+    }
+
+    /// Always false on wasm32, which has no threads to compile on.
+    pub fn async_compile(&self) -> bool {
+        self.async_compile && cfg!(not(target_arch = "wasm32"))
+    }
+
+    pub fn pipeline_stats(&self) -> PipelineCacheStats {
+        self.pipelines.borrow().stats()
+    }
+
+    pub fn default_format(&self) -> TextureFormat {
+        self.default_format
+    }
+
+    /// True if the device has the features needed to create textures of this format.
+    pub fn supports_format(&self, fmt: TextureFormat) -> bool {
+        self.device
+            .features()
+            .contains(fmt.to_wgpu().required_features())
+    }
+
     pub(crate) fn set_timing(&mut self, timing: TimeInfo) {
         self.state.timing = timing;
     }
 
-    /// Ensure the texture exists with the correct dimensions, replacing in-place if needed.
+    pub(crate) fn set_seed(&mut self, seed: u64) {
+        self.state.seed = seed;
+    }
+
+    /// Ensure the texture exists with the correct dimensions and format, replacing in-place if needed.
     /// This is intended for render targets that are about to be overwritten anyways, it zeros them.
+    /// Replaced textures go back to the pool for reuse.
     pub fn ensure_texture(&mut self, handle: &mut TextureHandle) {
         match handle.id {
             None => {
-                handle.id = self.textures.alloc_texture(&self.device, handle).into();
+                let id = self
+                    .textures
+                    .alloc_texture(&self.device, &self.queue, self.owner, handle);
+                handle.id = Some(id);
             }
             Some(id) => {
-                let needs_resize = self.textures.get_texture(id).map_or(false, |tex| {
-                    let size = tex.size();
-                    size.width != handle.width || size.height != handle.height
-                });
-                if needs_resize {
-                    let texture = create_gpu_texture_empty(&self.device, handle);
-                    handle.id = self.textures.replace_texture(id, texture).into();
+                let needs_realloc = match self.textures.try_get_texture(id) {
+                    Ok(tex) => {
+                        let size = tex.size();
+                        size.width != handle.width
+                            || size.height != handle.height
+                            || tex.format() != handle.fmt.to_wgpu()
+                            || tex.mip_level_count() != handle.mip_level_count()
+                    }
+                    // Catch the handle up with whatever replaced the texture
+                    Err(Error::StaleTexture { .. }) => true,
+                    Err(_) => false,
+                };
+                if needs_realloc {
+                    let id = self
+                        .textures
+                        .realloc_texture(&self.device, &self.queue, id, handle);
+                    handle.id = Some(id);
                 }
             }
         }
     }
+
+    /// Render `src` into `dst`, reallocating `dst` to match the size of `src`
+    /// and converting to the format and colour space of `dst` on the way.
+    /// Only the first mip level of `dst` is written, see [ExecutionContext::generate_mipmaps].
+    pub fn convert_texture(&mut self, src: &TextureHandle, dst: &mut TextureHandle) {
+        dst.width = src.width;
+        dst.height = src.height;
+        self.ensure_texture(dst);
+
+        let texture = |h: &TextureHandle| self.textures.get_texture(h.id?);
+        let (Some(src_tex), Some(dst_tex)) = (texture(src), texture(dst)) else {
+            return;
+        };
+
+        let mut encoder = self.device.create_command_encoder(&Default::default());
+        let src_view = mip_view(src_tex, 0);
+        if src.color_space == dst.color_space {
+            let blitter = self
+                .converter
+                .blitters
+                .entry(dst.fmt)
+                .or_insert_with(|| TextureBlitter::new(&self.device, dst.fmt.to_wgpu()));
+            blitter.copy(&self.device, &mut encoder, &src_view, &mip_view(dst_tex, 0));
+        } else {
+            self.color.convert(
+                &self.device,
+                &mut encoder,
+                &src_view,
+                dst_tex,
+                (src.color_space, dst.color_space),
+            );
+        }
+        self.queue.submit(Some(encoder.finish()));
+    }
+
+    /// Fill every mip level of `handle` past the first by downsampling the
+    /// level above it. Does nothing for textures without mipmaps.
+    pub fn generate_mipmaps(&mut self, handle: &TextureHandle) {
+        let Some(texture) = handle.id.and_then(|id| self.textures.get_texture(id)) else {
+            return;
+        };
+        if texture.mip_level_count() < 2 {
+            return;
+        }
+
+        let format = handle.fmt.to_wgpu();
+        // Formats that can't be filtered fall back to point sampling each level
+        let filter = match format
+            .guaranteed_format_features(self.device.features())
+            .flags
+            .contains(wgpu::TextureFormatFeatureFlags::FILTERABLE)
+        {
+            true => wgpu::FilterMode::Linear,
+            false => wgpu::FilterMode::Nearest,
+        };
+        let blitter = self
+            .converter
+            .downsamplers
+            .entry(handle.fmt)
+            .or_insert_with(|| {
+                TextureBlitterBuilder::new(&self.device, format)
+                    .sample_type(filter)
+                    .build()
+            });
+
+        let mut encoder = self.device.create_command_encoder(&Default::default());
+        for level in 1..texture.mip_level_count() {
+            blitter.copy(
+                &self.device,
+                &mut encoder,
+                &mip_view(texture, level - 1),
+                &mip_view(texture, level),
+            );
+        }
+        self.queue.submit(Some(encoder.finish()));
+    }
+
+    /// Replace the texture behind `handle` with `image`, taking on its size,
+    /// format and colour space. 16 bit images are widened to float on devices
+    /// without 16 bit normalized textures.
+    pub fn upload_image(
+        &mut self,
+        handle: &mut TextureHandle,
+        image: &ImageData,
+    ) -> Result<(), Error> {
+        let widened;
+        let image = match self.supports_format(image.format()) {
+            true => image,
+            false => {
+                widened = image.to_float()?;
+                &widened
+            }
+        };
+
+        if let Some(old_id) = handle.id.take() {
+            self.textures.release_texture(old_id);
+        }
+
+        handle.width = image.width();
+        handle.height = image.height();
+        handle.fmt = image.format();
+        handle.color_space = image.color_space();
+        handle.mipmaps = false;
+
+        let id = self.textures.alloc_texture_with_data(
+            &self.device,
+            &self.queue,
+            self.owner,
+            handle,
+            image.bytes_per_row(),
+            image.data(),
+        );
+        handle.id = Some(id);
+        Ok(())
+    }
+
+    /// Copy a texture back to the CPU, blocking until the GPU is done with it.
+    /// Only textures rendered by nodes can be read, convert others first.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn read_texture(&self, handle: &TextureHandle) -> Result<ImageData, Error> {
+        let texture = self.try_texture(handle)?;
+        let size = texture.size();
+        let bytes_per_row = (size.width * handle.fmt.bytes_per_pixel())
+            .next_multiple_of(wgpu::COPY_BYTES_PER_ROW_ALIGNMENT);
+
+        let buffer = self.device.create_buffer(&wgpu::BufferDescriptor {
+            label: None,
+            size: bytes_per_row as u64 * size.height as u64,
+            usage: wgpu::BufferUsages::MAP_READ | wgpu::BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        });
+
+        let mut encoder = self.device.create_command_encoder(&Default::default());
+        encoder.copy_texture_to_buffer(
+            texture.as_image_copy(),
+            wgpu::TexelCopyBufferInfo {
+                buffer: &buffer,
+                layout: wgpu::TexelCopyBufferLayout {
+                    offset: 0,
+                    bytes_per_row: Some(bytes_per_row),
+                    rows_per_image: Some(size.height),
+                },
+            },
+            wgpu::Extent3d {
+                depth_or_array_layers: 1,
+                ..size
+            },
+        );
+        self.queue.submit(Some(encoder.finish()));
+
+        let (tx, rx) = std::sync::mpsc::channel();
+        buffer
+            .slice(..)
+            .map_async(wgpu::MapMode::Read, move |result| {
+                let _ = tx.send(result);
+            });
+        self.device
+            .poll(wgpu::PollType::wait_indefinitely())
+            .map_err(|e| Error::TextureReadback(e.to_string()))?;
+        rx.recv()
+            .map_err(|e| Error::TextureReadback(e.to_string()))?
+            .map_err(|e| Error::TextureReadback(e.to_string()))?;
+
+        let data = buffer.slice(..).get_mapped_range().to_vec();
+        ImageData::with_row_stride(
+            size.width,
+            size.height,
+            handle.fmt,
+            handle.color_space,
+            bytes_per_row,
+            data,
+        )
+    }
+
+    /// Readback has to block on the GPU, which the browser doesn't allow.
+    #[cfg(target_arch = "wasm32")]
+    pub fn read_texture(&self, _handle: &TextureHandle) -> Result<ImageData, Error> {
+        Err(Error::TextureReadback(
+            "textures can't be read back on wasm32".into(),
+        ))
+    }
 }
@@ -0,0 +1,38 @@
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+/// Watches a single file by polling its modification time.
+/// Polling keeps this usable on every platform the engine runs on, and the
+/// engine only asks once per [crate::Engine::poll].
+#[derive(Debug, Clone)]
+pub struct FileWatch {
+    path: PathBuf,
+    modified: Option<SystemTime>,
+}
+
+impl FileWatch {
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        let path = path.into();
+        let modified = modified_time(&path);
+        Self { path, modified }
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Returns true if the file was modified, created or removed since the
+    /// last call.
+    pub fn changed(&mut self) -> bool {
+        let modified = modified_time(&self.path);
+        if modified == self.modified {
+            return false;
+        }
+        self.modified = modified;
+        true
+    }
+}
+
+fn modified_time(path: &Path) -> Option<SystemTime> {
+    std::fs::metadata(path).and_then(|m| m.modified()).ok()
+}
@@ -4,6 +4,7 @@ use petgraph::graph::NodeIndex;
 use serde::{Deserialize, Serialize};
 use wgpu::{Device, Queue, Texture, TextureDescriptor, TextureUsages};
 
+use crate::error::Error;
 use crate::registry::consts::SYSTEM_TEXTURE_COUNT;
 use crate::value::{TextureFormat, TextureHandle};
 
@@ -23,30 +24,108 @@ impl TextureId {
     }
 }
 
+/// Who a texture is charged to in [TexturePoolStats::bytes_per_owner].
+/// Textures owned by a node are released when it is deleted.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub enum TextureOwner {
     Engine,
     Node(NodeIndex),
 }
 
+/// Textures are only recycled into allocations with the same key.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+struct TextureKey {
+    width: u32,
+    height: u32,
+    format: TextureFormat,
+    usage: TextureUsages,
+    mip_levels: u32,
+}
+
+impl TextureKey {
+    fn new(handle: &TextureHandle, usage: TextureUsages) -> Self {
+        Self {
+            width: handle.width,
+            height: handle.height,
+            format: handle.fmt,
+            usage,
+            mip_levels: handle.mip_level_count(),
+        }
+    }
+
+    fn bytes(&self) -> u64 {
+        (0..self.mip_levels)
+            .map(|level| {
+                let width = (self.width >> level).max(1) as u64;
+                let height = (self.height >> level).max(1) as u64;
+                width * height * self.format.bytes_per_pixel() as u64
+            })
+            .sum()
+    }
+}
+
 #[derive(Debug)]
 struct TextureEntry {
     texture: Texture,
     owner: TextureOwner,
+    key: TextureKey,
+    /// Bumped every time the texture is replaced, handles with an older one are stale
+    generation: u64,
 }
 
-/// Manages GPU textures and their ownership.
+/// A released texture waiting to be reused.
+#[derive(Debug)]
+struct FreeTexture {
+    texture: Texture,
+    /// Value of [GPUResourcePool::clock] when released, the oldest is evicted first
+    released_at: u64,
+}
+
+/// Counters for seeing where texture memory goes. Sizes ignore driver padding.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct TexturePoolStats {
+    /// Textures currently in use
+    pub live_textures: usize,
+    pub live_bytes: u64,
+    /// Released textures kept for reuse
+    pub free_textures: usize,
+    pub free_bytes: u64,
+    /// Highest live plus free bytes held at once
+    pub peak_bytes: u64,
+    /// Allocations answered from the free list
+    pub recycled: u64,
+    /// Allocations that created a new texture
+    pub created: u64,
+    /// Free textures dropped to stay within the budget
+    pub evicted: u64,
+    /// Live bytes charged to each owner
+    pub bytes_per_owner: HashMap<TextureOwner, u64>,
+}
+
+/// Manages GPU textures and their ownership. Released textures go on a free
+/// list and are handed back out to allocations of the same size, format and
+/// usage. With a budget set the least recently released free textures are
+/// dropped whenever the pool holds more than the budget.
 #[derive(Debug, Default)]
 pub struct GPUResourcePool {
     textures: HashMap<u64, TextureEntry>,
+    free: HashMap<TextureKey, Vec<FreeTexture>>,
     next_id: u64,
+    clock: u64,
+    budget: Option<u64>,
+    live_bytes: u64,
+    free_bytes: u64,
+    peak_bytes: u64,
+    recycled: u64,
+    created: u64,
+    evicted: u64,
 }
 
 impl GPUResourcePool {
     pub fn new() -> Self {
         Self {
-            textures: HashMap::new(),
             next_id: SYSTEM_TEXTURE_COUNT,
+            ..Default::default()
         }
     }
 
@@ -68,26 +147,24 @@ impl GPUResourcePool {
         data: &[u8],
     ) {
         let id = handle.id.expect("system texture must have predefined ID");
-        let texture = create_gpu_texture(device, queue, &handle, data);
-        self.textures.insert(
-            id.stable_id,
-            TextureEntry {
-                texture,
-                owner: TextureOwner::Engine,
-            },
-        );
+        let key = TextureKey::new(&handle, UPLOAD_USAGES);
+        let texture = self.acquire(device, queue, key, false);
+        let bytes_per_row = handle.width * handle.fmt.bytes_per_pixel();
+        write_texture(queue, &texture, bytes_per_row, data);
+        self.insert(id, texture, TextureOwner::Engine, key);
     }
 
-    pub(crate) fn alloc_texture(&mut self, device: &Device, handle: &TextureHandle) -> TextureId {
+    pub(crate) fn alloc_texture(
+        &mut self,
+        device: &Device,
+        queue: &Queue,
+        owner: TextureOwner,
+        handle: &TextureHandle,
+    ) -> TextureId {
         let id = self.next_id();
-        let texture = create_gpu_texture_empty(device, handle);
-        self.textures.insert(
-            id.stable_id,
-            TextureEntry {
-                texture,
-                owner: TextureOwner::Engine,
-            },
-        );
+        let key = TextureKey::new(handle, render_target_usages(device, handle.fmt));
+        let texture = self.acquire(device, queue, key, true);
+        self.insert(id, texture, owner, key);
         id
     }
 
@@ -95,87 +172,234 @@ impl GPUResourcePool {
         &mut self,
         device: &Device,
         queue: &Queue,
-        owner: NodeIndex,
+        owner: TextureOwner,
         handle: &TextureHandle,
+        bytes_per_row: u32,
         data: &[u8],
     ) -> TextureId {
         let id = self.next_id();
-        let texture = create_gpu_texture(device, queue, handle, data);
-        self.textures.insert(
-            id.stable_id,
-            TextureEntry {
-                texture,
-                owner: TextureOwner::Node(owner),
-            },
-        );
+        let key = TextureKey::new(handle, UPLOAD_USAGES);
+        let texture = self.acquire(device, queue, key, false);
+        write_texture(queue, &texture, bytes_per_row, data);
+        self.insert(id, texture, owner, key);
         id
     }
 
+    /// Look up a texture, None if it was released or the handle is stale.
     pub fn get_texture(&self, id: TextureId) -> Option<&Texture> {
-        self.textures.get(&id.stable_id).map(|e| &e.texture)
+        self.try_get_texture(id).ok()
     }
 
-    // Returns textureId with the generation incremneted
-    pub fn replace_texture(&mut self, id: TextureId, texture: Texture) -> TextureId {
-        if let Some(entry) = self.textures.get_mut(&id.stable_id) {
-            entry.texture = texture;
+    /// Look up a texture, telling a released texture apart from a stale handle
+    /// to one that has since been resized or replaced.
+    pub fn try_get_texture(&self, id: TextureId) -> Result<&Texture, Error> {
+        let entry = self
+            .textures
+            .get(&id.stable_id)
+            .ok_or(Error::TextureNotFound(id))?;
+        if entry.generation != id.generation {
+            return Err(Error::StaleTexture {
+                id,
+                current: entry.generation,
+            });
         }
-        let mut id = id;
-        id.generation += 1;
+        Ok(&entry.texture)
+    }
+
+    /// Swap the texture behind `id` for one matching `handle`, recycling the
+    /// old one. Returns the ID with its generation incremented.
+    pub(crate) fn realloc_texture(
+        &mut self,
+        device: &Device,
+        queue: &Queue,
+        id: TextureId,
+        handle: &TextureHandle,
+    ) -> TextureId {
+        let key = TextureKey::new(handle, render_target_usages(device, handle.fmt));
+        let texture = self.acquire(device, queue, key, true);
+        let (owner, generation) = match self.take(id) {
+            Some(entry) => {
+                let current = (entry.owner, entry.generation);
+                self.recycle(entry);
+                current
+            }
+            None => (TextureOwner::Engine, id.generation),
+        };
+
+        let id = TextureId {
+            stable_id: id.stable_id,
+            generation: generation + 1,
+        };
+        self.insert(id, texture, owner, key);
         id
     }
 
+    /// Return a texture to the free list. Stale handles are ignored, the
+    /// texture that replaced theirs belongs to someone else now.
+    /// System textures are never released.
     pub fn release_texture(&mut self, id: TextureId) {
-        self.textures.remove(&id.stable_id);
+        if id.stable_id < SYSTEM_TEXTURE_COUNT {
+            return;
+        }
+        match self.textures.get(&id.stable_id) {
+            Some(entry) if entry.generation != id.generation => {
+                log::warn!(
+                    "Ignoring release of stale texture {id:?}, it is now at generation {}",
+                    entry.generation
+                );
+            }
+            Some(_) => {
+                if let Some(entry) = self.take(id) {
+                    self.recycle(entry);
+                }
+            }
+            None => {}
+        }
     }
 
     pub fn release_node_textures(&mut self, node: NodeIndex) {
-        self.textures
-            .retain(|_, e| e.owner != TextureOwner::Node(node));
+        let owned: Vec<_> = self
+            .textures
+            .iter()
+            .filter(|(_, e)| e.owner == TextureOwner::Node(node))
+            .map(|(&stable_id, e)| TextureId {
+                stable_id,
+                generation: e.generation,
+            })
+            .collect();
+        for id in owned {
+            self.release_texture(id);
+        }
+    }
+
+    /// Cap the bytes held by the pool, None for no limit. Only free textures
+    /// are evicted, so textures in use can still push the pool past it.
+    pub fn set_budget(&mut self, budget: Option<u64>) {
+        self.budget = budget;
+        self.enforce_budget();
+    }
+
+    pub fn stats(&self) -> TexturePoolStats {
+        let mut bytes_per_owner = HashMap::new();
+        for entry in self.textures.values() {
+            *bytes_per_owner.entry(entry.owner).or_default() += entry.key.bytes();
+        }
+        TexturePoolStats {
+            live_textures: self.textures.len(),
+            live_bytes: self.live_bytes,
+            free_textures: self.free.values().map(Vec::len).sum(),
+            free_bytes: self.free_bytes,
+            peak_bytes: self.peak_bytes,
+            recycled: self.recycled,
+            created: self.created,
+            evicted: self.evicted,
+            bytes_per_owner,
+        }
+    }
+
+    /// Reuse the most recently released texture with this key, or create one.
+    /// With `clear` set reused textures are cleared so they read the same as new ones.
+    fn acquire(&mut self, device: &Device, queue: &Queue, key: TextureKey, clear: bool) -> Texture {
+        let reused = self.free.get_mut(&key).and_then(Vec::pop);
+        match reused {
+            Some(free) => {
+                self.free_bytes -= key.bytes();
+                self.recycled += 1;
+                if clear {
+                    clear_texture(device, queue, &free.texture);
+                }
+                free.texture
+            }
+            None => {
+                self.created += 1;
+                create_texture(device, key)
+            }
+        }
+    }
+
+    fn insert(&mut self, id: TextureId, texture: Texture, owner: TextureOwner, key: TextureKey) {
+        self.live_bytes += key.bytes();
+        self.peak_bytes = self.peak_bytes.max(self.live_bytes + self.free_bytes);
+        self.textures.insert(
+            id.stable_id,
+            TextureEntry {
+                texture,
+                owner,
+                key,
+                generation: id.generation,
+            },
+        );
+        self.enforce_budget();
+    }
+
+    /// Remove the texture behind `id`, regardless of generation.
+    fn take(&mut self, id: TextureId) -> Option<TextureEntry> {
+        let entry = self.textures.remove(&id.stable_id)?;
+        self.live_bytes -= entry.key.bytes();
+        Some(entry)
+    }
+
+    fn recycle(&mut self, entry: TextureEntry) {
+        self.clock += 1;
+        self.free_bytes += entry.key.bytes();
+        self.free.entry(entry.key).or_default().push(FreeTexture {
+            texture: entry.texture,
+            released_at: self.clock,
+        });
+        self.enforce_budget();
     }
-}
 
-fn texture_format_to_wgpu(fmt: TextureFormat) -> wgpu::TextureFormat {
-    match fmt {
-        TextureFormat::RGBAu8 => wgpu::TextureFormat::Rgba8Unorm,
-        TextureFormat::RGBAu16 => wgpu::TextureFormat::Rgba16Unorm,
-        TextureFormat::RGBAF32 => wgpu::TextureFormat::Rgba32Float,
-        TextureFormat::BGRA8 => wgpu::TextureFormat::Bgra8Unorm,
+    fn enforce_budget(&mut self) {
+        let Some(budget) = self.budget else {
+            return;
+        };
+        while self.live_bytes + self.free_bytes > budget {
+            let oldest = self
+                .free
+                .iter()
+                .filter_map(|(key, list)| Some((*key, list.first()?.released_at)))
+                .min_by_key(|(_, released_at)| *released_at);
+            let Some((key, _)) = oldest else {
+                break;
+            };
+            let list = self.free.get_mut(&key).expect("key was just found");
+            // Lists are pushed in release order, so the front is the oldest
+            list.remove(0);
+            if list.is_empty() {
+                self.free.remove(&key);
+            }
+            self.free_bytes -= key.bytes();
+            self.evicted += 1;
+        }
     }
 }
 
-fn create_gpu_texture(
-    device: &Device,
-    queue: &Queue,
-    handle: &TextureHandle,
-    data: &[u8],
-) -> Texture {
-    let size = wgpu::Extent3d {
-        width: handle.width,
-        height: handle.height,
-        depth_or_array_layers: 1,
-    };
+/// Usages of textures created from uploaded pixel data.
+const UPLOAD_USAGES: TextureUsages = TextureUsages::TEXTURE_BINDING.union(TextureUsages::COPY_DST);
 
-    let texture = device.create_texture(&TextureDescriptor {
+fn create_texture(device: &Device, key: TextureKey) -> Texture {
+    device.create_texture(&TextureDescriptor {
         label: None,
-        size,
-        mip_level_count: 1,
+        size: wgpu::Extent3d {
+            width: key.width,
+            height: key.height,
+            depth_or_array_layers: 1,
+        },
+        mip_level_count: key.mip_levels,
         sample_count: 1,
         dimension: wgpu::TextureDimension::D2,
-        format: texture_format_to_wgpu(handle.fmt),
-        usage: TextureUsages::TEXTURE_BINDING | TextureUsages::COPY_DST,
-        view_formats: &[],
-    });
-
-    let bytes_per_pixel = match handle.fmt {
-        TextureFormat::RGBAu8 | TextureFormat::BGRA8 => 4,
-        TextureFormat::RGBAu16 => 8,
-        TextureFormat::RGBAF32 => 16,
-    };
+        format: key.format.to_wgpu(),
+        usage: key.usage,
+        view_formats: &srgb_view_format(key.format),
+    })
+}
 
+/// Write the first mip level from rows starting every `bytes_per_row` bytes of `data`.
+fn write_texture(queue: &Queue, texture: &Texture, bytes_per_row: u32, data: &[u8]) {
+    let size = texture.size();
     queue.write_texture(
         wgpu::TexelCopyTextureInfo {
-            texture: &texture,
+            texture,
             mip_level: 0,
             origin: wgpu::Origin3d::ZERO,
             aspect: wgpu::TextureAspect::All,
@@ -183,33 +407,71 @@ fn create_gpu_texture(
         data,
         wgpu::TexelCopyBufferLayout {
             offset: 0,
-            bytes_per_row: Some(handle.width * bytes_per_pixel),
-            rows_per_image: Some(handle.height),
+            bytes_per_row: Some(bytes_per_row),
+            rows_per_image: Some(size.height),
+        },
+        wgpu::Extent3d {
+            depth_or_array_layers: 1,
+            ..size
         },
-        size,
     );
+}
 
-    texture
+/// Zero a recycled texture, uploads skip this as they overwrite every texel.
+fn clear_texture(device: &Device, queue: &Queue, texture: &Texture) {
+    let mut encoder = device.create_command_encoder(&Default::default());
+    if device.features().contains(wgpu::Features::CLEAR_TEXTURE) {
+        encoder.clear_texture(texture, &Default::default());
+    } else if texture.usage().contains(TextureUsages::RENDER_ATTACHMENT) {
+        let view = mip_view(texture, 0);
+        encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+            color_attachments: &[Some(wgpu::RenderPassColorAttachment {
+                view: &view,
+                depth_slice: None,
+                resolve_target: None,
+                ops: wgpu::Operations {
+                    load: wgpu::LoadOp::Clear(wgpu::Color::TRANSPARENT),
+                    store: wgpu::StoreOp::Store,
+                },
+            })],
+            ..Default::default()
+        });
+    } else {
+        return;
+    }
+    queue.submit(Some(encoder.finish()));
 }
 
-pub(crate) fn create_gpu_texture_empty(device: &Device, handle: &TextureHandle) -> Texture {
-    let size = wgpu::Extent3d {
-        width: handle.width,
-        height: handle.height,
-        depth_or_array_layers: 1,
-    };
+/// Usages for textures nodes render into, limited to what the format allows on
+/// this device. BGRA8 for instance can't be a storage texture without an extra feature.
+fn render_target_usages(device: &Device, fmt: TextureFormat) -> TextureUsages {
+    let wanted = TextureUsages::TEXTURE_BINDING
+        | TextureUsages::COPY_DST
+        | TextureUsages::COPY_SRC
+        | TextureUsages::STORAGE_BINDING
+        | TextureUsages::RENDER_ATTACHMENT;
+    let allowed = fmt
+        .to_wgpu()
+        .guaranteed_format_features(device.features())
+        .allowed_usages;
+    wanted & allowed
+}
 
-    device.create_texture(&TextureDescriptor {
-        label: None,
-        size,
-        mip_level_count: 1,
-        sample_count: 1,
-        dimension: wgpu::TextureDimension::D2,
-        format: texture_format_to_wgpu(handle.fmt),
-        usage: TextureUsages::TEXTURE_BINDING
-            | TextureUsages::COPY_DST
-            | TextureUsages::STORAGE_BINDING
-            | TextureUsages::RENDER_ATTACHMENT,
-        view_formats: &[],
+/// The `Srgb` twin of the format where there is one, so sRGB encoded
+/// textures can be viewed as linear light.
+fn srgb_view_format(fmt: TextureFormat) -> Vec<wgpu::TextureFormat> {
+    let srgb = fmt.to_wgpu().add_srgb_suffix();
+    (srgb != fmt.to_wgpu())
+        .then_some(srgb)
+        .into_iter()
+        .collect()
+}
+
+/// View of a single mip level, as render passes and blits need.
+pub(crate) fn mip_view(texture: &Texture, level: u32) -> wgpu::TextureView {
+    texture.create_view(&wgpu::TextureViewDescriptor {
+        base_mip_level: level,
+        mip_level_count: Some(1),
+        ..Default::default()
     })
 }
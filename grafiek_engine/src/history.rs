@@ -1,7 +1,7 @@
 use petgraph::prelude::NodeIndex;
 
-use crate::Value;
 use crate::node::NodeRecord;
+use crate::{TextureId, Value};
 
 pub type SlotIndex = usize;
 
@@ -44,6 +44,18 @@ pub enum Event {
     NodeExecuted { node: NodeIndex },
     /// Graph was marked dirty (needs re-execution)
     GraphDirtied,
+    /// A node started compiling in the background, it keeps running its
+    /// previous version until the compile finishes
+    CompileStarted { node: NodeIndex },
+    /// A background compile finished, on failure the node's errors say why
+    CompileFinished { node: NodeIndex, success: bool },
+    /// With texture debugging on, a node was about to execute holding a
+    /// handle to a texture that has since been resized or replaced
+    StaleTexture {
+        node: NodeIndex,
+        slot: SlotIndex,
+        id: TextureId,
+    },
 }
 
 /// A mutation that can be applied to the graph, stored for undo/redo
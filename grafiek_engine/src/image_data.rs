@@ -0,0 +1,257 @@
+use std::path::Path;
+
+use image::{DynamicImage, ImageBuffer, Rgba, Rgba32FImage, RgbaImage};
+
+use crate::error::Error;
+use crate::{ColorSpace, TextureFormat};
+
+/// Decoded pixels ready to upload to a texture with [crate::Engine::upload_image].
+///
+/// Rows may be padded past `width * bytes_per_pixel`, as some decoders and
+/// capture APIs align them, the padding is skipped on upload.
+#[derive(Debug, Clone)]
+pub struct ImageData {
+    width: u32,
+    height: u32,
+    format: TextureFormat,
+    color_space: ColorSpace,
+    bytes_per_row: u32,
+    data: Vec<u8>,
+}
+
+impl ImageData {
+    /// Tightly packed pixels in `format`.
+    pub fn new(
+        width: u32,
+        height: u32,
+        format: TextureFormat,
+        color_space: ColorSpace,
+        data: Vec<u8>,
+    ) -> Result<Self, Error> {
+        // Rows too wide for a u32 stride fail the layout check below
+        let bytes_per_row = width.saturating_mul(format.bytes_per_pixel());
+        Self::with_row_stride(width, height, format, color_space, bytes_per_row, data)
+    }
+
+    /// Pixels in `format` whose rows start every `bytes_per_row` bytes.
+    pub fn with_row_stride(
+        width: u32,
+        height: u32,
+        format: TextureFormat,
+        color_space: ColorSpace,
+        bytes_per_row: u32,
+        data: Vec<u8>,
+    ) -> Result<Self, Error> {
+        let invalid = || Error::InvalidImageLayout {
+            width,
+            height,
+            bytes_per_row,
+        };
+        let row = (width as usize)
+            .checked_mul(format.bytes_per_pixel() as usize)
+            .ok_or_else(invalid)?;
+        if width == 0 || height == 0 || (bytes_per_row as usize) < row {
+            return Err(invalid());
+        }
+
+        // The last row needs no padding
+        let expected = (bytes_per_row as usize)
+            .checked_mul(height as usize - 1)
+            .and_then(|rows| rows.checked_add(row))
+            .ok_or_else(invalid)?;
+        if data.len() < expected {
+            return Err(Error::TextureDataSize {
+                expected,
+                actual: data.len(),
+            });
+        }
+
+        Ok(Self {
+            width,
+            height,
+            format,
+            color_space,
+            bytes_per_row,
+            data,
+        })
+    }
+
+    /// Decode an image file held in memory, see [ImageData::from_image].
+    pub fn decode(bytes: &[u8]) -> Result<Self, Error> {
+        let image =
+            image::load_from_memory(bytes).map_err(|e| Error::DecodeImage(e.to_string()))?;
+        Self::from_image(image)
+    }
+
+    /// Read and decode an image file, see [ImageData::from_image].
+    pub fn open(path: impl AsRef<Path>) -> Result<Self, Error> {
+        let path = path.as_ref();
+        let image = image::open(path).map_err(|e| match e {
+            image::ImageError::IoError(source) => Error::ReadFile {
+                path: path.to_path_buf(),
+                source,
+            },
+            e => Error::DecodeImage(format!("{path:?}: {e}")),
+        })?;
+        Self::from_image(image)
+    }
+
+    /// Pick the texture format that keeps the image's precision. 8 bit images
+    /// are read as [ColorSpace::Srgb] and so are 16 bit ones, which are
+    /// usually PNG or TIFF. Float images such as EXR and Radiance HDR hold
+    /// linear light.
+    pub fn from_image(image: DynamicImage) -> Result<Self, Error> {
+        let (width, height) = (image.width(), image.height());
+        match image {
+            DynamicImage::ImageLuma8(_)
+            | DynamicImage::ImageLumaA8(_)
+            | DynamicImage::ImageRgb8(_)
+            | DynamicImage::ImageRgba8(_) => Self::new(
+                width,
+                height,
+                TextureFormat::RGBAu8,
+                ColorSpace::Srgb,
+                image.into_rgba8().into_raw(),
+            ),
+            DynamicImage::ImageLuma16(_)
+            | DynamicImage::ImageLumaA16(_)
+            | DynamicImage::ImageRgb16(_)
+            | DynamicImage::ImageRgba16(_) => {
+                let pixels = image.into_rgba16().into_raw();
+                let data = pixels.iter().flat_map(|c| c.to_ne_bytes()).collect();
+                Self::new(
+                    width,
+                    height,
+                    TextureFormat::RGBAu16,
+                    ColorSpace::Srgb,
+                    data,
+                )
+            }
+            image => {
+                let pixels = image.into_rgba32f().into_raw();
+                let data = pixels.iter().flat_map(|c| c.to_ne_bytes()).collect();
+                Self::new(
+                    width,
+                    height,
+                    TextureFormat::RGBAF32,
+                    ColorSpace::Linear,
+                    data,
+                )
+            }
+        }
+    }
+
+    /// Widen the pixels to 32 bit float, for devices without 16 bit normalized textures.
+    /// Fails if the widened rows no longer fit the layout.
+    pub fn to_float(&self) -> Result<Self, Error> {
+        let float = TextureFormat::RGBAF32.bytes_per_pixel();
+        let bytes_per_row = self
+            .width
+            .checked_mul(float)
+            .ok_or(Error::InvalidImageLayout {
+                width: self.width,
+                height: self.height,
+                bytes_per_row: self.bytes_per_row,
+            })?;
+        let mut data = Vec::with_capacity(bytes_per_row as usize * self.height as usize);
+        for row in self.rows() {
+            match self.format {
+                TextureFormat::RGBAF32 => data.extend_from_slice(row),
+                TextureFormat::RGBAu16 => data.extend(
+                    row.chunks_exact(2)
+                        .map(|c| u16::from_ne_bytes([c[0], c[1]]) as f32 / u16::MAX as f32)
+                        .flat_map(f32::to_ne_bytes),
+                ),
+                TextureFormat::RGBAu8 => data.extend(
+                    row.iter()
+                        .map(|&c| c as f32 / u8::MAX as f32)
+                        .flat_map(f32::to_ne_bytes),
+                ),
+                TextureFormat::BGRA8 => data.extend(
+                    row.chunks_exact(4)
+                        .flat_map(|c| [c[2], c[1], c[0], c[3]])
+                        .map(|c| c as f32 / u8::MAX as f32)
+                        .flat_map(f32::to_ne_bytes),
+                ),
+            }
+        }
+
+        Ok(Self {
+            format: TextureFormat::RGBAF32,
+            bytes_per_row,
+            data,
+            ..*self
+        })
+    }
+
+    /// Pixels as an [image::DynamicImage], ready to encode.
+    pub fn to_image(&self) -> Result<DynamicImage, Error> {
+        let packed: Vec<u8> = self.rows().flatten().copied().collect();
+        let (width, height) = (self.width, self.height);
+        let image = match self.format {
+            TextureFormat::RGBAu8 => {
+                RgbaImage::from_raw(width, height, packed).map(DynamicImage::from)
+            }
+            TextureFormat::BGRA8 => {
+                let rgba = packed
+                    .chunks_exact(4)
+                    .flat_map(|c| [c[2], c[1], c[0], c[3]])
+                    .collect();
+                RgbaImage::from_raw(width, height, rgba).map(DynamicImage::from)
+            }
+            TextureFormat::RGBAu16 => {
+                let pixels = packed
+                    .chunks_exact(2)
+                    .map(|c| u16::from_ne_bytes([c[0], c[1]]))
+                    .collect();
+                ImageBuffer::<Rgba<u16>, _>::from_raw(width, height, pixels).map(DynamicImage::from)
+            }
+            TextureFormat::RGBAF32 => {
+                let pixels = packed
+                    .chunks_exact(4)
+                    .map(|c| f32::from_ne_bytes([c[0], c[1], c[2], c[3]]))
+                    .collect();
+                Rgba32FImage::from_raw(width, height, pixels).map(DynamicImage::from)
+            }
+        };
+        image.ok_or(Error::InvalidImageLayout {
+            width,
+            height,
+            bytes_per_row: self.bytes_per_row,
+        })
+    }
+
+    /// Each row of pixels without padding.
+    fn rows(&self) -> impl Iterator<Item = &[u8]> {
+        // Fits, the layout was checked on construction
+        let row = self.width as usize * self.format.bytes_per_pixel() as usize;
+        (0..self.height as usize).map(move |y| {
+            let start = y * self.bytes_per_row as usize;
+            &self.data[start..start + row]
+        })
+    }
+
+    pub fn width(&self) -> u32 {
+        self.width
+    }
+
+    pub fn height(&self) -> u32 {
+        self.height
+    }
+
+    pub fn format(&self) -> TextureFormat {
+        self.format
+    }
+
+    pub fn color_space(&self) -> ColorSpace {
+        self.color_space
+    }
+
+    pub fn bytes_per_row(&self) -> u32 {
+        self.bytes_per_row
+    }
+
+    pub fn data(&self) -> &[u8] {
+        &self.data
+    }
+}
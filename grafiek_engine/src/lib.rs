@@ -1,8 +1,13 @@
+mod color;
 mod document;
 mod engine;
 mod execution_context;
+mod file_watch;
 mod gpu_pool;
+mod image_data;
 mod node;
+mod pipeline_cache;
+mod random;
 mod registry;
 mod value;
 
@@ -13,8 +18,11 @@ pub mod ops;
 pub mod traits;
 
 pub use engine::*;
-pub use gpu_pool::TextureId;
-pub use node::Node;
+pub use gpu_pool::{TextureId, TextureOwner, TexturePoolStats};
+pub use image_data::ImageData;
+pub use node::{DirtyFlag, Node};
+pub use pipeline_cache::PipelineCacheStats;
+pub use random::Rng;
 pub use registry::*;
 pub use value::*;
 
@@ -258,6 +258,8 @@ impl Node {
 // Lifecycle
 impl Node {
     pub fn setup(&mut self, ctx: &mut ExecutionContext) -> Result<(), crate::error::Error> {
+        self.operation
+            .set_reconfigure_flag(self.needs_reconfigure.clone());
         self.operation.setup(ctx, &mut self.signature);
 
         self.signature.validate_unique_names()?;
@@ -366,9 +368,17 @@ impl Node {
             .map(Value::as_ref)
             .collect();
 
+        let old_configs: Vec<_> = self.signature.config.clone();
+        let old_inputs: Vec<_> = self.signature.inputs.clone();
+
+        // Cleared first so a failed configure isn't retried on every poll,
+        // background work that finishes from here on sets it again
+        self.needs_reconfigure.clear();
         self.operation.configure(ctx, config, &mut self.signature)?;
 
         self.signature.validate_unique_names()?;
+        self.sync_config_values(&old_configs);
+        self.sync_input_values(&old_inputs);
 
         self.output_values = self
             .signature
@@ -377,14 +387,95 @@ impl Node {
             .map(|s| s.default_value())
             .collect();
 
-        self.needs_reconfigure.clear();
-
         Ok(())
     }
 
+    /// Operations may add or remove config slots while configuring, carry
+    /// values over to the new slots by name and default the rest.
+    fn sync_config_values(&mut self, old_configs: &[SlotDef]) {
+        let unchanged = old_configs.len() == self.signature.config.len()
+            && old_configs
+                .iter()
+                .zip(&self.signature.config)
+                .all(|(a, b)| a.name() == b.name() && a.value_type() == b.value_type());
+        if unchanged {
+            return;
+        }
+
+        let old_values = std::mem::take(&mut self.record.config_values);
+        self.record.config_values = self
+            .signature
+            .config
+            .iter()
+            .map(|def| {
+                old_configs
+                    .iter()
+                    .position(|old| {
+                        old.name() == def.name() && old.value_type() == def.value_type()
+                    })
+                    .and_then(|i| old_values.get(i))
+                    .cloned()
+                    .unwrap_or_else(|| def.default_value())
+            })
+            .collect();
+    }
+
+    /// Same as [Node::sync_config_values] for the stored input values. Incoming
+    /// values stay with their slot index, edges that no longer fit are
+    /// disconnected by the engine.
+    fn sync_input_values(&mut self, old_inputs: &[SlotDef]) {
+        self.incoming_input_values.resize(self.input_count(), None);
+
+        let unchanged = old_inputs.len() == self.signature.inputs.len()
+            && old_inputs
+                .iter()
+                .zip(&self.signature.inputs)
+                .all(|(a, b)| a.name() == b.name() && a.value_type() == b.value_type());
+        if unchanged {
+            return;
+        }
+
+        let old_values = std::mem::take(&mut self.record.input_values);
+        self.record.input_values = self
+            .signature
+            .inputs
+            .iter()
+            .map(|def| {
+                old_inputs
+                    .iter()
+                    .position(|old| {
+                        old.name() == def.name() && old.value_type() == def.value_type()
+                    })
+                    .and_then(|i| old_values.get(i))
+                    .cloned()
+                    .unwrap_or_else(|| def.default_value())
+            })
+            .collect();
+    }
+
+    /// Ask the operation whether something outside the graph changed and it
+    /// should be reconfigured, or if background work flagged it.
+    pub(crate) fn poll_reconfigure(&mut self) -> bool {
+        self.operation.needs_reconfigure() || self.needs_reconfigure.get()
+    }
+
+    /// True while the operation is compiling in the background.
+    pub fn is_compiling(&self) -> bool {
+        self.operation.is_compiling()
+    }
+
     pub fn teardown(&mut self, ctx: &mut ExecutionContext) {
         self.operation.teardown(ctx);
     }
+
+    /// True if the operation keeps state between executions.
+    pub fn is_stateful(&self) -> bool {
+        self.operation.is_stateful()
+    }
+
+    pub(crate) fn reset(&mut self) {
+        self.operation.reset();
+    }
 }
 
 // Execution
@@ -417,8 +508,21 @@ impl Node {
     /// Builds inputs from incoming values (or falls back to record values),
     /// then calls the operation's execute method.
     pub fn execute(&mut self, ctx: &mut ExecutionContext) -> crate::error::Result<()> {
-        let inputs = self
-            .incoming_input_values
+        let inputs = self.gather_inputs()?;
+        let inputs = inputs.iter().map(|i| i.as_ref()).collect();
+
+        let outputs: Outputs = self.output_values.iter_mut().map(Value::as_mut).collect();
+
+        self.operation.execute(ctx, inputs, outputs)?;
+
+        self.needs_execute.clear();
+
+        Ok(())
+    }
+
+    /// Input values as the operation sees them, incoming values cast to the slot type.
+    fn gather_inputs(&self) -> crate::error::Result<ArrayVec<Value, 32>> {
+        self.incoming_input_values
             .iter()
             .zip(self.record.input_values.iter())
             .enumerate()
@@ -434,17 +538,24 @@ impl Node {
                         to_slot,
                     })
             })
-            .collect::<crate::error::Result<ArrayVec<Value, 32>>>()?;
-
-        let inputs = inputs.iter().map(|i| i.as_ref()).collect();
-
-        let outputs: Outputs = self.output_values.iter_mut().map(Value::as_mut).collect();
-
-        self.operation.execute(ctx, inputs, outputs)?;
+            .collect()
+    }
 
-        self.needs_execute.clear();
+    /// See [Operation::condition_inputs].
+    pub(crate) fn condition_inputs(&self) -> std::ops::Range<usize> {
+        self.operation.condition_inputs()
+    }
 
-        Ok(())
+    /// See [Operation::selected_inputs], only asked of conditional operations
+    /// once their condition inputs have arrived.
+    pub(crate) fn selected_inputs(&self) -> Option<Vec<usize>> {
+        if self.condition_inputs().is_empty() {
+            return None;
+        }
+        // Errors are left for execute to report
+        let inputs = self.gather_inputs().ok()?;
+        self.operation
+            .selected_inputs(inputs.iter().map(|i| i.as_ref()).collect())
     }
 
     pub(crate) fn on_edge_connected(
@@ -452,8 +563,14 @@ impl Node {
         slot: usize,
         ty: crate::ValueType,
     ) -> crate::error::Result<()> {
-        self.operation
-            .on_edge_connected(slot, ty, &mut self.signature)
+        let old_inputs: Vec<_> = self.signature.inputs.clone();
+        let old_outputs: Vec<_> = self.signature.outputs.clone();
+        let result = self
+            .operation
+            .on_edge_connected(slot, ty, &mut self.signature);
+        self.sync_input_values(&old_inputs);
+        self.sync_output_values(&old_outputs);
+        result
     }
 
     pub(crate) fn on_edge_disconnected(
@@ -461,7 +578,33 @@ impl Node {
         slot: usize,
         ty: crate::ValueType,
     ) -> crate::error::Result<()> {
-        self.operation
-            .on_edge_disconnected(slot, ty, &mut self.signature)
+        let old_inputs: Vec<_> = self.signature.inputs.clone();
+        let old_outputs: Vec<_> = self.signature.outputs.clone();
+        let result = self
+            .operation
+            .on_edge_disconnected(slot, ty, &mut self.signature);
+        self.sync_input_values(&old_inputs);
+        self.sync_output_values(&old_outputs);
+        result
+    }
+
+    /// Keep output values whose slot kept its type when an operation changes
+    /// its outputs outside of [Node::configure], and default the rest.
+    fn sync_output_values(&mut self, old_outputs: &[SlotDef]) {
+        let old_values = std::mem::take(&mut self.output_values);
+        self.output_values = self
+            .signature
+            .outputs
+            .iter()
+            .enumerate()
+            .map(|(i, def)| {
+                old_outputs
+                    .get(i)
+                    .filter(|old| old.value_type() == def.value_type())
+                    .and_then(|_| old_values.get(i))
+                    .cloned()
+                    .unwrap_or_else(|| def.default_value())
+            })
+            .collect();
     }
 }
@@ -0,0 +1,89 @@
+use crate::error::Result;
+use crate::registry::{SignatureRegistery, TextureMeta};
+use crate::traits::{OpPath, Operation, OperationFactory};
+use crate::value::{Config, Inputs, InputsExt, Outputs, OutputsExt};
+use crate::{ColorSpace, ConfigSchema, ExecutionContext, SPECK, TextureHandle};
+
+/// Converts a texture into another colour space, or retags it without
+/// touching the pixels when an image was loaded with the wrong tag.
+#[derive(Default)]
+pub struct ConvertColorSpace {
+    space: ColorSpace,
+    reinterpret: bool,
+}
+
+#[derive(ConfigSchema)]
+struct ConvertConfig {
+    #[on_node_body]
+    #[label("to")]
+    space: ColorSpace,
+
+    /// Retag the input as `to` instead of converting it
+    reinterpret: bool,
+}
+
+impl Operation for ConvertColorSpace {
+    fn is_stateful(&self) -> bool {
+        false
+    }
+
+    fn op_path(&self) -> OpPath {
+        <Self as OperationFactory>::op_path()
+    }
+
+    fn setup(&mut self, _ctx: &mut ExecutionContext, registry: &mut SignatureRegistery) {
+        registry
+            .add_input::<TextureHandle>("image")
+            .default(SPECK)
+            .build();
+        registry
+            .add_output::<TextureHandle>("image")
+            .meta(TextureMeta {
+                preview: true,
+                allow_file: false,
+                ..Default::default()
+            })
+            .build();
+        registry.register_config::<ConvertConfig>();
+    }
+
+    fn configure(
+        &mut self,
+        _ctx: &ExecutionContext,
+        config: Config,
+        _registry: &mut SignatureRegistery,
+    ) -> Result<()> {
+        let cfg = ConvertConfig::try_extract(config)?;
+        self.space = cfg.space;
+        self.reinterpret = cfg.reinterpret;
+        Ok(())
+    }
+
+    fn execute(
+        &mut self,
+        ctx: &mut ExecutionContext,
+        inputs: Inputs,
+        mut outputs: Outputs,
+    ) -> Result<()> {
+        let mut input: TextureHandle = inputs.extract(0)?;
+        if self.reinterpret {
+            input.color_space = self.space;
+        }
+
+        let output: &mut TextureHandle = outputs.extract(0)?;
+        output.fmt = input.fmt;
+        output.color_space = self.space;
+        ctx.convert_texture(&input, output);
+        Ok(())
+    }
+}
+
+impl OperationFactory for ConvertColorSpace {
+    const LIBRARY: &'static str = "core";
+    const OPERATOR: &'static str = "color_space";
+    const LABEL: &'static str = "Color Space";
+
+    fn build() -> Result<Box<dyn Operation>> {
+        Ok(Box::new(ConvertColorSpace::default()))
+    }
+}
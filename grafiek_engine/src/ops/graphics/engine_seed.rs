@@ -0,0 +1,90 @@
+use tweak_shader::{RenderContext, input_type::InputType};
+
+use super::include::Preprocessed;
+use crate::error::{LocatedError, ScriptError};
+
+/// The int input a `#pragma engine_seed(name)` directive names.
+pub struct EngineSeed {
+    pub name: String,
+    /// Where the directive is, errors about the input it names point here
+    location: LocatedError,
+}
+
+impl EngineSeed {
+    /// Point the directive back at the file it was included from.
+    pub fn map_location(mut self, pre: &Preprocessed) -> Self {
+        pre.map_location(&mut self.location);
+        self
+    }
+
+    /// Check the named input exists and is an int, once the shader has been reflected.
+    pub fn check(&self, ctx: &RenderContext) -> Result<(), ScriptError> {
+        let message = match ctx.iter_inputs().find(|(n, _)| n.as_str() == self.name) {
+            Some((_, InputType::Int(..))) => return Ok(()),
+            Some(_) => format!("engine_seed input {} is not an int", self.name),
+            None => format!("engine_seed names {}, which is not an input", self.name),
+        };
+        Err(ScriptError {
+            errors: vec![LocatedError {
+                message,
+                ..self.location.clone()
+            }],
+        })
+    }
+}
+
+/// Resolve a `#pragma engine_seed(name)` directive, returning the int input it
+/// names. That input is drawn under [crate::Engine::set_seed] before each
+/// render, every other input is passed through as set.
+///
+/// tweak_shader doesn't know the directive, so it's blanked in place, keeping
+/// line numbers intact for errors.
+pub fn preprocess(source: &mut String) -> Result<Option<EngineSeed>, ScriptError> {
+    let mut seed = None;
+    let mut out = String::with_capacity(source.len());
+
+    for (i, line) in source.lines().enumerate() {
+        let Some(directive) = line.trim_start().strip_prefix("#pragma engine_seed") else {
+            out.push_str(line);
+            out.push('\n');
+            continue;
+        };
+        out.push('\n');
+
+        let location = LocatedError {
+            message: String::new(),
+            file: None,
+            line: i as u32 + 1,
+            column: 1,
+        };
+        let error = |message: String| ScriptError {
+            errors: vec![LocatedError {
+                message,
+                ..location.clone()
+            }],
+        };
+
+        let name = directive
+            .trim()
+            .strip_prefix('(')
+            .and_then(|d| d.strip_suffix(')'))
+            .map(|n| n.trim().trim_matches('"'))
+            .filter(|n| !n.is_empty())
+            .ok_or_else(|| {
+                error(format!(
+                    "malformed engine_seed directive: {}",
+                    directive.trim()
+                ))
+            })?;
+        if seed.is_some() {
+            return Err(error("only one input can follow the engine seed".into()));
+        }
+        seed = Some(EngineSeed {
+            name: name.to_owned(),
+            location,
+        });
+    }
+
+    *source = out;
+    Ok(seed)
+}
@@ -0,0 +1,23 @@
+#version 450
+
+// Snippets from the built in library or your include paths can be pulled in
+// with `#include`, see `sampler.glsl`, `color.glsl` and `noise.glsl`.
+#include "sampler.glsl"
+
+// Every `#pragma input` becomes an input slot on the node.
+#pragma input(image, name=image)
+layout(set = 0, binding = 1) uniform texture2D image;
+
+#pragma input(float, name="amount", default=1.0, min=0.0, max=1.0)
+layout(set = 0, binding = 2) uniform Inputs {
+    float amount;
+};
+
+layout(location = 0) out vec4 out_color;
+
+void main() {
+    ivec2 size = textureSize(sampler2D(image, default_sampler), 0);
+    vec4 color = texture(sampler2D(image, default_sampler), frag_uv(size));
+
+    out_color = vec4(color.rgb * amount, color.a);
+}
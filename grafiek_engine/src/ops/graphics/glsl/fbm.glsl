@@ -0,0 +1,29 @@
+#version 450
+
+// Fractal Brownian motion, layers of gradient noise at finer and finer scales.
+
+#include "noise.glsl"
+
+#pragma input(float, name="size", default=128.0, min=1.0, max=1024.0)
+#pragma input(int, name="seed", default=0, min=0, max=1000)
+#pragma engine_seed(seed)
+#pragma input(int, name="octaves", default=5, min=1, max=10)
+#pragma input(float, name="lacunarity", default=2.0, min=1.0, max=4.0)
+#pragma input(float, name="gain", default=0.5, min=0.0, max=1.0)
+layout(set = 0, binding = 0) uniform Inputs {
+    float size;
+    int seed;
+    int octaves;
+    float lacunarity;
+    float gain;
+};
+
+layout(location = 0) out vec4 out_color;
+
+void main() {
+    // The coarsest octave's cells are `size` pixels across at any resolution
+    vec2 p = gl_FragCoord.xy / size + seed_offset(seed);
+    float n = fbm(p, octaves, lacunarity, gain) * 0.5 + 0.5;
+
+    out_color = vec4(vec3(n), 1.0);
+}
@@ -0,0 +1,105 @@
+// Hash based noise, deterministic across runs and platforms.
+
+float hash12(vec2 p) {
+    vec3 p3 = fract(vec3(p.xyx) * 0.1031);
+    p3 += dot(p3, p3.yzx + 33.33);
+    return fract((p3.x + p3.y) * p3.z);
+}
+
+vec2 hash22(vec2 p) {
+    vec3 p3 = fract(vec3(p.xyx) * vec3(0.1031, 0.1030, 0.0973));
+    p3 += dot(p3, p3.yzx + 33.33);
+    return fract((p3.xx + p3.yz) * p3.zy);
+}
+
+// Value noise in [0, 1].
+float value_noise(vec2 p) {
+    vec2 i = floor(p);
+    vec2 f = fract(p);
+    vec2 u = f * f * (3.0 - 2.0 * f);
+
+    float a = hash12(i);
+    float b = hash12(i + vec2(1.0, 0.0));
+    float c = hash12(i + vec2(0.0, 1.0));
+    float d = hash12(i + vec2(1.0, 1.0));
+
+    return mix(mix(a, b, u.x), mix(c, d, u.x), u.y);
+}
+
+// Gradient (Perlin style) noise in roughly [-1, 1].
+float gradient_noise(vec2 p) {
+    vec2 i = floor(p);
+    vec2 f = fract(p);
+    vec2 u = f * f * f * (f * (f * 6.0 - 15.0) + 10.0);
+
+    vec2 ga = hash22(i) * 2.0 - 1.0;
+    vec2 gb = hash22(i + vec2(1.0, 0.0)) * 2.0 - 1.0;
+    vec2 gc = hash22(i + vec2(0.0, 1.0)) * 2.0 - 1.0;
+    vec2 gd = hash22(i + vec2(1.0, 1.0)) * 2.0 - 1.0;
+
+    float a = dot(ga, f);
+    float b = dot(gb, f - vec2(1.0, 0.0));
+    float c = dot(gc, f - vec2(0.0, 1.0));
+    float d = dot(gd, f - vec2(1.0, 1.0));
+
+    return mix(mix(a, b, u.x), mix(c, d, u.x), u.y);
+}
+
+// Domain offset for a seed, so each seed shows a different part of the noise.
+vec2 seed_offset(int seed) {
+    return hash22(vec2(float(seed) * 0.7317, float(seed) * -0.3193 + 0.5)) * 512.0;
+}
+
+// Simplex noise in roughly [-1, 1], with fewer directional artifacts than
+// gradient noise.
+float simplex_noise(vec2 p) {
+    const float F2 = 0.36602540378; // (sqrt(3) - 1) / 2
+    const float G2 = 0.21132486540; // (3 - sqrt(3)) / 6
+
+    vec2 i = floor(p + dot(p, vec2(F2)));
+    vec2 x0 = p - i + dot(i, vec2(G2));
+    vec2 o = x0.x > x0.y ? vec2(1.0, 0.0) : vec2(0.0, 1.0);
+    vec2 x1 = x0 - o + G2;
+    vec2 x2 = x0 - 1.0 + 2.0 * G2;
+
+    vec3 w = max(0.5 - vec3(dot(x0, x0), dot(x1, x1), dot(x2, x2)), 0.0);
+    w = w * w * w * w;
+    vec3 g = vec3(
+        dot(hash22(i) * 2.0 - 1.0, x0),
+        dot(hash22(i + o) * 2.0 - 1.0, x1),
+        dot(hash22(i + 1.0) * 2.0 - 1.0, x2)
+    );
+    return 70.0 * dot(w, g);
+}
+
+// Worley (cellular) noise, the distance to the nearest of one random point
+// per cell, in [0, ~1].
+float worley_noise(vec2 p) {
+    vec2 i = floor(p);
+    vec2 f = fract(p);
+    float nearest = 8.0;
+    for (int y = -1; y <= 1; y++) {
+        for (int x = -1; x <= 1; x++) {
+            vec2 cell = vec2(float(x), float(y));
+            vec2 d = cell + hash22(i + cell) - f;
+            nearest = min(nearest, dot(d, d));
+        }
+    }
+    return sqrt(nearest);
+}
+
+// Fractal Brownian motion, `octaves` layers of gradient noise each `lacunarity`
+// times finer and `gain` times fainter than the last, in roughly [-1, 1].
+float fbm(vec2 p, int octaves, float lacunarity, float gain) {
+    float sum = 0.0;
+    float amplitude = 1.0;
+    float total = 0.0;
+    for (int i = 0; i < octaves; i++) {
+        sum += amplitude * gradient_noise(p);
+        total += amplitude;
+        // Offset each octave so their lattices don't line up at the origin
+        p = p * lacunarity + vec2(17.13, 31.71);
+        amplitude *= gain;
+    }
+    return total > 0.0 ? sum / total : 0.0;
+}
@@ -0,0 +1,8 @@
+// The sampler bound alongside every image input. It is the same for all of
+// them, `#pragma sampling(<name>, mipmaps)` only gives an input a mip chain.
+layout(set = 0, binding = 0) uniform sampler default_sampler;
+
+// Normalized coordinate of the current fragment in a target of `size` pixels.
+vec2 frag_uv(ivec2 size) {
+    return gl_FragCoord.xy / vec2(size);
+}
@@ -0,0 +1,23 @@
+#version 450
+
+// Simplex noise, like Perlin noise without its grid aligned artifacts.
+
+#include "noise.glsl"
+
+#pragma input(float, name="size", default=64.0, min=1.0, max=1024.0)
+#pragma input(int, name="seed", default=0, min=0, max=1000)
+#pragma engine_seed(seed)
+layout(set = 0, binding = 0) uniform Inputs {
+    float size;
+    int seed;
+};
+
+layout(location = 0) out vec4 out_color;
+
+void main() {
+    // Cells are `size` pixels across at any resolution
+    vec2 p = gl_FragCoord.xy / size + seed_offset(seed);
+    float n = simplex_noise(p) * 0.5 + 0.5;
+
+    out_color = vec4(vec3(n), 1.0);
+}
@@ -0,0 +1,138 @@
+use std::path::{Path, PathBuf};
+
+use crate::error::{LocatedError, ScriptError};
+
+/// Built in snippets every shader can `#include` by name.
+const SNIPPETS: &[(&str, &str)] = &[
+    ("sampler.glsl", include_str!("glsl/include/sampler.glsl")),
+    ("color.glsl", include_str!("glsl/include/color.glsl")),
+    ("noise.glsl", include_str!("glsl/include/noise.glsl")),
+];
+
+/// Where a line of the expanded source came from.
+#[derive(Debug, Clone)]
+struct LineOrigin {
+    /// None for the shader's own source
+    file: Option<String>,
+    line: u32,
+}
+
+/// Shader source with every `#include` resolved.
+#[derive(Debug, Clone)]
+pub struct Preprocessed {
+    pub source: String,
+    /// Included files that were read from disk
+    pub files: Vec<PathBuf>,
+    lines: Vec<LineOrigin>,
+}
+
+impl Preprocessed {
+    /// Map a compile error against the expanded source back to the file and
+    /// line it originated from.
+    pub fn map_error(&self, mut err: ScriptError) -> ScriptError {
+        err.errors.iter_mut().for_each(|e| self.map_location(e));
+        err
+    }
+
+    /// Same as [Preprocessed::map_error] for a single location.
+    pub fn map_location(&self, e: &mut LocatedError) {
+        let Some(origin) = (e.line as usize)
+            .checked_sub(1)
+            .and_then(|i| self.lines.get(i))
+        else {
+            return;
+        };
+        e.file = origin.file.clone();
+        e.line = origin.line;
+    }
+}
+
+/// Resolve `#include "name.glsl"` directives against the built in snippet
+/// library, then each of `include_paths` in order. Every file is included at
+/// most once, so snippets may include each other freely.
+pub fn preprocess(source: &str, include_paths: &[PathBuf]) -> Result<Preprocessed, ScriptError> {
+    let mut out = Preprocessed {
+        source: String::with_capacity(source.len()),
+        files: vec![],
+        lines: vec![],
+    };
+    let mut included = vec![];
+    expand(source, None, include_paths, &mut included, &mut out)?;
+    Ok(out)
+}
+
+fn expand(
+    source: &str,
+    file: Option<&str>,
+    include_paths: &[PathBuf],
+    included: &mut Vec<String>,
+    out: &mut Preprocessed,
+) -> Result<(), ScriptError> {
+    for (i, line) in source.lines().enumerate() {
+        let line_number = i as u32 + 1;
+
+        let Some(directive) = line.trim_start().strip_prefix("#include") else {
+            out.source.push_str(line);
+            out.source.push('\n');
+            out.lines.push(LineOrigin {
+                file: file.map(str::to_owned),
+                line: line_number,
+            });
+            continue;
+        };
+
+        let error = |message: String| ScriptError {
+            errors: vec![LocatedError {
+                message,
+                file: file.map(str::to_owned),
+                line: line_number,
+                column: 1,
+            }],
+        };
+
+        let name = parse_include_name(directive)
+            .ok_or_else(|| error(format!("malformed include: {}", line.trim())))?;
+
+        if included.iter().any(|n| n == name) {
+            continue;
+        }
+        included.push(name.to_owned());
+
+        let contents = match SNIPPETS.iter().find(|(n, _)| *n == name) {
+            Some((_, src)) => (*src).to_owned(),
+            None => {
+                let path = find_include(name, include_paths)
+                    .ok_or_else(|| error(format!("could not find include \"{name}\"")))?;
+                let contents = std::fs::read_to_string(&path)
+                    .map_err(|e| error(format!("failed to read {path:?}: {e}")))?;
+                out.files.push(path);
+                contents
+            }
+        };
+
+        expand(&contents, Some(name), include_paths, included, out)?;
+    }
+
+    Ok(())
+}
+
+/// Accepts both `"name"` and `<name>`.
+fn parse_include_name(directive: &str) -> Option<&str> {
+    let directive = directive.trim();
+    directive
+        .strip_prefix('"')
+        .and_then(|d| d.strip_suffix('"'))
+        .or_else(|| {
+            directive
+                .strip_prefix('<')
+                .and_then(|d| d.strip_suffix('>'))
+        })
+        .filter(|name| !name.is_empty())
+}
+
+fn find_include(name: &str, include_paths: &[PathBuf]) -> Option<PathBuf> {
+    include_paths
+        .iter()
+        .map(|dir| dir.join(Path::new(name)))
+        .find(|path| path.is_file())
+}
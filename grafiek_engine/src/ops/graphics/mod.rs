@@ -1,2 +1,7 @@
+pub mod color_space;
+pub mod engine_seed;
+pub mod include;
+pub mod sampling;
 pub mod shade;
 pub mod tweak_shader_template;
+pub mod user_shader;
@@ -0,0 +1,77 @@
+use std::collections::HashMap;
+
+use crate::error::{LocatedError, ScriptError};
+use crate::registry::SamplerMeta;
+
+/// Resolve `#pragma sampling(name, mipmaps)` directives, returning the
+/// settings for each named image input.
+///
+/// Only mip chains can be asked for, every input is sampled with tweak_shader's
+/// default sampler, see [SamplerMeta]. Directives are blanked in place, keeping
+/// line numbers intact so errors still map back through the include preprocessor.
+pub fn preprocess(source: &mut String) -> Result<HashMap<String, SamplerMeta>, ScriptError> {
+    let mut sampling = HashMap::new();
+    let mut out = String::with_capacity(source.len());
+
+    for (i, line) in source.lines().enumerate() {
+        let Some(directive) = line.trim_start().strip_prefix("#pragma sampling") else {
+            out.push_str(line);
+            out.push('\n');
+            continue;
+        };
+
+        let error = |message: String| ScriptError {
+            errors: vec![LocatedError {
+                message,
+                file: None,
+                line: i as u32 + 1,
+                column: 1,
+            }],
+        };
+
+        let (name, meta) = parse_directive(directive).map_err(error)?;
+        out.push('\n');
+        sampling.insert(name.to_owned(), meta);
+    }
+
+    *source = out;
+    Ok(sampling)
+}
+
+fn parse_directive(directive: &str) -> Result<(&str, SamplerMeta), String> {
+    let args = directive
+        .trim()
+        .strip_prefix('(')
+        .and_then(|d| d.strip_suffix(')'))
+        .ok_or_else(|| format!("malformed sampling directive: {}", directive.trim()))?;
+
+    let mut args = args.split(',').map(str::trim);
+    let name = args
+        .next()
+        .filter(|n| !n.is_empty())
+        .ok_or("sampling directive is missing an input name")?;
+
+    let mut meta = SamplerMeta::default();
+    for arg in args {
+        let (key, value) = match arg.split_once('=') {
+            Some((k, v)) => (k.trim(), Some(v.trim().trim_matches('"'))),
+            None => (arg, None),
+        };
+        match (key, value) {
+            ("filter" | "wrap" | "anisotropy", _) => {
+                return Err(format!(
+                    "{key} can't be set, inputs are sampled with the default sampler"
+                ));
+            }
+            ("mipmaps", None) => meta.mipmaps = true,
+            ("mipmaps", Some(v)) => {
+                meta.mipmaps = v
+                    .parse()
+                    .map_err(|_| format!("mipmaps must be true or false, got {v}"))?;
+            }
+            _ => return Err(format!("unknown sampling setting: {arg}")),
+        }
+    }
+
+    Ok((name, meta))
+}
@@ -1,12 +1,10 @@
-use super::tweak_shader_template::ShaderTemplate;
-use tweak_shader::RenderContext;
+use super::tweak_shader_template::{ShaderState, ShaderTemplate};
 
 macro_rules! shader_op {
     ($name:ident, $operator:literal, $label:literal, $path:literal) => {
         #[derive(Default)]
         pub struct $name {
-            ctx: Option<RenderContext>,
-            match_input_dimensions: bool,
+            state: ShaderState,
         }
 
         impl ShaderTemplate for $name {
@@ -14,27 +12,41 @@ macro_rules! shader_op {
             const OPERATOR: &'static str = $operator;
             const LABEL: &'static str = $label;
 
-            fn context(&self) -> Option<&RenderContext> {
-                self.ctx.as_ref()
+            fn state(&self) -> &ShaderState {
+                &self.state
             }
 
-            fn context_mut(&mut self) -> Option<&mut RenderContext> {
-                self.ctx.as_mut()
-            }
-
-            fn set_context(&mut self, ctx: RenderContext) {
-                self.ctx = Some(ctx);
-            }
-
-            fn match_input_dimensions(&self) -> bool {
-                self.match_input_dimensions
-            }
-
-            fn set_match_input_dimensions(&mut self, val: bool) {
-                self.match_input_dimensions = val;
+            fn state_mut(&mut self) -> &mut ShaderState {
+                &mut self.state
             }
         }
     };
 }
 
 shader_op!(Grayscale, "grayscale", "Grayscale", "glsl/grayscale.glsl");
+shader_op!(Custom, "custom", "Custom Shader", "glsl/custom.glsl");
+shader_op!(
+    ValueNoise,
+    "value_noise",
+    "Value Noise",
+    "glsl/value_noise.glsl"
+);
+shader_op!(
+    PerlinNoise,
+    "perlin_noise",
+    "Perlin Noise",
+    "glsl/perlin_noise.glsl"
+);
+shader_op!(
+    SimplexNoise,
+    "simplex_noise",
+    "Simplex Noise",
+    "glsl/simplex_noise.glsl"
+);
+shader_op!(
+    WorleyNoise,
+    "worley_noise",
+    "Worley Noise",
+    "glsl/worley_noise.glsl"
+);
+shader_op!(Fbm, "fbm", "fBm Noise", "glsl/fbm.glsl");
@@ -1,25 +1,39 @@
 use std::any::Any;
+use std::collections::HashMap;
+use std::path::Path;
+use std::sync::mpsc::{self, TryRecvError};
 
 use parameter_schema_derive::{ConfigSchema, EnumSchema};
 use tweak_shader::{RenderContext, input_type::InputType};
 
+use super::engine_seed::{self, EngineSeed};
+use super::include::{self, Preprocessed};
+use super::sampling;
 use crate::error::{Error, Result, ScriptError};
-use crate::registry::{FloatRange, IntEnum, IntRange, SignatureRegistery};
+use crate::file_watch::FileWatch;
+use crate::gpu_pool::mip_view;
+use crate::pipeline_cache::{PipelineKey, SharedPipeline};
+use crate::registry::{FloatRange, IntEnum, IntRange, SamplerMeta, SignatureRegistery};
 use crate::traits::{OpPath, Operation, OperationFactory};
 use crate::value::{Inputs, Outputs, OutputsExt};
-use crate::{ExecutionContext, SPECK, TextureMeta};
+use crate::{
+    ColorSpace, DirtyFlag, ExecutionContext, SPECK, TextureFormat, TextureHandle, TextureMeta,
+};
 
-#[derive(EnumSchema, Default, Clone)]
-pub enum TextureFormat {
-    #[default]
+/// Format a shader renders to, [OutputFormat::Default] follows the engine's default format.
+#[derive(EnumSchema, Default, Clone, Copy, PartialEq)]
+pub enum OutputFormat {
     RGBA8,
     RGBA16,
     RGBAf32,
+    BGRA8,
+    #[default]
+    Default,
 }
 
 #[derive(ConfigSchema)]
 pub struct ShaderConfig {
-    pub format: TextureFormat,
+    pub format: OutputFormat,
 
     #[meta(IntRange { min: 1, max: 8192, step: 1 })]
     #[default(512)]
@@ -41,9 +55,24 @@ pub struct ShaderConfig {
 
     #[meta(crate::registry::StringMeta { kind: crate::registry::StringKind::Glsl, multi_line: true })]
     pub source: String,
+
+    /// When set the source is read from this file instead, and reloaded whenever it changes.
+    #[label("file")]
+    pub path: String,
+
+    /// Convert texture inputs to linear light before rendering. When off
+    /// inputs are sampled as stored and outputs keep the first input's colour space.
+    #[default(true)]
+    #[label("linear light")]
+    pub linear_light: bool,
 }
 
-fn register_input(name: &str, input: &InputType, registry: &mut SignatureRegistery) {
+fn register_input(
+    name: &str,
+    input: &InputType,
+    sampler: SamplerMeta,
+    registry: &mut SignatureRegistery,
+) {
     let name = name.to_string();
     match input {
         InputType::Float(b) => {
@@ -85,8 +114,12 @@ fn register_input(name: &str, input: &InputType, registry: &mut SignatureRegiste
         }
         InputType::Image(_) => {
             registry
-                .add_input::<crate::TextureHandle>(name)
+                .add_input::<TextureHandle>(name)
                 .default(SPECK)
+                .meta(TextureMeta {
+                    sampler,
+                    ..Default::default()
+                })
                 .build();
         }
         InputType::Point(_) | InputType::Color(_) | InputType::RawBytes(_) => {
@@ -95,108 +128,364 @@ fn register_input(name: &str, input: &InputType, registry: &mut SignatureRegiste
     }
 }
 
-fn register_all_inputs(ctx: &RenderContext, registry: &mut SignatureRegistery) {
-    for (name, input) in ctx.iter_inputs() {
-        register_input(name, input, registry);
+/// Register every shader input, returning the sampling settings of each by slot.
+fn register_all_inputs(
+    ctx: &RenderContext,
+    sampling: &HashMap<String, SamplerMeta>,
+    registry: &mut SignatureRegistery,
+) -> Vec<SamplerMeta> {
+    ctx.iter_inputs()
+        .map(|(name, input)| {
+            let sampler = sampling.get(name).copied().unwrap_or_default();
+            register_input(name, input, sampler, registry);
+            sampler
+        })
+        .collect()
+}
+
+impl OutputFormat {
+    fn resolve(self, default: TextureFormat) -> TextureFormat {
+        match self {
+            OutputFormat::RGBA8 => TextureFormat::RGBAu8,
+            OutputFormat::RGBA16 => TextureFormat::RGBAu16,
+            OutputFormat::RGBAf32 => TextureFormat::RGBAF32,
+            OutputFormat::BGRA8 => TextureFormat::BGRA8,
+            OutputFormat::Default => default,
+        }
     }
 }
 
-pub trait ShaderTemplate: Any + Default + 'static {
-    const SRC: &'static str;
-    const OPERATOR: &'static str;
-    const LABEL: &'static str;
+type CompileResult = std::result::Result<RenderContext, tweak_shader::Error>;
 
-    fn context(&self) -> Option<&RenderContext>;
-    fn context_mut(&mut self) -> Option<&mut RenderContext>;
-    fn set_context(&mut self, ctx: RenderContext);
+/// The compiled pipeline a shader node renders with.
+#[derive(Clone)]
+struct Target {
+    format: TextureFormat,
+    key: PipelineKey,
+    /// Kept to rebuild stateful pipelines on reset
+    source: String,
+    pipeline: SharedPipeline,
+}
 
-    fn match_input_dimensions(&self) -> bool;
-    fn set_match_input_dimensions(&mut self, val: bool);
+/// Expanded source, ready to compile.
+struct Variant {
+    format: TextureFormat,
+    key: PipelineKey,
+    source: String,
 }
 
-impl<T: ShaderTemplate> OperationFactory for T {
-    const LIBRARY: &'static str = "shader";
-    const OPERATOR: &'static str = T::OPERATOR;
-    const LABEL: &'static str = T::LABEL;
+/// A compile running on a worker thread.
+struct PendingCompile {
+    variant: Variant,
+    /// Kept to map errors back to included files
+    pre: Preprocessed,
+    result: mpsc::Receiver<CompileResult>,
+}
 
-    fn build() -> Result<Box<dyn Operation>> {
-        Ok(Box::new(T::default()))
-    }
+/// Compile `variant` on a worker thread, setting `flag` once the result is sent.
+#[cfg(not(target_arch = "wasm32"))]
+fn spawn_compile(
+    variant: &Variant,
+    ctx: &ExecutionContext,
+    flag: DirtyFlag,
+) -> mpsc::Receiver<CompileResult> {
+    let (tx, rx) = mpsc::channel();
+    let (device, queue) = (ctx.device.clone(), ctx.queue.clone());
+    let (source, format) = (variant.source.clone(), variant.format.to_wgpu());
+    std::thread::spawn(move || {
+        let _ = tx.send(RenderContext::new(&source, format, &device, &queue));
+        flag.set();
+    });
+    rx
 }
 
-impl TextureFormat {
-    fn to_wgpu(&self) -> wgpu::TextureFormat {
-        match self {
-            TextureFormat::RGBA8 => wgpu::TextureFormat::Rgba8Unorm,
-            TextureFormat::RGBA16 => wgpu::TextureFormat::Rgba16Unorm,
-            TextureFormat::RGBAf32 => wgpu::TextureFormat::Rgba32Float,
-        }
-    }
+/// There are no threads to compile on, and [ExecutionContext::async_compile]
+/// is always off, so this only runs the compile in place.
+#[cfg(target_arch = "wasm32")]
+fn spawn_compile(
+    variant: &Variant,
+    ctx: &ExecutionContext,
+    flag: DirtyFlag,
+) -> mpsc::Receiver<CompileResult> {
+    let (tx, rx) = mpsc::channel();
+    let format = variant.format.to_wgpu();
+    let _ = tx.send(RenderContext::new(
+        &variant.source,
+        format,
+        &ctx.device,
+        &ctx.queue,
+    ));
+    flag.set();
+    rx
 }
 
-impl<T: ShaderTemplate> Operation for T {
-    fn is_stateful(&self) -> bool {
-        self.context().map(|c| c.is_stateful()).unwrap_or(false)
+/// Compiled shader and per node settings shared by every shader backed operation.
+#[derive(Default)]
+pub struct ShaderState {
+    target: Option<Target>,
+    pending: Option<PendingCompile>,
+    /// Set by the worker thread when a pending compile finishes
+    reconfigure: DirtyFlag,
+    match_input_dimensions: bool,
+    linear_light: bool,
+    watch: Option<FileWatch>,
+    /// Files pulled in by `#include` from the include paths
+    include_watches: Vec<FileWatch>,
+    /// Copies of input textures converted to the node's format and colour space, by input slot
+    converted: Vec<TextureHandle>,
+    /// Settings from `#pragma sampling` in the last preprocessed source, by input name
+    sampling: HashMap<String, SamplerMeta>,
+    /// Sampling settings of the registered inputs, by input slot
+    input_sampling: Vec<SamplerMeta>,
+    /// Int input named by `#pragma engine_seed`, drawn under the engine's seed
+    engine_seed: Option<EngineSeed>,
+    /// Set by [ShaderState::reset], persistent targets are cleared on the next execute
+    reset: bool,
+}
+
+impl ShaderState {
+    pub fn is_stateful(&self) -> bool {
+        self.target
+            .as_ref()
+            .is_some_and(|t| t.pipeline.borrow().is_stateful())
     }
 
-    fn op_path(&self) -> OpPath {
-        <Self as OperationFactory>::op_path()
+    pub fn is_compiling(&self) -> bool {
+        self.pending.is_some()
     }
 
-    fn setup(&mut self, ctx: &mut ExecutionContext, registry: &mut SignatureRegistery) {
-        registry.register_config::<ShaderConfig>();
+    /// Forget what feedback shaders kept from previous frames. Reset has no
+    /// device to hand, so the pipeline is rebuilt on the next execute.
+    pub fn reset(&mut self) {
+        self.reset = true;
+    }
 
-        if let Some(mut slot) = registry.config_by_name::<String>("source") {
-            slot.set_default(T::SRC.to_string());
+    pub fn set_reconfigure_flag(&mut self, flag: DirtyFlag) {
+        self.reconfigure = flag;
+    }
+
+    /// True if the source file or any included file changed since it was last read.
+    pub fn needs_reconfigure(&mut self) -> bool {
+        // Poll every watch so none of them report the same change twice
+        self.include_watches
+            .iter_mut()
+            .chain(self.watch.as_mut())
+            .fold(false, |changed, w| w.changed() | changed)
+    }
+
+    /// Expand includes and sampling directives, ready to compile for `format`.
+    fn variant(
+        &mut self,
+        source: &str,
+        ctx: &ExecutionContext,
+        format: OutputFormat,
+    ) -> Result<(Preprocessed, Variant)> {
+        let mut pre =
+            include::preprocess(source, ctx.shader_include_paths()).map_err(Error::Script)?;
+        self.include_watches = pre.files.iter().map(FileWatch::new).collect();
+        self.sampling =
+            sampling::preprocess(&mut pre.source).map_err(|e| Error::Script(pre.map_error(e)))?;
+        self.engine_seed = engine_seed::preprocess(&mut pre.source)
+            .map_err(|e| Error::Script(pre.map_error(e)))?
+            .map(|seed| seed.map_location(&pre));
+
+        let format = format.resolve(ctx.default_format());
+        if !ctx.supports_format(format) {
+            return Err(Error::UnsupportedTextureFormat(format));
         }
 
-        let render_ctx = match RenderContext::new(
-            T::SRC,
-            wgpu::TextureFormat::Rgba8Unorm,
-            &ctx.device,
-            &ctx.queue,
-        ) {
-            Ok(c) => c,
-            Err(e) => {
-                log::error!("Failed to compile shader: {e}");
-                return;
+        let variant = Variant {
+            key: PipelineKey::new(&pre.source, format.to_wgpu()),
+            format,
+            source: pre.source.clone(),
+        };
+        Ok((pre, variant))
+    }
+
+    /// Fetch the pipeline for `variant` from the cache, compiling it if it's missing.
+    /// Compile errors are mapped back to the file they came from.
+    ///
+    /// Returns None while the compile runs in the background, the current
+    /// target stays in use until the node is flagged for reconfiguration.
+    fn compile(
+        &mut self,
+        pre: Preprocessed,
+        variant: Variant,
+        ctx: &ExecutionContext,
+    ) -> Result<Option<Target>> {
+        let mut cache = ctx.pipelines();
+
+        // Keeping our own pipeline also covers stateful shaders, which the cache won't share
+        let current = |key, source: &str| key == variant.key && source == variant.source;
+        if let Some(target) = self.target.as_ref().filter(|t| current(t.key, &t.source)) {
+            self.pending = None;
+            cache.record_hit();
+            return Ok(Some(target.clone()));
+        }
+
+        let target = |pipeline| Target {
+            format: variant.format,
+            key: variant.key,
+            source: variant.source.clone(),
+            pipeline,
+        };
+        if let Some(pipeline) = cache.get(variant.key, &variant.source) {
+            self.pending = None;
+            return Ok(Some(target(pipeline)));
+        }
+
+        let pending = self
+            .pending
+            .take_if(|p| current(p.variant.key, &p.variant.source));
+        let (pre, result) = match pending {
+            Some(pending) => match pending.result.try_recv() {
+                Ok(result) => (pending.pre, result),
+                Err(TryRecvError::Empty) => {
+                    self.pending = Some(pending);
+                    return Ok(None);
+                }
+                Err(TryRecvError::Disconnected) => {
+                    return Err(Error::Script(ScriptError::new("shader compiler crashed")));
+                }
+            },
+            // Without a target to fall back on the node has no signature, so compile in place
+            None if !ctx.async_compile() || self.target.is_none() => {
+                self.pending = None;
+                let result = RenderContext::new(
+                    &variant.source,
+                    variant.format.to_wgpu(),
+                    &ctx.device,
+                    &ctx.queue,
+                );
+                (pre, result)
+            }
+            None => {
+                // A superseded compile is left to finish and its result dropped
+                let result = spawn_compile(&variant, ctx, self.reconfigure.clone());
+                self.pending = Some(PendingCompile {
+                    variant,
+                    pre,
+                    result,
+                });
+                return Ok(None);
             }
         };
 
-        register_all_inputs(&render_ctx, registry);
-        self.set_context(render_ctx);
+        match result {
+            Ok(render_ctx) => {
+                let pipeline = cache.insert(variant.key, variant.source.clone(), render_ctx);
+                Ok(Some(target(pipeline)))
+            }
+            Err(e) => Err(Error::Script(
+                pre.map_error(ScriptError::from_tweak_shader(e)),
+            )),
+        }
+    }
+
+    /// Resolve the source to compile, reading and watching the file if one is set.
+    /// The watch is kept even if reading fails so that fixing the file reloads it.
+    fn load_source(&mut self, cfg: &ShaderConfig) -> Result<String> {
+        if cfg.path.is_empty() {
+            self.watch = None;
+            return Ok(cfg.source.clone());
+        }
+
+        let path = Path::new(&cfg.path);
+        if self.watch.as_ref().is_none_or(|w| w.path() != path) {
+            self.watch = Some(FileWatch::new(path));
+        }
+
+        std::fs::read_to_string(path).map_err(|source| Error::ReadFile {
+            path: path.to_path_buf(),
+            source,
+        })
+    }
+
+    /// Fail if `#pragma engine_seed` names something other than an int input of `target`.
+    fn check_engine_seed(&self, target: Option<Target>) -> Result<Option<Target>> {
+        if let (Some(seed), Some(target)) = (&self.engine_seed, &target) {
+            seed.check(&target.pipeline.borrow())
+                .map_err(Error::Script)?;
+        }
+        Ok(target)
+    }
 
+    fn register_output(
+        &self,
+        registry: &mut SignatureRegistery,
+        width: u32,
+        height: u32,
+        preview: bool,
+    ) {
+        let Some(target) = &self.target else {
+            return;
+        };
+        registry.clear_outputs();
         registry
-            .add_output::<crate::TextureHandle>("output")
+            .add_output::<TextureHandle>("output")
+            .dimensions(width, height)
+            .format(target.format)
             .meta(TextureMeta {
-                preview: true,
+                preview,
                 allow_file: false,
+                ..Default::default()
             })
-            .dimensions(512, 512)
             .build();
     }
 
-    fn configure(
+    /// Registers the shader config with `src` as the default source and compiles it.
+    pub fn setup(
+        &mut self,
+        src: &str,
+        ctx: &mut ExecutionContext,
+        registry: &mut SignatureRegistery,
+    ) {
+        registry.register_config::<ShaderConfig>();
+
+        if let Some(mut slot) = registry.config_by_name::<String>("source") {
+            slot.set_default(src.to_string());
+        }
+
+        let compiled = self
+            .variant(src, ctx, OutputFormat::default())
+            .and_then(|(pre, variant)| self.compile(pre, variant, ctx));
+
+        let target = match compiled.and_then(|t| self.check_engine_seed(t)) {
+            Ok(Some(target)) => target,
+            // Setup has no target yet so it compiles in place, but if the compile
+            // is ever deferred there's nothing to register until it lands
+            Ok(None) => {
+                log::error!("Shader compile was deferred during setup");
+                return;
+            }
+            Err(e) => {
+                log::error!("Failed to compile shader: {e}");
+                return;
+            }
+        };
+
+        self.input_sampling =
+            register_all_inputs(&target.pipeline.borrow(), &self.sampling, registry);
+        self.target = Some(target);
+        self.register_output(registry, 512, 512, true);
+    }
+
+    pub fn configure(
         &mut self,
         ctx: &ExecutionContext,
         config: crate::value::Config,
         registry: &mut SignatureRegistery,
     ) -> Result<()> {
         let cfg = ShaderConfig::try_extract(config)?;
-        let format = cfg.format.to_wgpu();
         let width = cfg.width as u32;
         let height = cfg.height as u32;
 
-        self.set_match_input_dimensions(cfg.match_input_dimensions);
-
-        let render_ctx = RenderContext::new(&cfg.source, format, &ctx.device, &ctx.queue)
-            .map_err(|e| Error::Script(ScriptError::from_tweak_shader(e)))?;
+        self.match_input_dimensions = cfg.match_input_dimensions;
+        self.linear_light = cfg.linear_light;
 
-        registry.clear_inputs();
-        register_all_inputs(&render_ctx, registry);
-        self.set_context(render_ctx);
-
-        registry.clear_outputs();
+        if let Some(mut slot) = registry.config_by_name::<String>("source") {
+            slot.set_visible(cfg.path.is_empty());
+        }
 
         if let Some(mut slot) = registry.config_by_name::<i32>("width") {
             slot.set_visible(!cfg.match_input_dimensions);
@@ -206,31 +495,33 @@ impl<T: ShaderTemplate> Operation for T {
             slot.set_visible(!cfg.match_input_dimensions);
         }
 
-        registry
-            .add_output::<crate::TextureHandle>("output")
-            .dimensions(width, height)
-            .meta(TextureMeta {
-                preview: cfg.preview,
-                allow_file: false,
-            })
-            .build();
+        // On failure the previous pipeline and signature are kept
+        let source = self.load_source(&cfg)?;
+        let (pre, variant) = self.variant(&source, ctx, cfg.format)?;
+
+        // While compiling in the background the current inputs and output stay registered
+        let compiled = self.compile(pre, variant, ctx)?;
+        if let Some(target) = self.check_engine_seed(compiled)? {
+            registry.clear_inputs();
+            self.input_sampling =
+                register_all_inputs(&target.pipeline.borrow(), &self.sampling, registry);
+            self.target = Some(target);
+        }
+
+        self.register_output(registry, width, height, cfg.preview);
 
         Ok(())
     }
 
-    fn execute(
+    pub fn execute(
         &mut self,
         ctx: &mut ExecutionContext,
         inputs: Inputs,
         mut outputs: Outputs,
     ) -> Result<()> {
-        let match_dims = self.match_input_dimensions();
-        let Some(render_ctx) = self.context_mut() else {
-            return Ok(());
-        };
-
         // Find first texture input dimensions if matching is enabled
-        let first_texture_dims = match_dims
+        let first_texture_dims = self
+            .match_input_dimensions
             .then(|| {
                 inputs.iter().find_map(|input| match input {
                     crate::ValueRef::Texture(h) => Some((h.width(), h.height())),
@@ -239,59 +530,77 @@ impl<T: ShaderTemplate> Operation for T {
             })
             .flatten();
 
-        // Copy input values to shader uniforms
-        let input_names: Vec<_> = render_ctx.iter_inputs().map(|(n, _)| n.clone()).collect();
+        let first_color_space = inputs.iter().find_map(|input| match input {
+            crate::ValueRef::Texture(h) => Some(h.color_space()),
+            _ => None,
+        });
+        let output_color_space = match self.linear_light {
+            true => ColorSpace::Linear,
+            false => first_color_space.unwrap_or_default(),
+        };
+
+        // Inputs are sampled in the node's format and, with linear light on,
+        // as linear light. Anything else is converted first, as are inputs
+        // that want a mip chain
+        let Some(target) = &self.target else {
+            return Ok(());
+        };
+        let mut bound = vec![None; inputs.len()];
+        self.converted
+            .resize(inputs.len(), TextureHandle::default());
         for (i, input) in inputs.iter().enumerate() {
-            let Some(name) = input_names.get(i) else {
+            let crate::ValueRef::Texture(handle) = input else {
                 continue;
             };
-            let Some(mut uniform) = render_ctx.get_input_mut(name) else {
-                continue;
+            let color_space = match self.linear_light {
+                true => ColorSpace::Linear,
+                false => handle.color_space,
             };
-
-            match input {
-                crate::ValueRef::F32(v) => {
-                    if let Some(f) = uniform.as_float() {
-                        f.current = **v;
-                    }
-                }
-                crate::ValueRef::I32(v) => {
-                    if let Some(i) = uniform.as_int() {
-                        i.value.current = **v;
-                    }
-                }
-                crate::ValueRef::Bool(v) => {
-                    if let Some(b) = uniform.as_bool() {
-                        b.current = if **v {
-                            tweak_shader::input_type::ShaderBool::True
-                        } else {
-                            tweak_shader::input_type::ShaderBool::False
-                        };
-                    }
-                }
-                crate::ValueRef::Texture(handle) => {
-                    if let Some(texture) = ctx.texture(handle) {
-                        render_ctx.load_shared_texture(texture, name);
-                    }
-                }
-                _ => log::error!("Unsupported input type"),
+            let mipmaps = self.input_sampling.get(i).is_some_and(|s| s.mipmaps);
+            if handle.fmt != target.format || handle.color_space != color_space || mipmaps {
+                let converted = &mut self.converted[i];
+                converted.fmt = target.format;
+                converted.color_space = color_space;
+                converted.mipmaps = mipmaps;
+                ctx.convert_texture(handle, converted);
+                ctx.generate_mipmaps(converted);
+                bound[i] = Some(*converted);
             }
         }
 
-        let output_handle: &mut crate::TextureHandle = outputs.extract(0)?;
-
+        let mut render_ctx = target.pipeline.borrow_mut();
+        // Stateful pipelines aren't shared, rebuilding one only clears this node's targets
+        if std::mem::take(&mut self.reset) && render_ctx.is_stateful() {
+            *render_ctx = RenderContext::new(
+                &target.source,
+                target.format.to_wgpu(),
+                &ctx.device,
+                &ctx.queue,
+            )
+            .map_err(|e| Error::Script(ScriptError::from_tweak_shader(e)))?;
+        }
+        upload_inputs(
+            &mut render_ctx,
+            ctx,
+            &inputs,
+            &bound,
+            self.engine_seed.as_ref().map(|s| s.name.as_str()),
+        )?;
+
+        let output_handle: &mut TextureHandle = outputs.extract(0)?;
         if let Some((w, h)) = first_texture_dims {
             output_handle.width = w;
             output_handle.height = h;
         }
+        output_handle.color_space = output_color_space;
 
         ctx.ensure_texture(output_handle);
         let Some(texture) = ctx.texture(output_handle) else {
             return Ok(());
         };
 
-        let view = texture.create_view(&Default::default());
         let mut encoder = ctx.device.create_command_encoder(&Default::default());
+        let view = mip_view(texture, 0);
         render_ctx.render(
             &ctx.queue,
             &ctx.device,
@@ -301,7 +610,140 @@ impl<T: ShaderTemplate> Operation for T {
             output_handle.height(),
         );
         ctx.queue.submit(Some(encoder.finish()));
+        ctx.generate_mipmaps(output_handle);
 
         Ok(())
     }
 }
+
+/// Copy input values to shader uniforms and bind input textures, preferring
+/// the converted copy of a texture in `bound` where there is one. Textures
+/// that can't be found bind the speck, stale handles are an error. The `engine_seed` input
+/// is drawn under the engine's seed.
+fn upload_inputs(
+    render_ctx: &mut RenderContext,
+    ctx: &ExecutionContext,
+    inputs: &Inputs,
+    bound: &[Option<TextureHandle>],
+    engine_seed: Option<&str>,
+) -> Result<()> {
+    let input_names: Vec<_> = render_ctx.iter_inputs().map(|(n, _)| n.clone()).collect();
+    for (i, input) in inputs.iter().enumerate() {
+        let Some(name) = input_names.get(i) else {
+            continue;
+        };
+        let Some(mut uniform) = render_ctx.get_input_mut(name) else {
+            continue;
+        };
+
+        match input {
+            crate::ValueRef::F32(v) => {
+                if let Some(f) = uniform.as_float() {
+                    f.current = **v;
+                }
+            }
+            // Picks a stream under the engine's seed like the random operators
+            // do, staying in the input's declared range
+            crate::ValueRef::I32(v) if engine_seed == Some(name.as_str()) => {
+                if let Some(i) = uniform.as_int() {
+                    let (min, max) = (i.value.min, i.value.max);
+                    i.value.current = ctx.rng(**v as u32 as u64).range_i32(min, max);
+                }
+            }
+            crate::ValueRef::I32(v) => {
+                if let Some(i) = uniform.as_int() {
+                    i.value.current = **v;
+                }
+            }
+            crate::ValueRef::Bool(v) => {
+                if let Some(b) = uniform.as_bool() {
+                    b.current = if **v {
+                        tweak_shader::input_type::ShaderBool::True
+                    } else {
+                        tweak_shader::input_type::ShaderBool::False
+                    };
+                }
+            }
+            crate::ValueRef::Texture(handle) => {
+                let handle = bound.get(i).and_then(Option::as_ref).unwrap_or(handle);
+                // The pipeline may be shared, so a missing texture binds the speck
+                // rather than leaving the last node's texture in place
+                let texture = match ctx.try_texture(handle) {
+                    Ok(texture) => texture,
+                    Err(e @ Error::StaleTexture { .. }) => return Err(e),
+                    Err(_) => ctx.try_texture(&SPECK)?,
+                };
+                render_ctx.load_shared_texture(texture, name);
+            }
+            _ => log::error!("Unsupported input type"),
+        }
+    }
+    Ok(())
+}
+
+pub trait ShaderTemplate: Any + Default + 'static {
+    const SRC: &'static str;
+    const OPERATOR: &'static str;
+    const LABEL: &'static str;
+
+    fn state(&self) -> &ShaderState;
+    fn state_mut(&mut self) -> &mut ShaderState;
+}
+
+impl<T: ShaderTemplate> OperationFactory for T {
+    const LIBRARY: &'static str = "shader";
+    const OPERATOR: &'static str = T::OPERATOR;
+    const LABEL: &'static str = T::LABEL;
+
+    fn build() -> Result<Box<dyn Operation>> {
+        Ok(Box::new(T::default()))
+    }
+}
+
+impl<T: ShaderTemplate> Operation for T {
+    fn is_stateful(&self) -> bool {
+        self.state().is_stateful()
+    }
+
+    fn needs_reconfigure(&mut self) -> bool {
+        self.state_mut().needs_reconfigure()
+    }
+
+    fn set_reconfigure_flag(&mut self, flag: DirtyFlag) {
+        self.state_mut().set_reconfigure_flag(flag);
+    }
+
+    fn is_compiling(&self) -> bool {
+        self.state().is_compiling()
+    }
+
+    fn reset(&mut self) {
+        self.state_mut().reset();
+    }
+
+    fn op_path(&self) -> OpPath {
+        <Self as OperationFactory>::op_path()
+    }
+
+    fn setup(&mut self, ctx: &mut ExecutionContext, registry: &mut SignatureRegistery) {
+        self.state_mut().setup(T::SRC, ctx, registry);
+    }
+
+    fn configure(
+        &mut self,
+        ctx: &ExecutionContext,
+        config: crate::value::Config,
+        registry: &mut SignatureRegistery,
+    ) -> Result<()> {
+        self.state_mut().configure(ctx, config, registry)
+    }
+
+    fn execute(
+        &mut self,
+        ctx: &mut ExecutionContext,
+        inputs: Inputs,
+        outputs: Outputs,
+    ) -> Result<()> {
+        self.state_mut().execute(ctx, inputs, outputs)
+    }
+}
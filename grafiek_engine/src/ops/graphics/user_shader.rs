@@ -0,0 +1,83 @@
+use super::tweak_shader_template::ShaderState;
+use crate::error::Result;
+use crate::registry::SignatureRegistery;
+use crate::traits::{OpPath, Operation};
+use crate::value::{Config, Inputs, Outputs};
+use crate::{DirtyFlag, ExecutionContext};
+
+/// Library user shader operators are registered under.
+pub const USER_LIBRARY: &str = "user";
+
+/// A shader operator loaded from the user library at runtime rather than
+/// compiled into the engine. Behaves exactly like the built in shader nodes,
+/// with its default source coming from disk.
+pub struct UserShader {
+    name: String,
+    source: String,
+    state: ShaderState,
+}
+
+impl UserShader {
+    pub fn new(name: impl Into<String>, source: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            source: source.into(),
+            state: ShaderState::default(),
+        }
+    }
+
+    pub fn source(&self) -> &str {
+        &self.source
+    }
+}
+
+impl Operation for UserShader {
+    fn is_stateful(&self) -> bool {
+        self.state.is_stateful()
+    }
+
+    fn needs_reconfigure(&mut self) -> bool {
+        self.state.needs_reconfigure()
+    }
+
+    fn set_reconfigure_flag(&mut self, flag: DirtyFlag) {
+        self.state.set_reconfigure_flag(flag);
+    }
+
+    fn is_compiling(&self) -> bool {
+        self.state.is_compiling()
+    }
+
+    fn reset(&mut self) {
+        self.state.reset();
+    }
+
+    fn op_path(&self) -> OpPath {
+        OpPath {
+            library: USER_LIBRARY.to_owned(),
+            operator: self.name.clone(),
+        }
+    }
+
+    fn setup(&mut self, ctx: &mut ExecutionContext, registry: &mut SignatureRegistery) {
+        self.state.setup(&self.source, ctx, registry);
+    }
+
+    fn configure(
+        &mut self,
+        ctx: &ExecutionContext,
+        config: Config,
+        registry: &mut SignatureRegistery,
+    ) -> Result<()> {
+        self.state.configure(ctx, config, registry)
+    }
+
+    fn execute(
+        &mut self,
+        ctx: &mut ExecutionContext,
+        inputs: Inputs,
+        outputs: Outputs,
+    ) -> Result<()> {
+        self.state.execute(ctx, inputs, outputs)
+    }
+}
@@ -0,0 +1,109 @@
+use crate::ConfigSchema;
+use crate::EnumSchema;
+use crate::ExecutionContext;
+use crate::error::Result;
+use crate::registry::{FloatRange, SignatureRegistery};
+use crate::traits::{OpPath, Operation, OperationFactory};
+use crate::value::{Config, Inputs, InputsExt, Outputs, OutputsExt};
+
+const F32_META: FloatRange = FloatRange {
+    min: f32::MIN,
+    max: f32::MAX,
+    step: 0.1,
+};
+
+pub struct Compare {
+    pub operation: CompareOp,
+    pub epsilon: f32,
+}
+
+#[derive(EnumSchema, Default, Copy, Clone)]
+pub enum CompareOp {
+    #[default]
+    Equal = 0,
+    NotEqual,
+    Less,
+    LessEqual,
+    Greater,
+    GreaterEqual,
+}
+
+#[derive(ConfigSchema)]
+struct CompareConfig {
+    #[label("")]
+    #[on_node_body]
+    operation: CompareOp,
+
+    /// Values closer than this count as equal
+    #[meta(FloatRange { min: 0.0, max: 1.0, step: 0.0001 })]
+    #[default(1e-5)]
+    epsilon: f32,
+}
+
+impl CompareOp {
+    pub fn apply(self, a: f32, b: f32, epsilon: f32) -> bool {
+        let equal = (a - b).abs() <= epsilon;
+        match self {
+            CompareOp::Equal => equal,
+            CompareOp::NotEqual => !equal,
+            CompareOp::Less => a < b && !equal,
+            CompareOp::LessEqual => a < b || equal,
+            CompareOp::Greater => a > b && !equal,
+            CompareOp::GreaterEqual => a > b || equal,
+        }
+    }
+}
+
+impl Operation for Compare {
+    fn is_stateful(&self) -> bool {
+        false
+    }
+
+    fn op_path(&self) -> OpPath {
+        <Self as OperationFactory>::op_path()
+    }
+
+    fn setup(&mut self, _ctx: &mut ExecutionContext, registry: &mut SignatureRegistery) {
+        registry.add_input::<f32>("a").meta(F32_META).build();
+        registry.add_input::<f32>("b").meta(F32_META).build();
+        registry.add_output::<bool>("result").build();
+        registry.register_config::<CompareConfig>();
+    }
+
+    fn configure(
+        &mut self,
+        _ctx: &ExecutionContext,
+        config: Config,
+        _registry: &mut SignatureRegistery,
+    ) -> Result<()> {
+        let cfg = CompareConfig::try_extract(config)?;
+        self.operation = cfg.operation;
+        self.epsilon = cfg.epsilon.max(0.0);
+        Ok(())
+    }
+
+    fn execute(
+        &mut self,
+        _ctx: &mut ExecutionContext,
+        inputs: Inputs,
+        mut outputs: Outputs,
+    ) -> Result<()> {
+        let a: f32 = inputs.extract(0)?;
+        let b: f32 = inputs.extract(1)?;
+        *outputs.extract::<bool>(0)? = self.operation.apply(a, b, self.epsilon);
+        Ok(())
+    }
+}
+
+impl OperationFactory for Compare {
+    const LIBRARY: &'static str = "logic";
+    const OPERATOR: &'static str = "compare";
+    const LABEL: &'static str = "Compare";
+
+    fn build() -> Result<Box<dyn Operation>> {
+        Ok(Box::new(Compare {
+            operation: CompareOp::Equal,
+            epsilon: 1e-5,
+        }))
+    }
+}
@@ -0,0 +1,91 @@
+use crate::ConfigSchema;
+use crate::EnumSchema;
+use crate::ExecutionContext;
+use crate::error::Result;
+use crate::registry::SignatureRegistery;
+use crate::traits::{OpPath, Operation, OperationFactory};
+use crate::value::{Config, Inputs, InputsExt, Outputs, OutputsExt};
+
+pub struct Gate {
+    pub operation: GateOp,
+}
+
+#[derive(EnumSchema, Default, Copy, Clone)]
+pub enum GateOp {
+    #[default]
+    And = 0,
+    Or,
+    Xor,
+    Not,
+}
+
+#[derive(ConfigSchema)]
+struct GateConfig {
+    #[label("")]
+    #[on_node_body]
+    operation: GateOp,
+}
+
+impl Operation for Gate {
+    fn is_stateful(&self) -> bool {
+        false
+    }
+
+    fn op_path(&self) -> OpPath {
+        <Self as OperationFactory>::op_path()
+    }
+
+    fn setup(&mut self, _ctx: &mut ExecutionContext, registry: &mut SignatureRegistery) {
+        registry.add_input::<bool>("a").build();
+        registry.add_input::<bool>("b").build();
+        registry.add_output::<bool>("result").build();
+        registry.register_config::<GateConfig>();
+    }
+
+    fn configure(
+        &mut self,
+        _ctx: &ExecutionContext,
+        config: Config,
+        registry: &mut SignatureRegistery,
+    ) -> Result<()> {
+        let cfg = GateConfig::try_extract(config)?;
+        self.operation = cfg.operation;
+
+        registry.clear_inputs();
+        registry.add_input::<bool>("a").build();
+        if !matches!(cfg.operation, GateOp::Not) {
+            registry.add_input::<bool>("b").build();
+        }
+
+        Ok(())
+    }
+
+    fn execute(
+        &mut self,
+        _ctx: &mut ExecutionContext,
+        inputs: Inputs,
+        mut outputs: Outputs,
+    ) -> Result<()> {
+        let a: bool = inputs.extract(0)?;
+        let result = match self.operation {
+            GateOp::And => a && inputs.extract::<bool>(1)?,
+            GateOp::Or => a || inputs.extract::<bool>(1)?,
+            GateOp::Xor => a != inputs.extract::<bool>(1)?,
+            GateOp::Not => !a,
+        };
+        *outputs.extract::<bool>(0)? = result;
+        Ok(())
+    }
+}
+
+impl OperationFactory for Gate {
+    const LIBRARY: &'static str = "logic";
+    const OPERATOR: &'static str = "gate";
+    const LABEL: &'static str = "Gate";
+
+    fn build() -> Result<Box<dyn Operation>> {
+        Ok(Box::new(Gate {
+            operation: GateOp::And,
+        }))
+    }
+}
@@ -0,0 +1,7 @@
+pub mod compare;
+pub mod gate;
+pub mod switch;
+
+pub use compare::{Compare, CompareOp};
+pub use gate::{Gate, GateOp};
+pub use switch::{Switch, SwitchMode};
@@ -0,0 +1,243 @@
+use std::borrow::Cow;
+use std::ops::Range;
+
+use crate::error::Result;
+use crate::registry::{IntRange, SignatureRegistery, TextureMeta};
+use crate::traits::{OpPath, Operation, OperationFactory};
+use crate::value::{Config, Inputs, InputsExt, Outputs};
+use crate::{
+    ConfigSchema, EnumSchema, ExecutionContext, ExtendedMetadata, SlotDef, ValueMut, ValueRef,
+    ValueType,
+};
+
+/// How a [Switch] picks its input.
+#[derive(EnumSchema, Default, Copy, Clone, PartialEq)]
+pub enum SwitchMode {
+    /// An index picks between any number of inputs
+    #[default]
+    Index = 0,
+    /// A bool picks between a `false` and a `true` input
+    Bool,
+}
+
+#[derive(ConfigSchema)]
+struct SwitchConfig {
+    #[on_node_body]
+    mode: SwitchMode,
+
+    /// Number of inputs to pick between in index mode
+    #[meta(IntRange { min: 2, max: 16, step: 1 })]
+    #[default(2)]
+    #[label("inputs")]
+    count: i32,
+}
+
+/// Value inputs follow the selector input.
+const FIRST_VALUE: usize = 1;
+
+/// Passes on one of its inputs, picked by an index or a bool. The inputs and
+/// output take the type of the first value connected to them.
+pub struct Switch {
+    mode: SwitchMode,
+    count: usize,
+    value_type: ValueType,
+    /// Which inputs have an edge, by input slot
+    connected: Vec<bool>,
+}
+
+impl Default for Switch {
+    fn default() -> Self {
+        Self {
+            mode: SwitchMode::Index,
+            count: 2,
+            value_type: ValueType::Any,
+            connected: Vec::new(),
+        }
+    }
+}
+
+impl Switch {
+    fn value_slot(&self, name: impl Into<Cow<'static, str>>) -> SlotDef {
+        let extended = match self.value_type {
+            ValueType::Texture => ExtendedMetadata::Texture(TextureMeta {
+                preview: true,
+                allow_file: false,
+                ..Default::default()
+            }),
+            _ => ExtendedMetadata::None,
+        };
+        SlotDef {
+            value_type: self.value_type,
+            name: name.into(),
+            extended,
+            ..Default::default()
+        }
+    }
+
+    fn register(&self, registry: &mut SignatureRegistery) {
+        registry.clear_inputs();
+        match self.mode {
+            SwitchMode::Index => registry
+                .add_input::<i32>("index")
+                .meta(IntRange {
+                    min: 0,
+                    max: self.count as i32 - 1,
+                    step: 1,
+                })
+                .build(),
+            SwitchMode::Bool => registry.add_input::<bool>("condition").build(),
+        }
+        match self.mode {
+            SwitchMode::Index => {
+                for i in 0..self.count {
+                    registry.push_input_raw(self.value_slot(i.to_string()));
+                }
+            }
+            SwitchMode::Bool => {
+                registry.push_input_raw(self.value_slot("false"));
+                registry.push_input_raw(self.value_slot("true"));
+            }
+        }
+
+        registry.clear_outputs();
+        registry.push_output_raw(self.value_slot("value"));
+    }
+
+    /// Number of inputs to pick between.
+    fn choices(&self) -> usize {
+        match self.mode {
+            SwitchMode::Index => self.count,
+            SwitchMode::Bool => 2,
+        }
+    }
+
+    /// The input slot picked by the selector.
+    fn selected(&self, inputs: &Inputs) -> Result<usize> {
+        let choice = match self.mode {
+            SwitchMode::Index => {
+                let index: i32 = inputs.extract(0)?;
+                index.clamp(0, self.choices() as i32 - 1) as usize
+            }
+            SwitchMode::Bool => inputs.extract::<bool>(0)? as usize,
+        };
+        Ok(FIRST_VALUE + choice)
+    }
+}
+
+impl Operation for Switch {
+    fn is_stateful(&self) -> bool {
+        false
+    }
+
+    fn op_path(&self) -> OpPath {
+        <Self as OperationFactory>::op_path()
+    }
+
+    fn condition_inputs(&self) -> Range<usize> {
+        0..FIRST_VALUE
+    }
+
+    fn selected_inputs(&self, inputs: Inputs) -> Option<Vec<usize>> {
+        self.selected(&inputs).ok().map(|slot| vec![slot])
+    }
+
+    fn setup(&mut self, _ctx: &mut ExecutionContext, registry: &mut SignatureRegistery) {
+        self.register(registry);
+        registry.register_config::<SwitchConfig>();
+    }
+
+    fn configure(
+        &mut self,
+        _ctx: &ExecutionContext,
+        config: Config,
+        registry: &mut SignatureRegistery,
+    ) -> Result<()> {
+        let cfg = SwitchConfig::try_extract(config)?;
+        self.mode = cfg.mode;
+        self.count = cfg.count.clamp(2, 16) as usize;
+
+        // Edges to inputs that are removed get disconnected by the engine
+        self.connected.resize(FIRST_VALUE + self.choices(), false);
+        self.register(registry);
+        Ok(())
+    }
+
+    fn on_edge_connected(
+        &mut self,
+        slot: usize,
+        connected_type: ValueType,
+        registry: &mut SignatureRegistery,
+    ) -> Result<()> {
+        if slot < FIRST_VALUE {
+            return Ok(());
+        }
+        if let Some(connected) = self.connected.get_mut(slot) {
+            *connected = true;
+        }
+
+        if self.value_type == ValueType::Any && connected_type != ValueType::Any {
+            self.value_type = connected_type;
+            self.register(registry);
+        }
+        Ok(())
+    }
+
+    fn on_edge_disconnected(
+        &mut self,
+        slot: usize,
+        _connected_type: ValueType,
+        registry: &mut SignatureRegistery,
+    ) -> Result<()> {
+        if slot < FIRST_VALUE {
+            return Ok(());
+        }
+        if let Some(connected) = self.connected.get_mut(slot) {
+            *connected = false;
+        }
+
+        // With nothing left connected the switch takes any type again
+        if self.value_type != ValueType::Any && !self.connected.contains(&true) {
+            self.value_type = ValueType::Any;
+            self.register(registry);
+        }
+        Ok(())
+    }
+
+    fn execute(
+        &mut self,
+        ctx: &mut ExecutionContext,
+        inputs: Inputs,
+        mut outputs: Outputs,
+    ) -> Result<()> {
+        let slot = self.selected(&inputs)?;
+        let (Some(input), Some(output)) = (inputs.get(slot), outputs.first_mut()) else {
+            return Ok(());
+        };
+
+        match (output, input) {
+            // Copied so the output texture stays owned by this node
+            (ValueMut::Texture(out), ValueRef::Texture(src)) if src.id.is_some() => {
+                out.fmt = src.fmt;
+                out.color_space = src.color_space;
+                ctx.convert_texture(src, out);
+            }
+            (ValueMut::F32(out), ValueRef::F32(v)) => **out = **v,
+            (ValueMut::I32(out), ValueRef::I32(v)) => **out = **v,
+            (ValueMut::Bool(out), ValueRef::Bool(v)) => **out = **v,
+            (ValueMut::String(out), ValueRef::String(v)) => (*v).clone_into(out),
+            (ValueMut::Buffer(out), ValueRef::Buffer(v)) => **out = **v,
+            _ => {}
+        }
+        Ok(())
+    }
+}
+
+impl OperationFactory for Switch {
+    const LIBRARY: &'static str = "logic";
+    const OPERATOR: &'static str = "switch";
+    const LABEL: &'static str = "Switch";
+
+    fn build() -> Result<Box<dyn Operation>> {
+        Ok(Box::new(Switch::default()))
+    }
+}
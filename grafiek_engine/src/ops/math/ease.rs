@@ -0,0 +1,163 @@
+use std::f32::consts::{FRAC_PI_2, TAU};
+
+use crate::ConfigSchema;
+use crate::EnumSchema;
+use crate::ExecutionContext;
+use crate::error::Result;
+use crate::registry::{FloatRange, SignatureRegistery};
+use crate::traits::{OpPath, Operation, OperationFactory};
+use crate::value::{Config, Inputs, InputsExt, Outputs, OutputsExt};
+
+const UNIT_META: FloatRange = FloatRange {
+    min: 0.0,
+    max: 1.0,
+    step: 0.01,
+};
+
+/// Shapes a `0..1` progress value with one of the standard easing curves.
+#[derive(Default)]
+pub struct Ease {
+    pub curve: EaseCurve,
+    pub direction: EaseDirection,
+}
+
+#[derive(EnumSchema, Default, Copy, Clone)]
+pub enum EaseCurve {
+    #[default]
+    Linear = 0,
+    Sine,
+    Quad,
+    Cubic,
+    Quart,
+    Expo,
+    Circ,
+    /// Overshoots backwards before moving
+    Back,
+    /// Springs past the end a few times
+    Elastic,
+    Bounce,
+}
+
+/// Which end of the motion the curve eases.
+#[derive(EnumSchema, Default, Copy, Clone)]
+pub enum EaseDirection {
+    /// Starts slow
+    #[default]
+    In = 0,
+    /// Ends slow
+    Out,
+    /// Starts and ends slow
+    InOut,
+}
+
+#[derive(ConfigSchema)]
+struct EaseConfig {
+    #[label("")]
+    #[on_node_body]
+    curve: EaseCurve,
+
+    #[on_node_body]
+    direction: EaseDirection,
+}
+
+impl EaseCurve {
+    /// The ease in form of the curve, for `t` in `0..1`.
+    fn ease_in(self, t: f32) -> f32 {
+        match self {
+            EaseCurve::Linear => t,
+            EaseCurve::Sine => 1.0 - (t * FRAC_PI_2).cos(),
+            EaseCurve::Quad => t * t,
+            EaseCurve::Cubic => t * t * t,
+            EaseCurve::Quart => t * t * t * t,
+            EaseCurve::Expo if t == 0.0 => 0.0,
+            EaseCurve::Expo => 2f32.powf(10.0 * t - 10.0),
+            EaseCurve::Circ => 1.0 - (1.0 - t * t).sqrt(),
+            EaseCurve::Back => {
+                const C1: f32 = 1.70158;
+                (C1 + 1.0) * t * t * t - C1 * t * t
+            }
+            EaseCurve::Elastic if t == 0.0 || t == 1.0 => t,
+            EaseCurve::Elastic => {
+                -(2f32.powf(10.0 * t - 10.0)) * ((t * 10.0 - 10.75) * TAU / 3.0).sin()
+            }
+            EaseCurve::Bounce => 1.0 - bounce_out(1.0 - t),
+        }
+    }
+
+    /// Evaluate the curve at `t`, clamped to `0..1`.
+    pub fn apply(self, direction: EaseDirection, t: f32) -> f32 {
+        let t = t.clamp(0.0, 1.0);
+        match direction {
+            EaseDirection::In => self.ease_in(t),
+            EaseDirection::Out => 1.0 - self.ease_in(1.0 - t),
+            EaseDirection::InOut if t < 0.5 => self.ease_in(2.0 * t) / 2.0,
+            EaseDirection::InOut => 1.0 - self.ease_in(2.0 - 2.0 * t) / 2.0,
+        }
+    }
+}
+
+fn bounce_out(t: f32) -> f32 {
+    const N1: f32 = 7.5625;
+    const D1: f32 = 2.75;
+    if t < 1.0 / D1 {
+        N1 * t * t
+    } else if t < 2.0 / D1 {
+        let t = t - 1.5 / D1;
+        N1 * t * t + 0.75
+    } else if t < 2.5 / D1 {
+        let t = t - 2.25 / D1;
+        N1 * t * t + 0.9375
+    } else {
+        let t = t - 2.625 / D1;
+        N1 * t * t + 0.984375
+    }
+}
+
+impl Operation for Ease {
+    fn is_stateful(&self) -> bool {
+        false
+    }
+
+    fn op_path(&self) -> OpPath {
+        <Self as OperationFactory>::op_path()
+    }
+
+    fn setup(&mut self, _ctx: &mut ExecutionContext, registry: &mut SignatureRegistery) {
+        registry.add_input::<f32>("t").meta(UNIT_META).build();
+        registry.add_output::<f32>("result").build();
+        registry.register_config::<EaseConfig>();
+    }
+
+    fn configure(
+        &mut self,
+        _ctx: &ExecutionContext,
+        config: Config,
+        _registry: &mut SignatureRegistery,
+    ) -> Result<()> {
+        let cfg = EaseConfig::try_extract(config)?;
+        self.curve = cfg.curve;
+        self.direction = cfg.direction;
+        Ok(())
+    }
+
+    fn execute(
+        &mut self,
+        _ctx: &mut ExecutionContext,
+        inputs: Inputs,
+        mut outputs: Outputs,
+    ) -> Result<()> {
+        let t: f32 = inputs.extract(0)?;
+        *outputs.extract::<f32>(0)? = self.curve.apply(self.direction, t);
+        Ok(())
+    }
+}
+
+impl OperationFactory for Ease {
+    const LIBRARY: &'static str = "math";
+    const OPERATOR: &'static str = "ease";
+    const LABEL: &'static str = "Ease";
+
+    fn build() -> Result<Box<dyn Operation>> {
+        Ok(Box::new(Ease::default()))
+    }
+}
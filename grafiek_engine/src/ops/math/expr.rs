@@ -0,0 +1,990 @@
+use std::f32::consts::{PI, TAU};
+
+use crate::error::{LocatedError, ScriptError};
+
+/// A value an expression computes with.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Scalar {
+    F32(f32),
+    I32(i32),
+    Bool(bool),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ScalarType {
+    F32,
+    I32,
+    Bool,
+}
+
+impl ScalarType {
+    fn name(self) -> &'static str {
+        match self {
+            ScalarType::F32 => "float",
+            ScalarType::I32 => "int",
+            ScalarType::Bool => "bool",
+        }
+    }
+
+    fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "float" => Some(ScalarType::F32),
+            "int" => Some(ScalarType::I32),
+            "bool" => Some(ScalarType::Bool),
+            _ => None,
+        }
+    }
+
+    fn is_numeric(self) -> bool {
+        self != ScalarType::Bool
+    }
+}
+
+impl Scalar {
+    fn as_f32(self) -> f32 {
+        match self {
+            Scalar::F32(v) => v,
+            Scalar::I32(v) => v as f32,
+            Scalar::Bool(v) => v as i32 as f32,
+        }
+    }
+
+    fn as_i32(self) -> i32 {
+        match self {
+            Scalar::F32(v) => v as i32,
+            Scalar::I32(v) => v,
+            Scalar::Bool(v) => v as i32,
+        }
+    }
+
+    fn as_bool(self) -> bool {
+        match self {
+            Scalar::F32(v) => v != 0.0,
+            Scalar::I32(v) => v != 0,
+            Scalar::Bool(v) => v,
+        }
+    }
+
+    fn cast(self, ty: ScalarType) -> Scalar {
+        match ty {
+            ScalarType::F32 => Scalar::F32(self.as_f32()),
+            ScalarType::I32 => Scalar::I32(self.as_i32()),
+            ScalarType::Bool => Scalar::Bool(self.as_bool()),
+        }
+    }
+}
+
+/// A free variable of the expression, each one becomes an input.
+#[derive(Debug, Clone)]
+pub struct Variable {
+    pub name: String,
+    pub ty: ScalarType,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Token<'a> {
+    Literal(Scalar),
+    Ident(&'a str),
+    Punct(&'static str),
+    End,
+}
+
+/// Two character operators come first so they win over their prefixes.
+const PUNCT: &[&str] = &[
+    "&&", "||", "==", "!=", "<=", ">=", "(", ")", ",", ":", "+", "-", "*", "/", "%", "^", "!", "<",
+    ">",
+];
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum UnOp {
+    Neg,
+    Not,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum BinOp {
+    Or,
+    And,
+    Eq,
+    Ne,
+    Lt,
+    Le,
+    Gt,
+    Ge,
+    Add,
+    Sub,
+    Mul,
+    Div,
+    Rem,
+    Pow,
+}
+
+impl BinOp {
+    fn from_punct(p: &str) -> Option<Self> {
+        Some(match p {
+            "||" => BinOp::Or,
+            "&&" => BinOp::And,
+            "==" => BinOp::Eq,
+            "!=" => BinOp::Ne,
+            "<" => BinOp::Lt,
+            "<=" => BinOp::Le,
+            ">" => BinOp::Gt,
+            ">=" => BinOp::Ge,
+            "+" => BinOp::Add,
+            "-" => BinOp::Sub,
+            "*" => BinOp::Mul,
+            "/" => BinOp::Div,
+            "%" => BinOp::Rem,
+            "^" => BinOp::Pow,
+            _ => return None,
+        })
+    }
+
+    /// Left and right binding power, `^` binds right to left.
+    fn binding_power(self) -> (u8, u8) {
+        match self {
+            BinOp::Or => (1, 2),
+            BinOp::And => (3, 4),
+            BinOp::Eq | BinOp::Ne => (5, 6),
+            BinOp::Lt | BinOp::Le | BinOp::Gt | BinOp::Ge => (7, 8),
+            BinOp::Add | BinOp::Sub => (9, 10),
+            BinOp::Mul | BinOp::Div | BinOp::Rem => (11, 12),
+            BinOp::Pow => (15, 15),
+        }
+    }
+}
+
+/// Binding power of unary operators, between `*` and `^` so `-a^2` is `-(a^2)`.
+const UNARY_BP: u8 = 13;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Func {
+    Sin,
+    Cos,
+    Tan,
+    Asin,
+    Acos,
+    Atan,
+    Atan2,
+    Sqrt,
+    Exp,
+    Log,
+    Log2,
+    Pow,
+    Abs,
+    Sign,
+    Floor,
+    Ceil,
+    Round,
+    Fract,
+    Min,
+    Max,
+    Clamp,
+    Mix,
+    If,
+    Cast(ScalarType),
+}
+
+impl Func {
+    fn from_name(name: &str) -> Option<Self> {
+        if let Some(ty) = ScalarType::from_name(name) {
+            return Some(Func::Cast(ty));
+        }
+        Some(match name {
+            "sin" => Func::Sin,
+            "cos" => Func::Cos,
+            "tan" => Func::Tan,
+            "asin" => Func::Asin,
+            "acos" => Func::Acos,
+            "atan" => Func::Atan,
+            "atan2" => Func::Atan2,
+            "sqrt" => Func::Sqrt,
+            "exp" => Func::Exp,
+            "log" => Func::Log,
+            "log2" => Func::Log2,
+            "pow" => Func::Pow,
+            "abs" => Func::Abs,
+            "sign" => Func::Sign,
+            "floor" => Func::Floor,
+            "ceil" => Func::Ceil,
+            "round" => Func::Round,
+            "fract" => Func::Fract,
+            "min" => Func::Min,
+            "max" => Func::Max,
+            "clamp" => Func::Clamp,
+            "mix" => Func::Mix,
+            "if" => Func::If,
+            _ => return None,
+        })
+    }
+
+    fn arity(self) -> usize {
+        match self {
+            Func::Atan2 | Func::Pow | Func::Min | Func::Max => 2,
+            Func::Clamp | Func::Mix | Func::If => 3,
+            _ => 1,
+        }
+    }
+
+    /// Functions of floats only, anything else is converted first.
+    fn float(self, args: &[f32]) -> f32 {
+        match self {
+            Func::Sin => args[0].sin(),
+            Func::Cos => args[0].cos(),
+            Func::Tan => args[0].tan(),
+            Func::Asin => args[0].asin(),
+            Func::Acos => args[0].acos(),
+            Func::Atan => args[0].atan(),
+            Func::Atan2 => args[0].atan2(args[1]),
+            Func::Sqrt => args[0].sqrt(),
+            Func::Exp => args[0].exp(),
+            Func::Log => args[0].ln(),
+            Func::Log2 => args[0].log2(),
+            Func::Pow => args[0].powf(args[1]),
+            Func::Abs => args[0].abs(),
+            Func::Sign if args[0] == 0.0 => 0.0,
+            Func::Sign => args[0].signum(),
+            Func::Floor => args[0].floor(),
+            Func::Ceil => args[0].ceil(),
+            Func::Round => args[0].round(),
+            Func::Fract => args[0] - args[0].floor(),
+            Func::Min => args[0].min(args[1]),
+            Func::Max => args[0].max(args[1]),
+            Func::Clamp => args[0].max(args[1]).min(args[2]),
+            Func::Mix => args[0] + (args[1] - args[0]) * args[2],
+            Func::If | Func::Cast(_) => unreachable!("not a float function"),
+        }
+    }
+
+    /// True for functions that keep integers as integers.
+    fn keeps_int(self) -> bool {
+        matches!(
+            self,
+            Func::Abs | Func::Sign | Func::Min | Func::Max | Func::Clamp
+        )
+    }
+
+    fn int(self, args: &[i32]) -> i32 {
+        match self {
+            Func::Abs => args[0].wrapping_abs(),
+            Func::Sign => args[0].signum(),
+            Func::Min => args[0].min(args[1]),
+            Func::Max => args[0].max(args[1]),
+            Func::Clamp => args[0].max(args[1]).min(args[2]),
+            _ => unreachable!("not an int function"),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+enum Expr {
+    Literal(Scalar),
+    Var(usize),
+    Unary(UnOp, Box<Node>),
+    /// Binary operators applied left to right, `a * b + c` is `a` followed by
+    /// `* b` and `+ c`. Kept flat so long chains don't nest the tree.
+    Chain(Box<Node>, Vec<Link>),
+    Call(Func, Vec<Node>),
+}
+
+/// One operator of a [Expr::Chain] and its right hand side.
+#[derive(Debug, Clone)]
+struct Link {
+    op: BinOp,
+    /// Byte offset of the operator, for errors
+    at: usize,
+    rhs: Node,
+}
+
+#[derive(Debug, Clone)]
+struct Node {
+    expr: Expr,
+    /// Byte offset into the source, for errors
+    at: usize,
+    ty: ScalarType,
+}
+
+struct Error {
+    message: String,
+    at: usize,
+}
+
+impl Error {
+    fn new(at: usize, message: impl Into<String>) -> Self {
+        Self {
+            message: message.into(),
+            at,
+        }
+    }
+}
+
+fn error<T>(at: usize, message: impl Into<String>) -> Result<T, Error> {
+    Err(Error::new(at, message))
+}
+
+fn lex(src: &str) -> Result<Vec<(Token<'_>, usize)>, Error> {
+    let bytes = src.as_bytes();
+    let mut tokens = Vec::new();
+    let mut i = 0;
+
+    while i < bytes.len() {
+        let c = bytes[i];
+        if c.is_ascii_whitespace() {
+            i += 1;
+            continue;
+        }
+
+        let start = i;
+        if c.is_ascii_digit() || (c == b'.' && bytes.get(i + 1).is_some_and(u8::is_ascii_digit)) {
+            let digits = |i: &mut usize| {
+                while bytes.get(*i).is_some_and(u8::is_ascii_digit) {
+                    *i += 1;
+                }
+            };
+            let mut float = false;
+            digits(&mut i);
+            if bytes.get(i) == Some(&b'.') {
+                float = true;
+                i += 1;
+                digits(&mut i);
+            }
+            if matches!(bytes.get(i), Some(b'e' | b'E')) {
+                let mut j = i + 1;
+                if matches!(bytes.get(j), Some(b'+' | b'-')) {
+                    j += 1;
+                }
+                if bytes.get(j).is_some_and(u8::is_ascii_digit) {
+                    float = true;
+                    i = j;
+                    digits(&mut i);
+                }
+            }
+
+            let text = &src[start..i];
+            let literal = match float {
+                true => text.parse().map(Scalar::F32).ok(),
+                false => text.parse().map(Scalar::I32).ok(),
+            };
+            match literal {
+                Some(literal) => tokens.push((Token::Literal(literal), start)),
+                None => return error(start, format!("`{text}` is out of range for an int")),
+            }
+            continue;
+        }
+
+        if c.is_ascii_alphabetic() || c == b'_' {
+            while bytes
+                .get(i)
+                .is_some_and(|c| c.is_ascii_alphanumeric() || *c == b'_')
+            {
+                i += 1;
+            }
+            let token = match &src[start..i] {
+                "true" => Token::Literal(Scalar::Bool(true)),
+                "false" => Token::Literal(Scalar::Bool(false)),
+                "pi" => Token::Literal(Scalar::F32(PI)),
+                "tau" => Token::Literal(Scalar::F32(TAU)),
+                ident => Token::Ident(ident),
+            };
+            tokens.push((token, start));
+            continue;
+        }
+
+        match PUNCT.iter().find(|p| src[i..].starts_with(**p)) {
+            Some(p) => {
+                tokens.push((Token::Punct(p), start));
+                i += p.len();
+            }
+            None => {
+                let c = src[i..].chars().next().unwrap_or_default();
+                return error(start, format!("unexpected character `{c}`"));
+            }
+        }
+    }
+
+    tokens.push((Token::End, src.len()));
+    Ok(tokens)
+}
+
+fn describe(token: Token) -> String {
+    match token {
+        Token::Literal(Scalar::F32(v)) => format!("`{v}`"),
+        Token::Literal(Scalar::I32(v)) => format!("`{v}`"),
+        Token::Literal(Scalar::Bool(v)) => format!("`{v}`"),
+        Token::Ident(name) => format!("`{name}`"),
+        Token::Punct(p) => format!("`{p}`"),
+        Token::End => "the end of the expression".into(),
+    }
+}
+
+/// Deepest an expression may nest, parsing and evaluating recurse once per level.
+const MAX_DEPTH: usize = 256;
+
+struct Parser<'a> {
+    tokens: Vec<(Token<'a>, usize)>,
+    pos: usize,
+    /// Levels of nesting entered so far, an upper bound on the tree's depth
+    depth: usize,
+    /// Variables in order of first use, with the type they were declared as
+    vars: Vec<(String, Option<ScalarType>)>,
+}
+
+impl<'a> Parser<'a> {
+    fn peek(&self) -> (Token<'a>, usize) {
+        self.tokens[self.pos]
+    }
+
+    fn next(&mut self) -> (Token<'a>, usize) {
+        let token = self.tokens[self.pos];
+        if token.0 != Token::End {
+            self.pos += 1;
+        }
+        token
+    }
+
+    fn expect(&mut self, punct: &str) -> Result<(), Error> {
+        match self.next() {
+            (Token::Punct(p), _) if p == punct => Ok(()),
+            (token, at) => error(at, format!("expected `{punct}`, found {}", describe(token))),
+        }
+    }
+
+    fn node(expr: Expr, at: usize) -> Node {
+        // Types are filled in by `check` once every variable is declared
+        Node {
+            expr,
+            at,
+            ty: ScalarType::F32,
+        }
+    }
+
+    /// Count a level of nesting, failing at `at` past [MAX_DEPTH].
+    fn enter(&mut self, at: usize) -> Result<(), Error> {
+        self.depth += 1;
+        if self.depth > MAX_DEPTH {
+            return error(at, "expression nested too deeply");
+        }
+        Ok(())
+    }
+
+    fn expr(&mut self, min_bp: u8) -> Result<Node, Error> {
+        let depth = self.depth;
+        self.enter(self.peek().1)?;
+        let first = self.prefix()?;
+        let mut links = Vec::new();
+        while let (Token::Punct(p), at) = self.peek() {
+            let Some(op) = BinOp::from_punct(p) else {
+                break;
+            };
+            let (lbp, rbp) = op.binding_power();
+            if lbp < min_bp {
+                break;
+            }
+            self.next();
+            // Only the right hand side nests, the chain itself stays flat
+            let rhs = self.expr(rbp)?;
+            links.push(Link { op, at, rhs });
+        }
+        self.depth = depth;
+
+        if links.is_empty() {
+            return Ok(first);
+        }
+        let at = first.at;
+        Ok(Self::node(Expr::Chain(Box::new(first), links), at))
+    }
+
+    fn prefix(&mut self) -> Result<Node, Error> {
+        match self.next() {
+            (Token::Literal(v), at) => Ok(Self::node(Expr::Literal(v), at)),
+            (Token::Punct("("), _) => {
+                let inner = self.expr(0)?;
+                self.expect(")")?;
+                Ok(inner)
+            }
+            (Token::Punct(p @ ("-" | "!")), at) => {
+                let op = if p == "-" { UnOp::Neg } else { UnOp::Not };
+                let operand = self.expr(UNARY_BP)?;
+                Ok(Self::node(Expr::Unary(op, Box::new(operand)), at))
+            }
+            (Token::Ident(name), at) if self.peek().0 == Token::Punct("(") => self.call(name, at),
+            (Token::Ident(name), at) => self.variable(name, at),
+            (Token::End, at) if self.tokens.len() == 1 => error(at, "expression is empty"),
+            (token, at) => error(at, format!("expected a value, found {}", describe(token))),
+        }
+    }
+
+    fn call(&mut self, name: &str, at: usize) -> Result<Node, Error> {
+        let Some(func) = Func::from_name(name) else {
+            return error(at, format!("unknown function `{name}`"));
+        };
+
+        self.expect("(")?;
+        let mut args = Vec::new();
+        if self.peek().0 != Token::Punct(")") {
+            loop {
+                args.push(self.expr(0)?);
+                if self.peek().0 != Token::Punct(",") {
+                    break;
+                }
+                self.next();
+            }
+        }
+        self.expect(")")?;
+
+        if args.len() != func.arity() {
+            return error(
+                at,
+                format!(
+                    "`{name}` takes {} argument{}, found {}",
+                    func.arity(),
+                    if func.arity() == 1 { "" } else { "s" },
+                    args.len()
+                ),
+            );
+        }
+        Ok(Self::node(Expr::Call(func, args), at))
+    }
+
+    /// A variable, optionally declared with a type as `name: int`.
+    fn variable(&mut self, name: &str, at: usize) -> Result<Node, Error> {
+        if Func::from_name(name).is_some() {
+            return error(at, format!("`{name}` is a function"));
+        }
+
+        let declared = match self.peek().0 {
+            Token::Punct(":") => {
+                self.next();
+                match self.next() {
+                    (Token::Ident(ty), at) => match ScalarType::from_name(ty) {
+                        Some(ty) => Some(ty),
+                        None => return error(at, format!("unknown type `{ty}`")),
+                    },
+                    (token, at) => {
+                        return error(at, format!("expected a type, found {}", describe(token)));
+                    }
+                }
+            }
+            _ => None,
+        };
+
+        let index = match self.vars.iter().position(|(n, _)| n == name) {
+            Some(index) => index,
+            None => {
+                self.vars.push((name.to_owned(), None));
+                self.vars.len() - 1
+            }
+        };
+
+        match (&mut self.vars[index].1, declared) {
+            (Some(ty), Some(declared)) if *ty != declared => {
+                return error(
+                    at,
+                    format!(
+                        "`{name}` is declared as both {} and {}",
+                        ty.name(),
+                        declared.name()
+                    ),
+                );
+            }
+            (ty, Some(declared)) => *ty = Some(declared),
+            _ => {}
+        }
+        Ok(Self::node(Expr::Var(index), at))
+    }
+}
+
+/// Work out the type of every node, with ints widening to floats where they mix.
+fn check(node: &mut Node, vars: &[Variable]) -> Result<ScalarType, Error> {
+    use ScalarType::*;
+
+    let at = node.at;
+    let numeric = |ty: ScalarType, what: &str| match ty.is_numeric() {
+        true => Ok(ty),
+        false => error(at, format!("{what} needs a number, found a bool")),
+    };
+
+    node.ty = match &mut node.expr {
+        Expr::Literal(Scalar::F32(_)) => F32,
+        Expr::Literal(Scalar::I32(_)) => I32,
+        Expr::Literal(Scalar::Bool(_)) => Bool,
+        Expr::Var(index) => vars[*index].ty,
+        Expr::Unary(UnOp::Neg, operand) => numeric(check(operand, vars)?, "`-`")?,
+        Expr::Unary(UnOp::Not, operand) => match check(operand, vars)? {
+            Bool => Bool,
+            ty => return error(at, format!("`!` needs a bool, found {}", ty.name())),
+        },
+        Expr::Chain(first, links) => {
+            let mut ty = check(first, vars)?;
+            for link in links {
+                ty = binary_type(link.op, ty, check(&mut link.rhs, vars)?, link.at)?;
+            }
+            ty
+        }
+        Expr::Call(func, args) => {
+            let types = args
+                .iter_mut()
+                .map(|arg| check(arg, vars))
+                .collect::<Result<Vec<_>, _>>()?;
+            match func {
+                Func::Cast(ty) => *ty,
+                Func::If => {
+                    if types[0] != Bool {
+                        return error(at, "the condition of `if` needs a bool");
+                    }
+                    match (types[1], types[2]) {
+                        (a, b) if a == b => a,
+                        (a, b) if a.is_numeric() && b.is_numeric() => F32,
+                        (a, b) => {
+                            return error(
+                                at,
+                                format!("`if` branches are {} and {}", a.name(), b.name()),
+                            );
+                        }
+                    }
+                }
+                func => {
+                    for ty in &types {
+                        numeric(*ty, "this function")?;
+                    }
+                    match func.keeps_int() && types.iter().all(|ty| *ty == I32) {
+                        true => I32,
+                        false => F32,
+                    }
+                }
+            }
+        }
+    };
+    Ok(node.ty)
+}
+
+/// Type of `a op b`, failing at the operator `at` if the operands don't fit it.
+fn binary_type(op: BinOp, a: ScalarType, b: ScalarType, at: usize) -> Result<ScalarType, Error> {
+    use ScalarType::*;
+
+    let numeric = |ty: ScalarType, what: &str| match ty.is_numeric() {
+        true => Ok(ty),
+        false => error(at, format!("{what} needs a number, found a bool")),
+    };
+    let widen = |a: ScalarType, b: ScalarType| if a == I32 && b == I32 { I32 } else { F32 };
+
+    Ok(match op {
+        BinOp::Or | BinOp::And => {
+            if a != Bool || b != Bool {
+                return error(at, "`&&` and `||` need bools on both sides");
+            }
+            Bool
+        }
+        BinOp::Eq | BinOp::Ne => {
+            if a.is_numeric() != b.is_numeric() {
+                return error(at, format!("cannot compare {} with {}", a.name(), b.name()));
+            }
+            Bool
+        }
+        BinOp::Lt | BinOp::Le | BinOp::Gt | BinOp::Ge => {
+            numeric(a, "comparison")?;
+            numeric(b, "comparison")?;
+            Bool
+        }
+        BinOp::Pow => {
+            numeric(a, "`^`")?;
+            numeric(b, "`^`")?;
+            F32
+        }
+        BinOp::Add | BinOp::Sub | BinOp::Mul | BinOp::Div | BinOp::Rem => {
+            widen(numeric(a, "arithmetic")?, numeric(b, "arithmetic")?)
+        }
+    })
+}
+
+fn eval(node: &Node, vars: &[Scalar]) -> Result<Scalar, Error> {
+    Ok(match &node.expr {
+        Expr::Literal(v) => *v,
+        Expr::Var(index) => vars[*index],
+        Expr::Unary(UnOp::Neg, operand) => match eval(operand, vars)? {
+            Scalar::I32(v) => Scalar::I32(v.wrapping_neg()),
+            v => Scalar::F32(-v.as_f32()),
+        },
+        Expr::Unary(UnOp::Not, operand) => Scalar::Bool(!eval(operand, vars)?.as_bool()),
+        Expr::Chain(first, links) => {
+            let mut value = eval(first, vars)?;
+            for link in links {
+                let rhs = || eval(&link.rhs, vars);
+                value = match link.op {
+                    BinOp::And => Scalar::Bool(value.as_bool() && rhs()?.as_bool()),
+                    BinOp::Or => Scalar::Bool(value.as_bool() || rhs()?.as_bool()),
+                    op => match binary(op, value, rhs()?) {
+                        Some(v) => v,
+                        None => return error(link.at, "integer division by zero"),
+                    },
+                };
+            }
+            value
+        }
+        Expr::Call(Func::If, args) => {
+            let branch = match eval(&args[0], vars)?.as_bool() {
+                true => &args[1],
+                false => &args[2],
+            };
+            eval(branch, vars)?.cast(node.ty)
+        }
+        Expr::Call(Func::Cast(ty), args) => eval(&args[0], vars)?.cast(*ty),
+        Expr::Call(func, args) => {
+            let (mut ints, mut floats) = ([0; 3], [0.0; 3]);
+            for (i, arg) in args.iter().enumerate() {
+                let value = eval(arg, vars)?;
+                ints[i] = value.as_i32();
+                floats[i] = value.as_f32();
+            }
+            match node.ty {
+                ScalarType::I32 => Scalar::I32(func.int(&ints)),
+                _ => Scalar::F32(func.float(&floats)),
+            }
+        }
+    })
+}
+
+/// Apply a binary operator other than `&&` and `||`, None on integer division by zero.
+fn binary(op: BinOp, a: Scalar, b: Scalar) -> Option<Scalar> {
+    use Scalar::*;
+
+    let cmp = match (a, b) {
+        (I32(a), I32(b)) => a.partial_cmp(&b),
+        (Bool(a), Bool(b)) => a.partial_cmp(&b),
+        (a, b) => a.as_f32().partial_cmp(&b.as_f32()),
+    };
+    let compare = |ok: fn(std::cmp::Ordering) -> bool| Some(Bool(cmp.is_some_and(ok)));
+
+    match (op, a, b) {
+        (BinOp::Eq, ..) => compare(|o| o.is_eq()),
+        (BinOp::Ne, ..) => Some(Bool(!cmp.is_some_and(|o| o.is_eq()))),
+        (BinOp::Lt, ..) => compare(|o| o.is_lt()),
+        (BinOp::Le, ..) => compare(|o| o.is_le()),
+        (BinOp::Gt, ..) => compare(|o| o.is_gt()),
+        (BinOp::Ge, ..) => compare(|o| o.is_ge()),
+        (BinOp::Pow, a, b) => Some(F32(a.as_f32().powf(b.as_f32()))),
+        (op, I32(a), I32(b)) => Some(I32(match op {
+            BinOp::Add => a.wrapping_add(b),
+            BinOp::Sub => a.wrapping_sub(b),
+            BinOp::Mul => a.wrapping_mul(b),
+            // Euclidean like `math/integer`, only zero fails and
+            // `i32::MIN / -1` wraps like the other operators
+            _ if b == 0 => return None,
+            BinOp::Div => a.wrapping_div_euclid(b),
+            _ => a.wrapping_rem_euclid(b),
+        })),
+        (op, a, b) => {
+            let (a, b) = (a.as_f32(), b.as_f32());
+            Some(F32(match op {
+                BinOp::Add => a + b,
+                BinOp::Sub => a - b,
+                BinOp::Mul => a * b,
+                BinOp::Div => a / b,
+                _ => a.rem_euclid(b),
+            }))
+        }
+    }
+}
+
+/// A parsed and type checked expression.
+///
+/// Numbers without a `.` or exponent are ints, and int arithmetic stays int
+/// until it meets a float. Variables are floats unless declared otherwise at
+/// any one of their uses, as in `count: int * 2`. Int division is Euclidean
+/// and `%` is never negative, so `-7 / 2` is `-4` and `-7 % 2` is `1`.
+#[derive(Debug, Clone)]
+pub struct Program {
+    source: String,
+    root: Node,
+    variables: Vec<Variable>,
+}
+
+impl Program {
+    pub fn parse(source: &str) -> Result<Self, ScriptError> {
+        let locate = |e: Error| locate(source, e);
+
+        let mut parser = Parser {
+            tokens: lex(source).map_err(locate)?,
+            pos: 0,
+            depth: 0,
+            vars: Vec::new(),
+        };
+        let mut root = parser.expr(0).map_err(locate)?;
+        match parser.peek() {
+            (Token::End, _) => {}
+            (Token::Punct(")"), at) => return Err(locate(Error::new(at, "unmatched `)`"))),
+            (token, at) => {
+                let message = format!("expected an operator, found {}", describe(token));
+                return Err(locate(Error::new(at, message)));
+            }
+        }
+
+        let variables: Vec<_> = parser
+            .vars
+            .into_iter()
+            .map(|(name, ty)| Variable {
+                name,
+                ty: ty.unwrap_or(ScalarType::F32),
+            })
+            .collect();
+        check(&mut root, &variables).map_err(locate)?;
+
+        Ok(Self {
+            source: source.to_owned(),
+            root,
+            variables,
+        })
+    }
+
+    /// Free variables in the order they first appear.
+    pub fn variables(&self) -> &[Variable] {
+        &self.variables
+    }
+
+    /// Type of the result.
+    pub fn ty(&self) -> ScalarType {
+        self.root.ty
+    }
+
+    /// Evaluate with a value for each of [Program::variables], of the declared types.
+    pub fn eval(&self, values: &[Scalar]) -> Result<Scalar, ScriptError> {
+        eval(&self.root, values).map_err(|e| locate(&self.source, e))
+    }
+}
+
+fn locate(source: &str, e: Error) -> ScriptError {
+    let before = &source[..e.at.min(source.len())];
+    let line_start = before.rfind('\n').map_or(0, |i| i + 1);
+    ScriptError {
+        errors: vec![LocatedError {
+            message: e.message,
+            file: None,
+            line: before.matches('\n').count() as u32 + 1,
+            column: before[line_start..].chars().count() as u32 + 1,
+        }],
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn run(source: &str, values: &[Scalar]) -> Scalar {
+        Program::parse(source).unwrap().eval(values).unwrap()
+    }
+
+    fn parse_error(source: &str) -> String {
+        Program::parse(source).unwrap_err().errors[0]
+            .message
+            .clone()
+    }
+
+    fn eval_error(source: &str, values: &[Scalar]) -> String {
+        let program = Program::parse(source).unwrap();
+        program.eval(values).unwrap_err().errors[0].message.clone()
+    }
+
+    #[test]
+    fn precedence_and_associativity() {
+        assert_eq!(run("1 + 2 * 3", &[]), Scalar::I32(7));
+        assert_eq!(run("(1 + 2) * 3", &[]), Scalar::I32(9));
+        assert_eq!(run("10 - 4 - 3", &[]), Scalar::I32(3));
+        assert_eq!(run("12 / 3 / 2", &[]), Scalar::I32(2));
+        assert_eq!(run("-a^2", &[Scalar::F32(3.0)]), Scalar::F32(-9.0));
+        assert_eq!(run("(-a)^2", &[Scalar::F32(3.0)]), Scalar::F32(9.0));
+        let values = [2.0, 3.0, 2.0].map(Scalar::F32);
+        assert_eq!(run("a^b^c", &values), Scalar::F32(512.0));
+        assert_eq!(run("1 < 2 == 2 < 3", &[]), Scalar::Bool(true));
+        assert_eq!(run("false && true || true", &[]), Scalar::Bool(true));
+    }
+
+    #[test]
+    fn types() {
+        let ty = |source: &str| Program::parse(source).unwrap().ty();
+        assert_eq!(ty("1 + 2"), ScalarType::I32);
+        assert_eq!(ty("1 + 2.0"), ScalarType::F32);
+        assert_eq!(ty("2 ^ 2"), ScalarType::F32);
+        assert_eq!(ty("1 < 2"), ScalarType::Bool);
+        assert_eq!(ty("n: int * 2"), ScalarType::I32);
+        assert_eq!(ty("if(true, 1, 2.5)"), ScalarType::F32);
+        assert_eq!(ty("int(1.5) + 1"), ScalarType::I32);
+
+        assert_eq!(
+            parse_error("true + 1"),
+            "arithmetic needs a number, found a bool"
+        );
+        assert_eq!(parse_error("-true"), "`-` needs a number, found a bool");
+        assert_eq!(parse_error("!1"), "`!` needs a bool, found int");
+        assert_eq!(
+            parse_error("1 && true"),
+            "`&&` and `||` need bools on both sides"
+        );
+        assert_eq!(parse_error("1 == true"), "cannot compare int with bool");
+        assert_eq!(
+            parse_error("if(1, 2, 3)"),
+            "the condition of `if` needs a bool"
+        );
+        assert_eq!(
+            parse_error("if(true, 1, false)"),
+            "`if` branches are int and bool"
+        );
+        assert_eq!(
+            parse_error("x: int + x: float"),
+            "`x` is declared as both int and float"
+        );
+    }
+
+    #[test]
+    fn euclidean_division() {
+        assert_eq!(run("-7 / 2", &[]), Scalar::I32(-4));
+        assert_eq!(run("-7 % 2", &[]), Scalar::I32(1));
+        assert_eq!(run("7 / -2", &[]), Scalar::I32(-3));
+        assert_eq!(run("7 % -2", &[]), Scalar::I32(1));
+        assert_eq!(run("-7 % -2", &[]), Scalar::I32(1));
+        assert_eq!(run("-7.0 % 2.0", &[]), Scalar::F32(1.0));
+
+        assert_eq!(eval_error("1 / 0", &[]), "integer division by zero");
+        assert_eq!(
+            eval_error("1 % n: int", &[Scalar::I32(0)]),
+            "integer division by zero"
+        );
+        assert_eq!(run("1.0 / 0", &[]), Scalar::F32(f32::INFINITY));
+    }
+
+    #[test]
+    fn int_min_wraps() {
+        let min = [Scalar::I32(i32::MIN)];
+        assert_eq!(run("n: int / -1", &min), Scalar::I32(i32::MIN));
+        assert_eq!(run("n: int % -1", &min), Scalar::I32(0));
+        assert_eq!(run("-n: int", &min), Scalar::I32(i32::MIN));
+        assert_eq!(
+            parse_error("2147483648"),
+            "`2147483648` is out of range for an int"
+        );
+    }
+
+    #[test]
+    fn nesting_limit() {
+        let parens = |n| format!("{}1{}", "(".repeat(n), ")".repeat(n));
+        assert_eq!(run(&parens(MAX_DEPTH - 1), &[]), Scalar::I32(1));
+        assert_eq!(
+            parse_error(&parens(MAX_DEPTH)),
+            "expression nested too deeply"
+        );
+        assert_eq!(
+            parse_error(&format!("{}1", "-".repeat(MAX_DEPTH))),
+            "expression nested too deeply"
+        );
+        let power = vec!["1"; MAX_DEPTH + 1].join("^");
+        assert_eq!(parse_error(&power), "expression nested too deeply");
+
+        // Flat chains don't nest however long they are
+        let chain = vec!["a"; 10 * MAX_DEPTH].join("+");
+        let program = Program::parse(&chain).unwrap();
+        assert_eq!(program.variables().len(), 1);
+        assert_eq!(
+            program.eval(&[Scalar::F32(1.0)]).unwrap(),
+            Scalar::F32(10.0 * MAX_DEPTH as f32)
+        );
+    }
+}
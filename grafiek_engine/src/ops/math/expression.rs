@@ -0,0 +1,132 @@
+use super::expr::{Program, Scalar, ScalarType};
+use crate::ConfigSchema;
+use crate::ExecutionContext;
+use crate::error::{Error, Result, ScriptError};
+use crate::registry::{FloatRange, IntRange, SignatureRegistery, StringKind, StringMeta};
+use crate::traits::{OpPath, Operation, OperationFactory};
+use crate::value::{Config, Inputs, InputsExt, MAX_SLOTS, Outputs, OutputsExt};
+
+const F32_META: FloatRange = FloatRange {
+    min: f32::MIN,
+    max: f32::MAX,
+    step: 0.1,
+};
+
+/// Evaluates a formula such as `a * sin(b) + c^2`, with an input for each variable.
+#[derive(Default)]
+pub struct Expression {
+    program: Option<Program>,
+}
+
+#[derive(ConfigSchema)]
+struct ExpressionConfig {
+    /// Variables are float inputs, write `name: int` or `name: bool` once to change that.
+    /// Int division is Euclidean like the integer node, so `%` is never negative.
+    #[label("")]
+    #[on_node_body]
+    #[meta(StringMeta { kind: StringKind::Plain, multi_line: false })]
+    #[default("a + b".to_string())]
+    expression: String,
+}
+
+impl Operation for Expression {
+    fn is_stateful(&self) -> bool {
+        false
+    }
+
+    fn op_path(&self) -> OpPath {
+        <Self as OperationFactory>::op_path()
+    }
+
+    fn setup(&mut self, _ctx: &mut ExecutionContext, registry: &mut SignatureRegistery) {
+        registry.add_output::<f32>("result").build();
+        registry.register_config::<ExpressionConfig>();
+    }
+
+    fn configure(
+        &mut self,
+        _ctx: &ExecutionContext,
+        config: Config,
+        registry: &mut SignatureRegistery,
+    ) -> Result<()> {
+        let cfg = ExpressionConfig::try_extract(config)?;
+        self.program = None;
+
+        if cfg.expression.trim().is_empty() {
+            registry.clear_inputs();
+            registry.clear_outputs();
+            registry.add_output::<f32>("result").build();
+            return Ok(());
+        }
+
+        // Inputs are left alone on errors so connections survive a typo
+        let program = Program::parse(&cfg.expression).map_err(Error::Script)?;
+        if program.variables().len() > MAX_SLOTS {
+            return Err(Error::Script(ScriptError::new(format!(
+                "expressions can use at most {MAX_SLOTS} variables"
+            ))));
+        }
+
+        registry.clear_inputs();
+        for var in program.variables() {
+            let name = var.name.clone();
+            match var.ty {
+                ScalarType::F32 => registry.add_input::<f32>(name).meta(F32_META).build(),
+                ScalarType::I32 => registry
+                    .add_input::<i32>(name)
+                    .meta(IntRange::default())
+                    .build(),
+                ScalarType::Bool => registry.add_input::<bool>(name).build(),
+            }
+        }
+
+        registry.clear_outputs();
+        match program.ty() {
+            ScalarType::F32 => registry.add_output::<f32>("result").build(),
+            ScalarType::I32 => registry.add_output::<i32>("result").build(),
+            ScalarType::Bool => registry.add_output::<bool>("result").build(),
+        }
+
+        self.program = Some(program);
+        Ok(())
+    }
+
+    fn execute(
+        &mut self,
+        _ctx: &mut ExecutionContext,
+        inputs: Inputs,
+        mut outputs: Outputs,
+    ) -> Result<()> {
+        let Some(program) = &self.program else {
+            return Ok(());
+        };
+
+        let values = program
+            .variables()
+            .iter()
+            .enumerate()
+            .map(|(i, var)| match var.ty {
+                ScalarType::F32 => inputs.extract(i).map(Scalar::F32),
+                ScalarType::I32 => inputs.extract(i).map(Scalar::I32),
+                ScalarType::Bool => inputs.extract(i).map(Scalar::Bool),
+            })
+            .collect::<std::result::Result<Vec<_>, _>>()?;
+
+        match program.eval(&values).map_err(Error::Script)? {
+            Scalar::F32(v) => *outputs.extract::<f32>(0)? = v,
+            Scalar::I32(v) => *outputs.extract::<i32>(0)? = v,
+            Scalar::Bool(v) => *outputs.extract::<bool>(0)? = v,
+        }
+        Ok(())
+    }
+}
+
+impl OperationFactory for Expression {
+    const LIBRARY: &'static str = "math";
+    const OPERATOR: &'static str = "expression";
+    const LABEL: &'static str = "Expression";
+
+    fn build() -> Result<Box<dyn Operation>> {
+        Ok(Box::new(Expression::default()))
+    }
+}
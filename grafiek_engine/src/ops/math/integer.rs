@@ -0,0 +1,191 @@
+use crate::ConfigSchema;
+use crate::EnumSchema;
+use crate::ExecutionContext;
+use crate::error::{Error, Result};
+use crate::registry::{IntRange, SignatureRegistery};
+use crate::traits::{OpPath, Operation, OperationFactory};
+use crate::value::{Config, Inputs, InputsExt, Outputs, OutputsExt};
+
+const I32_META: IntRange = IntRange {
+    min: i32::MIN,
+    max: i32::MAX,
+    step: 1,
+};
+
+const SHIFT_META: IntRange = IntRange {
+    min: 0,
+    max: 31,
+    step: 1,
+};
+
+/// Integer math that stays in `i32`, for frame counts, tile indices and the like.
+/// Overflow wraps around instead of failing.
+pub struct Integer {
+    pub operation: IntOp,
+}
+
+#[derive(EnumSchema, Default, Copy, Clone)]
+pub enum IntOp {
+    #[default]
+    Add = 0,
+    Subtract,
+    Multiply,
+    /// Euclidean division, pairs with [IntOp::Modulo] so the remainder is
+    /// never negative. `7 / -2` is `-3` and `-7 / 2` is `-4`
+    Divide,
+    /// Never negative, so counters wrap the same way on both sides of zero
+    Modulo,
+    Min,
+    Max,
+    Abs,
+    And,
+    Or,
+    Xor,
+    Not,
+    ShiftLeft,
+    /// Keeps the sign bit
+    ShiftRight,
+    Clamp,
+    /// Wraps into `min..max`, excluding `max`
+    Wrap,
+}
+
+#[derive(ConfigSchema)]
+struct IntegerConfig {
+    #[label("")]
+    #[on_node_body]
+    operation: IntOp,
+}
+
+impl IntOp {
+    /// Apply to the inputs in slot order, unused ones are ignored.
+    pub fn apply(self, a: i32, b: i32, c: i32) -> Result<i32> {
+        Ok(match self {
+            IntOp::Add => a.wrapping_add(b),
+            IntOp::Subtract => a.wrapping_sub(b),
+            IntOp::Multiply => a.wrapping_mul(b),
+            IntOp::Divide | IntOp::Modulo if b == 0 => return Err(Error::DivisionByZero),
+            IntOp::Divide => a.wrapping_div_euclid(b),
+            IntOp::Modulo => a.wrapping_rem_euclid(b),
+            IntOp::Min => a.min(b),
+            IntOp::Max => a.max(b),
+            IntOp::Abs => a.wrapping_abs(),
+            IntOp::And => a & b,
+            IntOp::Or => a | b,
+            IntOp::Xor => a ^ b,
+            IntOp::Not => !a,
+            IntOp::ShiftLeft => a.wrapping_shl(b.clamp(0, 31) as u32),
+            IntOp::ShiftRight => a.wrapping_shr(b.clamp(0, 31) as u32),
+            // Unlike `i32::clamp` this doesn't panic when min > max
+            IntOp::Clamp => a.max(b).min(c),
+            IntOp::Wrap => {
+                let range = c as i64 - b as i64;
+                if range <= 0 {
+                    b
+                } else {
+                    (b as i64 + (a as i64 - b as i64).rem_euclid(range)) as i32
+                }
+            }
+        })
+    }
+}
+
+impl Operation for Integer {
+    fn is_stateful(&self) -> bool {
+        false
+    }
+
+    fn op_path(&self) -> OpPath {
+        <Self as OperationFactory>::op_path()
+    }
+
+    fn setup(&mut self, _ctx: &mut ExecutionContext, registry: &mut SignatureRegistery) {
+        registry.add_input::<i32>("a").meta(I32_META).build();
+        registry.add_input::<i32>("b").meta(I32_META).build();
+        registry.add_output::<i32>("result").build();
+        registry.register_config::<IntegerConfig>();
+    }
+
+    fn configure(
+        &mut self,
+        _ctx: &ExecutionContext,
+        config: Config,
+        registry: &mut SignatureRegistery,
+    ) -> Result<()> {
+        let cfg = IntegerConfig::try_extract(config)?;
+        self.operation = cfg.operation;
+
+        registry.clear_inputs();
+
+        match cfg.operation {
+            IntOp::Add
+            | IntOp::Multiply
+            | IntOp::Min
+            | IntOp::Max
+            | IntOp::And
+            | IntOp::Or
+            | IntOp::Xor => {
+                registry.add_input::<i32>("a").meta(I32_META).build();
+                registry.add_input::<i32>("b").meta(I32_META).build();
+            }
+            IntOp::Subtract => {
+                registry.add_input::<i32>("minuend").meta(I32_META).build();
+                registry
+                    .add_input::<i32>("subtrahend")
+                    .meta(I32_META)
+                    .build();
+            }
+            IntOp::Divide | IntOp::Modulo => {
+                registry.add_input::<i32>("dividend").meta(I32_META).build();
+                registry
+                    .add_input::<i32>("divisor")
+                    .meta(I32_META)
+                    .default(1)
+                    .build();
+            }
+            IntOp::Abs | IntOp::Not => {
+                registry.add_input::<i32>("a").meta(I32_META).build();
+            }
+            IntOp::ShiftLeft | IntOp::ShiftRight => {
+                registry.add_input::<i32>("a").meta(I32_META).build();
+                registry.add_input::<i32>("bits").meta(SHIFT_META).build();
+            }
+            IntOp::Clamp | IntOp::Wrap => {
+                registry.add_input::<i32>("value").meta(I32_META).build();
+                registry.add_input::<i32>("min").meta(I32_META).build();
+                registry
+                    .add_input::<i32>("max")
+                    .meta(I32_META)
+                    .default(10)
+                    .build();
+            }
+        }
+
+        Ok(())
+    }
+
+    fn execute(
+        &mut self,
+        _ctx: &mut ExecutionContext,
+        inputs: Inputs,
+        mut outputs: Outputs,
+    ) -> Result<()> {
+        let a: i32 = inputs.extract(0)?;
+        let b: i32 = inputs.extract(1).unwrap_or(0);
+        let c: i32 = inputs.extract(2).unwrap_or(0);
+        *outputs.extract::<i32>(0)? = self.operation.apply(a, b, c)?;
+        Ok(())
+    }
+}
+
+impl OperationFactory for Integer {
+    const LIBRARY: &'static str = "math";
+    const OPERATOR: &'static str = "integer";
+    const LABEL: &'static str = "Integer";
+
+    fn build() -> Result<Box<dyn Operation>> {
+        Ok(Box::new(Integer {
+            operation: IntOp::Add,
+        }))
+    }
+}
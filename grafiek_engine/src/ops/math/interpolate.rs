@@ -0,0 +1,165 @@
+use crate::ExecutionContext;
+use crate::error::Result;
+use crate::registry::{FloatRange, SignatureRegistery};
+use crate::traits::{OpPath, Operation, OperationFactory};
+use crate::value::{Inputs, InputsExt, Outputs, OutputsExt};
+
+const F32_META: FloatRange = FloatRange {
+    min: f32::MIN,
+    max: f32::MAX,
+    step: 0.1,
+};
+
+const UNIT_META: FloatRange = FloatRange {
+    min: 0.0,
+    max: 1.0,
+    step: 0.01,
+};
+
+/// Linear interpolation from `a` at `t = 0` to `b` at `t = 1`, as GLSL's `mix`.
+/// `t` outside of `0..1` extrapolates.
+pub struct Mix;
+
+/// Hermite interpolation from 0 at `edge0` to 1 at `edge1`, as GLSL's `smoothstep`.
+pub struct Smoothstep;
+
+/// 0 below `edge` and 1 from `edge` upwards, as GLSL's `step`.
+pub struct Step;
+
+pub fn smoothstep(edge0: f32, edge1: f32, x: f32) -> f32 {
+    if edge0 == edge1 {
+        return step(edge0, x);
+    }
+    let t = ((x - edge0) / (edge1 - edge0)).clamp(0.0, 1.0);
+    t * t * (3.0 - 2.0 * t)
+}
+
+pub fn step(edge: f32, x: f32) -> f32 {
+    if x < edge { 0.0 } else { 1.0 }
+}
+
+impl Operation for Mix {
+    fn is_stateful(&self) -> bool {
+        false
+    }
+
+    fn op_path(&self) -> OpPath {
+        <Self as OperationFactory>::op_path()
+    }
+
+    fn setup(&mut self, _ctx: &mut ExecutionContext, registry: &mut SignatureRegistery) {
+        registry.add_input::<f32>("a").meta(F32_META).build();
+        registry
+            .add_input::<f32>("b")
+            .meta(F32_META)
+            .default(1.0)
+            .build();
+        registry.add_input::<f32>("t").meta(UNIT_META).build();
+        registry.add_output::<f32>("result").build();
+    }
+
+    fn execute(
+        &mut self,
+        _ctx: &mut ExecutionContext,
+        inputs: Inputs,
+        mut outputs: Outputs,
+    ) -> Result<()> {
+        let a: f32 = inputs.extract(0)?;
+        let b: f32 = inputs.extract(1)?;
+        let t: f32 = inputs.extract(2)?;
+        *outputs.extract::<f32>(0)? = a + (b - a) * t;
+        Ok(())
+    }
+}
+
+impl OperationFactory for Mix {
+    const LIBRARY: &'static str = "math";
+    const OPERATOR: &'static str = "mix";
+    const LABEL: &'static str = "Mix";
+
+    fn build() -> Result<Box<dyn Operation>> {
+        Ok(Box::new(Mix))
+    }
+}
+
+impl Operation for Smoothstep {
+    fn is_stateful(&self) -> bool {
+        false
+    }
+
+    fn op_path(&self) -> OpPath {
+        <Self as OperationFactory>::op_path()
+    }
+
+    fn setup(&mut self, _ctx: &mut ExecutionContext, registry: &mut SignatureRegistery) {
+        registry.add_input::<f32>("edge0").meta(F32_META).build();
+        registry
+            .add_input::<f32>("edge1")
+            .meta(F32_META)
+            .default(1.0)
+            .build();
+        registry.add_input::<f32>("x").meta(F32_META).build();
+        registry.add_output::<f32>("result").build();
+    }
+
+    fn execute(
+        &mut self,
+        _ctx: &mut ExecutionContext,
+        inputs: Inputs,
+        mut outputs: Outputs,
+    ) -> Result<()> {
+        let edge0: f32 = inputs.extract(0)?;
+        let edge1: f32 = inputs.extract(1)?;
+        let x: f32 = inputs.extract(2)?;
+        *outputs.extract::<f32>(0)? = smoothstep(edge0, edge1, x);
+        Ok(())
+    }
+}
+
+impl OperationFactory for Smoothstep {
+    const LIBRARY: &'static str = "math";
+    const OPERATOR: &'static str = "smoothstep";
+    const LABEL: &'static str = "Smoothstep";
+
+    fn build() -> Result<Box<dyn Operation>> {
+        Ok(Box::new(Smoothstep))
+    }
+}
+
+impl Operation for Step {
+    fn is_stateful(&self) -> bool {
+        false
+    }
+
+    fn op_path(&self) -> OpPath {
+        <Self as OperationFactory>::op_path()
+    }
+
+    fn setup(&mut self, _ctx: &mut ExecutionContext, registry: &mut SignatureRegistery) {
+        registry.add_input::<f32>("edge").meta(F32_META).build();
+        registry.add_input::<f32>("x").meta(F32_META).build();
+        registry.add_output::<f32>("result").build();
+    }
+
+    fn execute(
+        &mut self,
+        _ctx: &mut ExecutionContext,
+        inputs: Inputs,
+        mut outputs: Outputs,
+    ) -> Result<()> {
+        let edge: f32 = inputs.extract(0)?;
+        let x: f32 = inputs.extract(1)?;
+        *outputs.extract::<f32>(0)? = step(edge, x);
+        Ok(())
+    }
+}
+
+impl OperationFactory for Step {
+    const LIBRARY: &'static str = "math";
+    const OPERATOR: &'static str = "step";
+    const LABEL: &'static str = "Step";
+
+    fn build() -> Result<Box<dyn Operation>> {
+        Ok(Box::new(Step))
+    }
+}
@@ -1,3 +1,16 @@
 pub mod arithmetic;
+pub mod ease;
+pub mod expr;
+pub mod expression;
+pub mod integer;
+pub mod interpolate;
+pub mod remap;
+pub mod trig;
 
 pub use arithmetic::{ArithOp, Arithmetic};
+pub use ease::{Ease, EaseCurve, EaseDirection};
+pub use expression::Expression;
+pub use integer::{IntOp, Integer};
+pub use interpolate::{Mix, Smoothstep, Step};
+pub use remap::Remap;
+pub use trig::{Trig, TrigOp};
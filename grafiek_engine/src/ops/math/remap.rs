@@ -0,0 +1,101 @@
+use crate::ConfigSchema;
+use crate::ExecutionContext;
+use crate::error::Result;
+use crate::registry::{FloatRange, SignatureRegistery};
+use crate::traits::{OpPath, Operation, OperationFactory};
+use crate::value::{Config, Inputs, InputsExt, Outputs, OutputsExt};
+
+const F32_META: FloatRange = FloatRange {
+    min: f32::MIN,
+    max: f32::MAX,
+    step: 0.1,
+};
+
+/// Maps a value from one range onto another, `in min` landing on `out min`
+/// and `in max` on `out max`. Either range may be reversed.
+#[derive(Default)]
+pub struct Remap {
+    pub clamp: bool,
+}
+
+#[derive(ConfigSchema)]
+struct RemapConfig {
+    /// Keep the result inside the output range
+    #[on_node_body]
+    clamp: bool,
+}
+
+/// Remap `value` from `from` onto `to`. An empty input range maps everything to `to.0`.
+pub fn remap(value: f32, from: (f32, f32), to: (f32, f32), clamp: bool) -> f32 {
+    let span = from.1 - from.0;
+    if span == 0.0 {
+        return to.0;
+    }
+    let mut t = (value - from.0) / span;
+    if clamp {
+        t = t.clamp(0.0, 1.0);
+    }
+    to.0 + (to.1 - to.0) * t
+}
+
+impl Operation for Remap {
+    fn is_stateful(&self) -> bool {
+        false
+    }
+
+    fn op_path(&self) -> OpPath {
+        <Self as OperationFactory>::op_path()
+    }
+
+    fn setup(&mut self, _ctx: &mut ExecutionContext, registry: &mut SignatureRegistery) {
+        registry.add_input::<f32>("value").meta(F32_META).build();
+        registry.add_input::<f32>("in min").meta(F32_META).build();
+        registry
+            .add_input::<f32>("in max")
+            .meta(F32_META)
+            .default(1.0)
+            .build();
+        registry.add_input::<f32>("out min").meta(F32_META).build();
+        registry
+            .add_input::<f32>("out max")
+            .meta(F32_META)
+            .default(1.0)
+            .build();
+        registry.add_output::<f32>("result").build();
+        registry.register_config::<RemapConfig>();
+    }
+
+    fn configure(
+        &mut self,
+        _ctx: &ExecutionContext,
+        config: Config,
+        _registry: &mut SignatureRegistery,
+    ) -> Result<()> {
+        let cfg = RemapConfig::try_extract(config)?;
+        self.clamp = cfg.clamp;
+        Ok(())
+    }
+
+    fn execute(
+        &mut self,
+        _ctx: &mut ExecutionContext,
+        inputs: Inputs,
+        mut outputs: Outputs,
+    ) -> Result<()> {
+        let value: f32 = inputs.extract(0)?;
+        let from = (inputs.extract(1)?, inputs.extract(2)?);
+        let to = (inputs.extract(3)?, inputs.extract(4)?);
+        *outputs.extract::<f32>(0)? = remap(value, from, to, self.clamp);
+        Ok(())
+    }
+}
+
+impl OperationFactory for Remap {
+    const LIBRARY: &'static str = "math";
+    const OPERATOR: &'static str = "remap";
+    const LABEL: &'static str = "Remap";
+
+    fn build() -> Result<Box<dyn Operation>> {
+        Ok(Box::new(Remap::default()))
+    }
+}
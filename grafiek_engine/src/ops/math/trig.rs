@@ -0,0 +1,219 @@
+use std::f32::consts::{PI, TAU};
+
+use crate::ConfigSchema;
+use crate::EnumSchema;
+use crate::ExecutionContext;
+use crate::error::Result;
+use crate::registry::{Angle, AngleUnit, FloatRange, SignatureRegistery};
+use crate::traits::{OpPath, Operation, OperationFactory};
+use crate::value::{Config, Inputs, InputsExt, Outputs, OutputsExt};
+
+const F32_META: FloatRange = FloatRange {
+    min: f32::MIN,
+    max: f32::MAX,
+    step: 0.1,
+};
+
+const UNIT_META: FloatRange = FloatRange {
+    min: -1.0,
+    max: 1.0,
+    step: 0.01,
+};
+
+/// Trigonometric functions of scalars, with angles in the configured unit.
+pub struct Trig {
+    pub operation: TrigOp,
+    pub unit: AngleUnit,
+}
+
+#[derive(EnumSchema, Default, Copy, Clone)]
+pub enum TrigOp {
+    #[default]
+    Sin = 0,
+    Cos,
+    Tan,
+    Asin,
+    Acos,
+    Atan,
+    Atan2,
+    Sinh,
+    Cosh,
+    Tanh,
+    ToDegrees,
+    ToRadians,
+}
+
+#[derive(ConfigSchema)]
+struct TrigConfig {
+    #[label("")]
+    #[on_node_body]
+    operation: TrigOp,
+
+    /// Unit of angle inputs and outputs
+    unit: AngleUnit,
+}
+
+impl TrigOp {
+    /// Apply to a single component, with angles in radians.
+    pub fn apply(self, a: f32, b: f32) -> f32 {
+        match self {
+            TrigOp::Sin => a.sin(),
+            TrigOp::Cos => a.cos(),
+            TrigOp::Tan => a.tan(),
+            TrigOp::Asin => a.asin(),
+            TrigOp::Acos => a.acos(),
+            TrigOp::Atan => a.atan(),
+            TrigOp::Atan2 => a.atan2(b),
+            TrigOp::Sinh => a.sinh(),
+            TrigOp::Cosh => a.cosh(),
+            TrigOp::Tanh => a.tanh(),
+            TrigOp::ToDegrees => a.to_degrees(),
+            TrigOp::ToRadians => a.to_radians(),
+        }
+    }
+
+    fn takes_angle(self) -> bool {
+        matches!(self, TrigOp::Sin | TrigOp::Cos | TrigOp::Tan)
+    }
+
+    fn gives_angle(self) -> bool {
+        matches!(
+            self,
+            TrigOp::Asin | TrigOp::Acos | TrigOp::Atan | TrigOp::Atan2
+        )
+    }
+}
+
+fn angle_meta(unit: AngleUnit) -> Angle {
+    let max = match unit {
+        AngleUnit::Radians => TAU,
+        AngleUnit::Degrees => 360.0,
+    };
+    Angle {
+        min: 0.0,
+        max,
+        unit,
+    }
+}
+
+impl Operation for Trig {
+    fn is_stateful(&self) -> bool {
+        false
+    }
+
+    fn op_path(&self) -> OpPath {
+        <Self as OperationFactory>::op_path()
+    }
+
+    fn setup(&mut self, _ctx: &mut ExecutionContext, registry: &mut SignatureRegistery) {
+        registry
+            .add_input::<f32>("angle")
+            .meta(angle_meta(AngleUnit::Radians))
+            .build();
+        registry.add_output::<f32>("result").build();
+        registry.register_config::<TrigConfig>();
+    }
+
+    fn configure(
+        &mut self,
+        _ctx: &ExecutionContext,
+        config: Config,
+        registry: &mut SignatureRegistery,
+    ) -> Result<()> {
+        let cfg = TrigConfig::try_extract(config)?;
+        self.operation = cfg.operation;
+        self.unit = cfg.unit;
+
+        registry.clear_inputs();
+        registry.clear_outputs();
+
+        match cfg.operation {
+            TrigOp::Sin | TrigOp::Cos | TrigOp::Tan => {
+                registry
+                    .add_input::<f32>("angle")
+                    .meta(angle_meta(cfg.unit))
+                    .build();
+            }
+            TrigOp::Asin | TrigOp::Acos => {
+                registry.add_input::<f32>("a").meta(UNIT_META).build();
+            }
+            TrigOp::Atan | TrigOp::Sinh | TrigOp::Cosh | TrigOp::Tanh => {
+                registry.add_input::<f32>("a").meta(F32_META).build();
+            }
+            TrigOp::Atan2 => {
+                registry.add_input::<f32>("y").meta(F32_META).build();
+                registry.add_input::<f32>("x").meta(F32_META).build();
+            }
+            // Conversions ignore the unit config, their units are in the name
+            TrigOp::ToDegrees => {
+                registry
+                    .add_input::<f32>("radians")
+                    .meta(angle_meta(AngleUnit::Radians))
+                    .build();
+            }
+            TrigOp::ToRadians => {
+                registry
+                    .add_input::<f32>("degrees")
+                    .meta(angle_meta(AngleUnit::Degrees))
+                    .build();
+            }
+        }
+
+        match cfg.operation {
+            TrigOp::Asin | TrigOp::Acos | TrigOp::Atan | TrigOp::Atan2 => {
+                let half_turn = match cfg.unit {
+                    AngleUnit::Radians => PI,
+                    AngleUnit::Degrees => 180.0,
+                };
+                registry
+                    .add_output::<f32>("angle")
+                    .meta(Angle {
+                        min: -half_turn,
+                        max: half_turn,
+                        unit: cfg.unit,
+                    })
+                    .build();
+            }
+            _ => registry.add_output::<f32>("result").build(),
+        }
+
+        Ok(())
+    }
+
+    fn execute(
+        &mut self,
+        _ctx: &mut ExecutionContext,
+        inputs: Inputs,
+        mut outputs: Outputs,
+    ) -> Result<()> {
+        let op = self.operation;
+        let degrees = self.unit == AngleUnit::Degrees;
+
+        let mut a: f32 = inputs.extract(0)?;
+        let b: f32 = inputs.extract(1).unwrap_or(0.);
+        if degrees && op.takes_angle() {
+            a = a.to_radians();
+        }
+
+        let mut result = op.apply(a, b);
+        if degrees && op.gives_angle() {
+            result = result.to_degrees();
+        }
+
+        *outputs.extract::<f32>(0)? = result;
+        Ok(())
+    }
+}
+
+impl OperationFactory for Trig {
+    const LIBRARY: &'static str = "math";
+    const OPERATOR: &'static str = "trig";
+    const LABEL: &'static str = "Trigonometry";
+
+    fn build() -> Result<Box<dyn Operation>> {
+        Ok(Box::new(Trig {
+            operation: TrigOp::Sin,
+            unit: AngleUnit::Radians,
+        }))
+    }
+}
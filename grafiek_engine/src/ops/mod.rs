@@ -1,8 +1,25 @@
 mod graphics;
+mod logic;
 mod math;
+mod random;
+mod signal;
 mod system;
+mod time;
+mod value;
 
-pub use graphics::shade::Grayscale;
+pub use graphics::color_space::ConvertColorSpace;
+pub use graphics::shade::{
+    Custom, Fbm, Grayscale, PerlinNoise, SimplexNoise, ValueNoise, WorleyNoise,
+};
+pub use graphics::user_shader::{USER_LIBRARY, UserShader};
+pub use logic::*;
 pub use math::*;
+pub use random::{Distribution, RandomValue};
+pub use signal::*;
+pub use system::file_output::{BitDepth, FileFormat, FileOutput, WriteMode};
+pub use system::image_file::ImageFile;
+pub use system::image_sequence::{ImageSequence, SequenceMode};
 pub use system::input::*;
 pub use system::output::Output;
+pub use time::*;
+pub use value::constant::{Constant, ConstantType};
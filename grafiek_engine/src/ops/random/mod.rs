@@ -0,0 +1,3 @@
+pub mod value;
+
+pub use value::{Distribution, RandomValue};
@@ -0,0 +1,163 @@
+use crate::ConfigSchema;
+use crate::EnumSchema;
+use crate::ExecutionContext;
+use crate::error::Result;
+use crate::registry::{FloatRange, IntRange, SignatureRegistery};
+use crate::traits::{OpPath, Operation, OperationFactory};
+use crate::value::{Config, Inputs, InputsExt, Outputs, OutputsExt};
+
+const F32_META: FloatRange = FloatRange {
+    min: f32::MIN,
+    max: f32::MAX,
+    step: 0.1,
+};
+
+const I32_META: IntRange = IntRange {
+    min: i32::MIN,
+    max: i32::MAX,
+    step: 1,
+};
+
+/// A random number drawn from the engine's seed and the `seed` input, so the
+/// same seeds always give the same value. Nodes with the same seed agree.
+#[derive(Default)]
+pub struct RandomValue {
+    pub distribution: Distribution,
+    pub reseed_each_frame: bool,
+}
+
+#[derive(EnumSchema, Default, Copy, Clone)]
+pub enum Distribution {
+    /// Any float between `min` and `max` equally likely
+    #[default]
+    Uniform = 0,
+    /// Bell shaped around `mean`
+    Normal,
+    /// Any integer from `min` to `max` inclusive equally likely
+    Integer,
+}
+
+#[derive(ConfigSchema)]
+struct RandomConfig {
+    #[label("")]
+    #[on_node_body]
+    distribution: Distribution,
+
+    /// Draw a new value every frame, otherwise it only changes with the seed
+    #[label("reseed each frame")]
+    reseed_each_frame: bool,
+}
+
+impl Operation for RandomValue {
+    // Drawn fresh from the seeds every execution
+    fn is_stateful(&self) -> bool {
+        false
+    }
+
+    fn op_path(&self) -> OpPath {
+        <Self as OperationFactory>::op_path()
+    }
+
+    fn setup(&mut self, _ctx: &mut ExecutionContext, registry: &mut SignatureRegistery) {
+        registry.add_input::<i32>("seed").meta(I32_META).build();
+        registry.add_input::<f32>("min").meta(F32_META).build();
+        registry
+            .add_input::<f32>("max")
+            .meta(F32_META)
+            .default(1.0)
+            .build();
+        registry.add_output::<f32>("value").build();
+        registry.register_config::<RandomConfig>();
+    }
+
+    fn configure(
+        &mut self,
+        _ctx: &ExecutionContext,
+        config: Config,
+        registry: &mut SignatureRegistery,
+    ) -> Result<()> {
+        let cfg = RandomConfig::try_extract(config)?;
+        self.distribution = cfg.distribution;
+        self.reseed_each_frame = cfg.reseed_each_frame;
+
+        registry.clear_inputs();
+        registry.clear_outputs();
+        registry.add_input::<i32>("seed").meta(I32_META).build();
+        match cfg.distribution {
+            Distribution::Uniform => {
+                registry.add_input::<f32>("min").meta(F32_META).build();
+                registry
+                    .add_input::<f32>("max")
+                    .meta(F32_META)
+                    .default(1.0)
+                    .build();
+                registry.add_output::<f32>("value").build();
+            }
+            Distribution::Normal => {
+                registry.add_input::<f32>("mean").meta(F32_META).build();
+                registry
+                    .add_input::<f32>("deviation")
+                    .meta(FloatRange {
+                        min: 0.0,
+                        max: f32::MAX,
+                        step: 0.1,
+                    })
+                    .default(1.0)
+                    .build();
+                registry.add_output::<f32>("value").build();
+            }
+            Distribution::Integer => {
+                registry.add_input::<i32>("min").meta(I32_META).build();
+                registry
+                    .add_input::<i32>("max")
+                    .meta(I32_META)
+                    .default(10)
+                    .build();
+                registry.add_output::<i32>("value").build();
+            }
+        }
+        Ok(())
+    }
+
+    fn execute(
+        &mut self,
+        ctx: &mut ExecutionContext,
+        inputs: Inputs,
+        mut outputs: Outputs,
+    ) -> Result<()> {
+        let seed: i32 = inputs.extract(0)?;
+        let mut rng = ctx.rng(seed as u32 as u64);
+        if self.reseed_each_frame {
+            rng = rng.derive(ctx.timing().frame);
+        }
+
+        match self.distribution {
+            Distribution::Uniform => {
+                let min: f32 = inputs.extract(1)?;
+                let max: f32 = inputs.extract(2)?;
+                *outputs.extract::<f32>(0)? = min + (max - min) * rng.next_f32();
+            }
+            Distribution::Normal => {
+                let mean: f32 = inputs.extract(1)?;
+                let deviation: f32 = inputs.extract(2)?;
+                *outputs.extract::<f32>(0)? = mean + deviation * rng.normal();
+            }
+            Distribution::Integer => {
+                let min: i32 = inputs.extract(1)?;
+                let max: i32 = inputs.extract(2)?;
+                *outputs.extract::<i32>(0)? = rng.range_i32(min, max);
+            }
+        }
+        Ok(())
+    }
+}
+
+impl OperationFactory for RandomValue {
+    const LIBRARY: &'static str = "random";
+    const OPERATOR: &'static str = "value";
+    const LABEL: &'static str = "Random Value";
+
+    fn build() -> Result<Box<dyn Operation>> {
+        Ok(Box::new(RandomValue::default()))
+    }
+}
@@ -0,0 +1,152 @@
+use super::edge::{EdgeDetector, EdgeKind};
+use crate::ConfigSchema;
+use crate::ExecutionContext;
+use crate::error::Result;
+use crate::registry::{FloatRange, IntRange, SignatureRegistery};
+use crate::traits::{OpPath, Operation, OperationFactory};
+use crate::value::{Config, Inputs, InputsExt, Outputs, OutputsExt};
+
+/// Adds its input to a running total every execution, zeroed while `reset` is true.
+#[derive(Default)]
+pub struct Accumulator {
+    pub per_second: bool,
+    total: f32,
+}
+
+#[derive(ConfigSchema)]
+struct AccumulatorConfig {
+    /// Scale the amount by the frame's delta time, so it's added per second
+    /// rather than per frame
+    #[on_node_body]
+    #[label("per second")]
+    per_second: bool,
+}
+
+impl Operation for Accumulator {
+    fn is_stateful(&self) -> bool {
+        true
+    }
+
+    fn op_path(&self) -> OpPath {
+        <Self as OperationFactory>::op_path()
+    }
+
+    fn setup(&mut self, _ctx: &mut ExecutionContext, registry: &mut SignatureRegistery) {
+        registry
+            .add_input::<f32>("amount")
+            .meta(FloatRange {
+                min: f32::MIN,
+                max: f32::MAX,
+                step: 0.1,
+            })
+            .default(1.0)
+            .build();
+        registry.add_input::<bool>("reset").build();
+        registry.add_output::<f32>("total").build();
+        registry.register_config::<AccumulatorConfig>();
+    }
+
+    fn configure(
+        &mut self,
+        _ctx: &ExecutionContext,
+        config: Config,
+        _registry: &mut SignatureRegistery,
+    ) -> Result<()> {
+        let cfg = AccumulatorConfig::try_extract(config)?;
+        self.per_second = cfg.per_second;
+        Ok(())
+    }
+
+    fn reset(&mut self) {
+        self.total = 0.0;
+    }
+
+    fn execute(
+        &mut self,
+        ctx: &mut ExecutionContext,
+        inputs: Inputs,
+        mut outputs: Outputs,
+    ) -> Result<()> {
+        let mut amount: f32 = inputs.extract(0)?;
+        let reset: bool = inputs.extract(1)?;
+        if self.per_second {
+            amount *= ctx.timing().delta;
+        }
+
+        self.total = if reset { 0.0 } else { self.total + amount };
+        *outputs.extract::<f32>(0)? = self.total;
+        Ok(())
+    }
+}
+
+impl OperationFactory for Accumulator {
+    const LIBRARY: &'static str = "signal";
+    const OPERATOR: &'static str = "accumulator";
+    const LABEL: &'static str = "Accumulator";
+
+    fn build() -> Result<Box<dyn Operation>> {
+        Ok(Box::new(Accumulator::default()))
+    }
+}
+
+/// Counts rising edges of its trigger, zeroed while `reset` is true.
+#[derive(Default)]
+pub struct Counter {
+    count: i32,
+    trigger: EdgeDetector,
+}
+
+impl Operation for Counter {
+    fn is_stateful(&self) -> bool {
+        true
+    }
+
+    fn op_path(&self) -> OpPath {
+        <Self as OperationFactory>::op_path()
+    }
+
+    fn setup(&mut self, _ctx: &mut ExecutionContext, registry: &mut SignatureRegistery) {
+        registry.add_input::<bool>("trigger").build();
+        registry
+            .add_input::<i32>("step")
+            .meta(IntRange::default())
+            .default(1)
+            .build();
+        registry.add_input::<bool>("reset").build();
+        registry.add_output::<i32>("count").build();
+    }
+
+    fn reset(&mut self) {
+        *self = Self::default();
+    }
+
+    fn execute(
+        &mut self,
+        _ctx: &mut ExecutionContext,
+        inputs: Inputs,
+        mut outputs: Outputs,
+    ) -> Result<()> {
+        let trigger: bool = inputs.extract(0)?;
+        let step: i32 = inputs.extract(1)?;
+        let reset: bool = inputs.extract(2)?;
+
+        let triggered = self.trigger.update(trigger, EdgeKind::Rising);
+        if reset {
+            self.count = 0;
+        } else if triggered {
+            self.count = self.count.wrapping_add(step);
+        }
+        *outputs.extract::<i32>(0)? = self.count;
+        Ok(())
+    }
+}
+
+impl OperationFactory for Counter {
+    const LIBRARY: &'static str = "signal";
+    const OPERATOR: &'static str = "counter";
+    const LABEL: &'static str = "Counter";
+
+    fn build() -> Result<Box<dyn Operation>> {
+        Ok(Box::new(Counter::default()))
+    }
+}
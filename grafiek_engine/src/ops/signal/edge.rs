@@ -0,0 +1,103 @@
+use crate::ConfigSchema;
+use crate::EnumSchema;
+use crate::ExecutionContext;
+use crate::error::Result;
+use crate::registry::SignatureRegistery;
+use crate::traits::{OpPath, Operation, OperationFactory};
+use crate::value::{Config, Inputs, InputsExt, Outputs, OutputsExt};
+
+/// Which changes of a bool count as an edge.
+#[derive(EnumSchema, Default, Copy, Clone)]
+pub enum EdgeKind {
+    /// False to true
+    #[default]
+    Rising = 0,
+    /// True to false
+    Falling,
+    Either,
+}
+
+/// Remembers a bool between executions to find where it changes. It starts
+/// out false, so a signal that is true from the start rises once.
+#[derive(Default)]
+pub(crate) struct EdgeDetector {
+    previous: bool,
+}
+
+impl EdgeDetector {
+    /// True if `value` crossed an edge of `kind` since the last update.
+    pub(crate) fn update(&mut self, value: bool, kind: EdgeKind) -> bool {
+        let previous = std::mem::replace(&mut self.previous, value);
+        match kind {
+            EdgeKind::Rising => value && !previous,
+            EdgeKind::Falling => !value && previous,
+            EdgeKind::Either => value != previous,
+        }
+    }
+}
+
+/// True for a single execution when its input changes.
+#[derive(Default)]
+pub struct EdgeDetect {
+    pub kind: EdgeKind,
+    detector: EdgeDetector,
+}
+
+#[derive(ConfigSchema)]
+struct EdgeConfig {
+    #[label("")]
+    #[on_node_body]
+    kind: EdgeKind,
+}
+
+impl Operation for EdgeDetect {
+    fn is_stateful(&self) -> bool {
+        true
+    }
+
+    fn op_path(&self) -> OpPath {
+        <Self as OperationFactory>::op_path()
+    }
+
+    fn setup(&mut self, _ctx: &mut ExecutionContext, registry: &mut SignatureRegistery) {
+        registry.add_input::<bool>("signal").build();
+        registry.add_output::<bool>("edge").build();
+        registry.register_config::<EdgeConfig>();
+    }
+
+    fn configure(
+        &mut self,
+        _ctx: &ExecutionContext,
+        config: Config,
+        _registry: &mut SignatureRegistery,
+    ) -> Result<()> {
+        let cfg = EdgeConfig::try_extract(config)?;
+        self.kind = cfg.kind;
+        Ok(())
+    }
+
+    fn reset(&mut self) {
+        self.detector = EdgeDetector::default();
+    }
+
+    fn execute(
+        &mut self,
+        _ctx: &mut ExecutionContext,
+        inputs: Inputs,
+        mut outputs: Outputs,
+    ) -> Result<()> {
+        let signal: bool = inputs.extract(0)?;
+        *outputs.extract::<bool>(0)? = self.detector.update(signal, self.kind);
+        Ok(())
+    }
+}
+
+impl OperationFactory for EdgeDetect {
+    const LIBRARY: &'static str = "signal";
+    const OPERATOR: &'static str = "edge";
+    const LABEL: &'static str = "Edge Detect";
+
+    fn build() -> Result<Box<dyn Operation>> {
+        Ok(Box::new(EdgeDetect::default()))
+    }
+}
@@ -0,0 +1,9 @@
+pub mod accumulator;
+pub mod edge;
+pub mod sample_hold;
+pub mod smooth;
+
+pub use accumulator::{Accumulator, Counter};
+pub use edge::{EdgeDetect, EdgeKind};
+pub use sample_hold::SampleHold;
+pub use smooth::{Smooth, SmoothMode};
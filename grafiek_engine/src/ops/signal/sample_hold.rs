@@ -0,0 +1,65 @@
+use super::edge::{EdgeDetector, EdgeKind};
+use crate::ExecutionContext;
+use crate::error::Result;
+use crate::registry::{FloatRange, SignatureRegistery};
+use crate::traits::{OpPath, Operation, OperationFactory};
+use crate::value::{Inputs, InputsExt, Outputs, OutputsExt};
+
+const F32_META: FloatRange = FloatRange {
+    min: f32::MIN,
+    max: f32::MAX,
+    step: 0.1,
+};
+
+/// Takes its input when the trigger rises and holds it until the next rise.
+/// Holds 0 until first triggered.
+#[derive(Default)]
+pub struct SampleHold {
+    held: f32,
+    trigger: EdgeDetector,
+}
+
+impl Operation for SampleHold {
+    fn is_stateful(&self) -> bool {
+        true
+    }
+
+    fn op_path(&self) -> OpPath {
+        <Self as OperationFactory>::op_path()
+    }
+
+    fn setup(&mut self, _ctx: &mut ExecutionContext, registry: &mut SignatureRegistery) {
+        registry.add_input::<f32>("value").meta(F32_META).build();
+        registry.add_input::<bool>("trigger").build();
+        registry.add_output::<f32>("value").build();
+    }
+
+    fn reset(&mut self) {
+        *self = Self::default();
+    }
+
+    fn execute(
+        &mut self,
+        _ctx: &mut ExecutionContext,
+        inputs: Inputs,
+        mut outputs: Outputs,
+    ) -> Result<()> {
+        let value: f32 = inputs.extract(0)?;
+        let trigger: bool = inputs.extract(1)?;
+        if self.trigger.update(trigger, EdgeKind::Rising) {
+            self.held = value;
+        }
+        *outputs.extract::<f32>(0)? = self.held;
+        Ok(())
+    }
+}
+
+impl OperationFactory for SampleHold {
+    const LIBRARY: &'static str = "signal";
+    const OPERATOR: &'static str = "sample_hold";
+    const LABEL: &'static str = "Sample & Hold";
+
+    fn build() -> Result<Box<dyn Operation>> {
+        Ok(Box::new(SampleHold::default()))
+    }
+}
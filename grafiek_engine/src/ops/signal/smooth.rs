@@ -0,0 +1,171 @@
+use crate::ConfigSchema;
+use crate::EnumSchema;
+use crate::ExecutionContext;
+use crate::error::Result;
+use crate::registry::{FloatRange, SignatureRegistery};
+use crate::traits::{OpPath, Operation, OperationFactory};
+use crate::value::{Config, Inputs, InputsExt, Outputs, OutputsExt};
+
+const F32_META: FloatRange = FloatRange {
+    min: f32::MIN,
+    max: f32::MAX,
+    step: 0.1,
+};
+
+/// Longest time step a spring is integrated over, longer frames are split up
+/// so stiff springs don't blow up.
+const MAX_SPRING_STEP: f32 = 1.0 / 120.0;
+
+/// Longest frame smoothed over, a stall or bogus delta time counts as this
+/// long so springs take at most 120 steps.
+const MAX_DELTA: f32 = 1.0;
+
+/// Follows its target over time, using the frame's delta time so the motion
+/// doesn't depend on the frame rate. Jumps to the target on the first
+/// execution after a reset.
+#[derive(Default)]
+pub struct Smooth {
+    pub mode: SmoothMode,
+    value: Option<f32>,
+    velocity: f32,
+}
+
+#[derive(EnumSchema, Default, Copy, Clone, PartialEq)]
+pub enum SmoothMode {
+    /// Closes a fixed share of the distance every second, never overshoots
+    #[default]
+    Exponential = 0,
+    /// A damped spring, overshoots and settles when lightly damped
+    Spring,
+}
+
+#[derive(ConfigSchema)]
+struct SmoothConfig {
+    #[label("")]
+    #[on_node_body]
+    mode: SmoothMode,
+}
+
+impl Smooth {
+    fn step(&mut self, value: f32, target: f32, a: f32, b: f32, delta: f32) -> f32 {
+        match self.mode {
+            // `a` is the time taken to close about 63% of the distance
+            SmoothMode::Exponential if a <= 0.0 => target,
+            SmoothMode::Exponential => value + (target - value) * (1.0 - (-delta / a).exp()),
+            // `a` is the stiffness and `b` the damping
+            SmoothMode::Spring => {
+                let steps = (delta / MAX_SPRING_STEP).ceil().max(1.0);
+                let dt = delta / steps;
+                let mut value = value;
+                for _ in 0..steps as u32 {
+                    let acceleration = a * (target - value) - b * self.velocity;
+                    self.velocity += acceleration * dt;
+                    value += self.velocity * dt;
+                }
+                value
+            }
+        }
+    }
+}
+
+impl Operation for Smooth {
+    fn is_stateful(&self) -> bool {
+        true
+    }
+
+    fn op_path(&self) -> OpPath {
+        <Self as OperationFactory>::op_path()
+    }
+
+    fn setup(&mut self, _ctx: &mut ExecutionContext, registry: &mut SignatureRegistery) {
+        registry.add_input::<f32>("target").meta(F32_META).build();
+        registry.add_output::<f32>("value").build();
+        registry.register_config::<SmoothConfig>();
+    }
+
+    fn configure(
+        &mut self,
+        _ctx: &ExecutionContext,
+        config: Config,
+        registry: &mut SignatureRegistery,
+    ) -> Result<()> {
+        let cfg = SmoothConfig::try_extract(config)?;
+        if cfg.mode != self.mode {
+            self.velocity = 0.0;
+        }
+        self.mode = cfg.mode;
+
+        registry.clear_inputs();
+        registry.add_input::<f32>("target").meta(F32_META).build();
+        match cfg.mode {
+            SmoothMode::Exponential => registry
+                .add_input::<f32>("response")
+                .meta(FloatRange {
+                    min: 0.0,
+                    max: 10.0,
+                    step: 0.01,
+                })
+                .default(0.25)
+                .build(),
+            SmoothMode::Spring => {
+                registry
+                    .add_input::<f32>("stiffness")
+                    .meta(FloatRange {
+                        min: 0.0,
+                        max: 1000.0,
+                        step: 1.0,
+                    })
+                    .default(100.0)
+                    .build();
+                registry
+                    .add_input::<f32>("damping")
+                    .meta(FloatRange {
+                        min: 0.0,
+                        max: 100.0,
+                        step: 0.1,
+                    })
+                    .default(10.0)
+                    .build();
+            }
+        }
+        Ok(())
+    }
+
+    fn reset(&mut self) {
+        self.value = None;
+        self.velocity = 0.0;
+    }
+
+    fn execute(
+        &mut self,
+        ctx: &mut ExecutionContext,
+        inputs: Inputs,
+        mut outputs: Outputs,
+    ) -> Result<()> {
+        let target: f32 = inputs.extract(0)?;
+        let a: f32 = inputs.extract(1)?;
+        let b: f32 = inputs.extract(2).unwrap_or(0.);
+        let delta = match ctx.timing().delta {
+            delta if delta.is_nan() => 0.0,
+            delta => delta.clamp(0.0, MAX_DELTA),
+        };
+
+        let value = match self.value {
+            Some(value) => self.step(value, target, a, b, delta),
+            None => target,
+        };
+        self.value = Some(value);
+        *outputs.extract::<f32>(0)? = value;
+        Ok(())
+    }
+}
+
+impl OperationFactory for Smooth {
+    const LIBRARY: &'static str = "signal";
+    const OPERATOR: &'static str = "smooth";
+    const LABEL: &'static str = "Smooth";
+
+    fn build() -> Result<Box<dyn Operation>> {
+        Ok(Box::new(Smooth::default()))
+    }
+}
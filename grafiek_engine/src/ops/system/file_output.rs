@@ -0,0 +1,371 @@
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+use image::{DynamicImage, ImageFormat};
+
+use super::image_sequence::Pattern;
+use crate::error::{Error, Result};
+use crate::registry::SignatureRegistery;
+use crate::traits::{OpPath, Operation, OperationFactory};
+use crate::value::{Config, Inputs, InputsExt, Outputs};
+use crate::{
+    ColorSpace, CommonMetadata, ConfigSchema, EnumSchema, ExecutionContext, TextureFormat,
+    TextureHandle, ValueRef,
+};
+
+/// File format to write, [FileFormat::Auto] goes by the file extension.
+#[derive(EnumSchema, Default, Copy, Clone, PartialEq, Debug)]
+pub enum FileFormat {
+    #[default]
+    Auto = 0,
+    Png,
+    Tiff,
+    Exr,
+    Hdr,
+    Jpeg,
+    Csv,
+    Json,
+}
+
+/// Bits per channel for images. Formats that only store some depths use the closest one.
+#[derive(EnumSchema, Default, Copy, Clone, PartialEq)]
+pub enum BitDepth {
+    #[default]
+    Eight = 0,
+    Sixteen,
+    Float,
+}
+
+#[derive(EnumSchema, Default, Copy, Clone, PartialEq)]
+pub enum WriteMode {
+    /// Write every time the graph executes
+    #[default]
+    Always = 0,
+    /// Write when the `write` input turns on
+    OnTrigger,
+}
+
+#[derive(ConfigSchema)]
+struct FileOutputConfig {
+    /// Where to write, a run of `#` is replaced with the frame number.
+    /// CSV rows are appended, the file starts over on the first write after
+    /// the node is configured or the engine is reset.
+    #[on_node_body]
+    #[label("file")]
+    path: String,
+
+    format: FileFormat,
+
+    #[label("bit depth")]
+    depth: BitDepth,
+
+    #[on_node_body]
+    #[label("write")]
+    mode: WriteMode,
+}
+
+impl FileFormat {
+    const ALL: [FileFormat; 7] = [
+        FileFormat::Png,
+        FileFormat::Tiff,
+        FileFormat::Exr,
+        FileFormat::Hdr,
+        FileFormat::Jpeg,
+        FileFormat::Csv,
+        FileFormat::Json,
+    ];
+
+    fn extension(self) -> &'static str {
+        match self {
+            FileFormat::Auto => "",
+            FileFormat::Png => "png",
+            FileFormat::Tiff => "tiff",
+            FileFormat::Exr => "exr",
+            FileFormat::Hdr => "hdr",
+            FileFormat::Jpeg => "jpg",
+            FileFormat::Csv => "csv",
+            FileFormat::Json => "json",
+        }
+    }
+
+    fn from_path(path: &Path) -> Option<Self> {
+        let extension = path.extension()?.to_str()?.to_ascii_lowercase();
+        match extension.as_str() {
+            "tif" => Some(FileFormat::Tiff),
+            "jpeg" => Some(FileFormat::Jpeg),
+            ext => Self::ALL.into_iter().find(|f| f.extension() == ext),
+        }
+    }
+
+    fn image_format(self) -> Option<ImageFormat> {
+        match self {
+            FileFormat::Png => Some(ImageFormat::Png),
+            FileFormat::Tiff => Some(ImageFormat::Tiff),
+            FileFormat::Exr => Some(ImageFormat::OpenExr),
+            FileFormat::Hdr => Some(ImageFormat::Hdr),
+            FileFormat::Jpeg => Some(ImageFormat::Jpeg),
+            FileFormat::Auto | FileFormat::Csv | FileFormat::Json => None,
+        }
+    }
+
+    /// The closest depth to `depth` this format can store.
+    fn depth(self, depth: BitDepth) -> BitDepth {
+        match (self, depth) {
+            (FileFormat::Exr | FileFormat::Hdr, _) => BitDepth::Float,
+            (FileFormat::Jpeg, _) => BitDepth::Eight,
+            (FileFormat::Png, BitDepth::Float) => BitDepth::Sixteen,
+            (_, depth) => depth,
+        }
+    }
+}
+
+/// Writes its input to disk, textures as images and other values as CSV or JSON.
+#[derive(Default)]
+pub struct FileOutput {
+    path: String,
+    format: FileFormat,
+    depth: BitDepth,
+    mode: WriteMode,
+    /// Last value of the `write` input, files are written when it turns on
+    triggered: bool,
+    /// The next CSV write replaces the file rather than appending to it
+    restart: bool,
+    /// Copy of the input in the format and colour space being written
+    converted: TextureHandle,
+}
+
+impl FileOutput {
+    /// The file to write for `frame`, with the format's extension added if it has none.
+    fn resolve(&self, frame: u64) -> Result<(PathBuf, FileFormat)> {
+        let mut path = match Pattern::parse(&self.path) {
+            Some(pattern) => pattern.path(frame),
+            None => PathBuf::from(&self.path),
+        };
+
+        let format = match self.format {
+            FileFormat::Auto => FileFormat::from_path(&path).ok_or_else(|| {
+                Error::EncodeImage(format!("no known file extension on {path:?}"))
+            })?,
+            format => format,
+        };
+        if path.extension().is_none() {
+            path.set_extension(format.extension());
+        }
+
+        if let Some(dir) = path.parent().filter(|d| !d.as_os_str().is_empty()) {
+            std::fs::create_dir_all(dir)?;
+        }
+        Ok((path, format))
+    }
+
+    fn write_texture(
+        &mut self,
+        ctx: &mut ExecutionContext,
+        input: &TextureHandle,
+        path: &Path,
+        format: FileFormat,
+    ) -> Result<()> {
+        let Some(image_format) = format.image_format() else {
+            return Err(Error::CannotWrite {
+                value: "texture".into(),
+                format: format!("{format:?}"),
+            });
+        };
+
+        // Integer formats hold sRGB encoded values, float formats linear light
+        let depth = format.depth(self.depth);
+        let (fmt, color_space) = match depth {
+            BitDepth::Eight => (TextureFormat::RGBAu8, ColorSpace::Srgb),
+            BitDepth::Sixteen => (TextureFormat::RGBAu16, ColorSpace::Srgb),
+            BitDepth::Float => (TextureFormat::RGBAF32, ColorSpace::Linear),
+        };
+        // Without 16 bit normalized textures the image crate narrows from float
+        self.converted.fmt = match ctx.supports_format(fmt) {
+            true => fmt,
+            false => TextureFormat::RGBAF32,
+        };
+        self.converted.color_space = color_space;
+        ctx.convert_texture(input, &mut self.converted);
+
+        let image = ctx.read_texture(&self.converted)?.to_image()?;
+        let image = match format {
+            FileFormat::Jpeg => DynamicImage::from(image.to_rgb8()),
+            FileFormat::Hdr => DynamicImage::from(image.to_rgb32f()),
+            _ => match depth {
+                BitDepth::Eight => DynamicImage::from(image.to_rgba8()),
+                BitDepth::Sixteen => DynamicImage::from(image.to_rgba16()),
+                BitDepth::Float => DynamicImage::from(image.to_rgba32f()),
+            },
+        };
+
+        image
+            .save_with_format(path, image_format)
+            .map_err(|e| Error::EncodeImage(format!("{path:?}: {e}")))
+    }
+
+    fn write_value(
+        &mut self,
+        ctx: &ExecutionContext,
+        value: ValueRef,
+        path: &Path,
+        format: FileFormat,
+    ) -> Result<()> {
+        let cannot_write = || Error::CannotWrite {
+            value: value_name(value).into(),
+            format: format!("{format:?}"),
+        };
+        let text = match value {
+            ValueRef::I32(v) => v.to_string(),
+            // JSON has no NaN or infinity
+            ValueRef::F32(v) if format == FileFormat::Json && !v.is_finite() => "null".into(),
+            ValueRef::F32(v) => v.to_string(),
+            ValueRef::Bool(v) => v.to_string(),
+            ValueRef::String(v) => json_string(v),
+            _ => return Err(cannot_write()),
+        };
+
+        let timing = ctx.timing();
+        match format {
+            // One row per write, appended to the file
+            FileFormat::Csv => {
+                let mut file = std::fs::OpenOptions::new()
+                    .create(true)
+                    .append(!self.restart)
+                    .write(true)
+                    .truncate(self.restart)
+                    .open(path)?;
+                self.restart = false;
+                if file.metadata()?.len() == 0 {
+                    writeln!(file, "frame,time,value")?;
+                }
+                writeln!(file, "{},{},{text}", timing.frame, timing.time)?;
+            }
+            FileFormat::Json => {
+                let json = format!(
+                    "{{\"frame\": {}, \"time\": {}, \"value\": {text}}}\n",
+                    timing.frame, timing.time
+                );
+                std::fs::write(path, json)?;
+            }
+            _ => return Err(cannot_write()),
+        }
+        Ok(())
+    }
+}
+
+fn value_name(value: ValueRef) -> &'static str {
+    match value {
+        ValueRef::I32(_) => "i32",
+        ValueRef::F32(_) => "f32",
+        ValueRef::Bool(_) => "bool",
+        ValueRef::Texture(_) => "texture",
+        ValueRef::Buffer(_) => "buffer",
+        ValueRef::String(_) => "string",
+        ValueRef::Null(_) => "null",
+    }
+}
+
+/// Quote a string for JSON, which also reads fine in a CSV cell.
+fn json_string(s: &str) -> String {
+    let mut out = String::with_capacity(s.len() + 2);
+    out.push('"');
+    for c in s.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            c if (c as u32) < 0x20 => out.push_str(&format!("\\u{:04x}", c as u32)),
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+    out
+}
+
+impl Operation for FileOutput {
+    // Remembers the trigger so a file is written once per rising edge
+    fn is_stateful(&self) -> bool {
+        true
+    }
+
+    fn op_path(&self) -> OpPath {
+        <Self as OperationFactory>::op_path()
+    }
+
+    fn setup(&mut self, _ctx: &mut ExecutionContext, registry: &mut SignatureRegistery) {
+        registry.push_input_raw(crate::SlotDef {
+            value_type: crate::ValueType::Any,
+            name: "value".into(),
+            extended: crate::ExtendedMetadata::None,
+            common: CommonMetadata::default(),
+            default_override: None,
+        });
+        registry.register_config::<FileOutputConfig>();
+    }
+
+    fn configure(
+        &mut self,
+        _ctx: &ExecutionContext,
+        config: Config,
+        registry: &mut SignatureRegistery,
+    ) -> Result<()> {
+        let cfg = FileOutputConfig::try_extract(config)?;
+        self.path = cfg.path;
+        self.format = cfg.format;
+        self.depth = cfg.depth;
+
+        self.mode = cfg.mode;
+        self.restart = true;
+        registry.truncate_inputs(1);
+        if self.mode == WriteMode::OnTrigger {
+            registry.add_input::<bool>("write").build();
+        }
+        Ok(())
+    }
+
+    fn reset(&mut self) {
+        self.triggered = false;
+        self.restart = true;
+    }
+
+    fn execute(
+        &mut self,
+        ctx: &mut ExecutionContext,
+        inputs: Inputs,
+        _outputs: Outputs,
+    ) -> Result<()> {
+        if self.mode == WriteMode::OnTrigger {
+            let trigger: bool = inputs.extract(1)?;
+            let rising = trigger && !self.triggered;
+            self.triggered = trigger;
+            if !rising {
+                return Ok(());
+            }
+        }
+
+        let Some(&value) = inputs.first() else {
+            return Ok(());
+        };
+        if self.path.is_empty() || matches!(value, ValueRef::Null(_)) {
+            return Ok(());
+        }
+
+        let (path, format) = self.resolve(ctx.timing().frame)?;
+        match value {
+            ValueRef::Texture(handle) => self.write_texture(ctx, handle, &path, format),
+            value => self.write_value(ctx, value, &path, format),
+        }
+    }
+}
+
+impl OperationFactory for FileOutput {
+    const LIBRARY: &'static str = "core";
+    const OPERATOR: &'static str = "file_output";
+    const LABEL: &'static str = "File Output";
+
+    fn build() -> Result<Box<dyn Operation>> {
+        Ok(Box::new(FileOutput::default()))
+    }
+}
@@ -0,0 +1,123 @@
+use std::path::Path;
+
+use crate::error::Result;
+use crate::file_watch::FileWatch;
+use crate::registry::{SignatureRegistery, TextureMeta};
+use crate::traits::{OpPath, Operation, OperationFactory};
+use crate::value::{Config, Inputs, Outputs, OutputsExt};
+use crate::{ConfigSchema, ExecutionContext, ImageData, SPECK, TextureHandle, TextureId};
+
+/// Loads an image file, reloading it whenever the file changes on disk.
+#[derive(Default)]
+pub struct ImageFile {
+    image: Option<ImageData>,
+    watch: Option<FileWatch>,
+    /// Texture the current image was last uploaded to
+    uploaded: Option<TextureId>,
+}
+
+#[derive(ConfigSchema)]
+struct ImageFileConfig {
+    #[on_node_body]
+    #[label("file")]
+    path: String,
+}
+
+fn register_output(image: Option<&ImageData>, registry: &mut SignatureRegistery) {
+    registry.clear_outputs();
+    let output = registry
+        .add_output::<TextureHandle>("image")
+        .meta(TextureMeta {
+            preview: true,
+            allow_file: false,
+            ..Default::default()
+        });
+    match image {
+        Some(image) => output
+            .dimensions(image.width(), image.height())
+            .format(image.format())
+            .build(),
+        None => output.default(SPECK).build(),
+    }
+}
+
+impl Operation for ImageFile {
+    fn is_stateful(&self) -> bool {
+        false
+    }
+
+    fn op_path(&self) -> OpPath {
+        <Self as OperationFactory>::op_path()
+    }
+
+    fn needs_reconfigure(&mut self) -> bool {
+        self.watch.as_mut().is_some_and(FileWatch::changed)
+    }
+
+    fn setup(&mut self, _ctx: &mut ExecutionContext, registry: &mut SignatureRegistery) {
+        register_output(None, registry);
+        registry.register_config::<ImageFileConfig>();
+    }
+
+    fn configure(
+        &mut self,
+        ctx: &ExecutionContext,
+        config: Config,
+        registry: &mut SignatureRegistery,
+    ) -> Result<()> {
+        let cfg = ImageFileConfig::try_extract(config)?;
+        self.image = None;
+        self.uploaded = None;
+
+        if cfg.path.is_empty() {
+            self.watch = None;
+            register_output(None, registry);
+            return Ok(());
+        }
+
+        // The watch is kept even if loading fails so that fixing the file reloads it
+        let path = Path::new(&cfg.path);
+        if self.watch.as_ref().is_none_or(|w| w.path() != path) {
+            self.watch = Some(FileWatch::new(path));
+        }
+
+        let loaded =
+            ImageData::open(path).and_then(|image| match ctx.supports_format(image.format()) {
+                true => Ok(image),
+                false => image.to_float(),
+            });
+        register_output(loaded.as_ref().ok(), registry);
+        self.image = Some(loaded?);
+        Ok(())
+    }
+
+    fn execute(
+        &mut self,
+        ctx: &mut ExecutionContext,
+        _inputs: Inputs,
+        mut outputs: Outputs,
+    ) -> Result<()> {
+        let Some(image) = &self.image else {
+            return Ok(());
+        };
+
+        let output: &mut TextureHandle = outputs.extract(0)?;
+        if output.id.is_none() || output.id != self.uploaded {
+            ctx.upload_image(output, image)?;
+            self.uploaded = output.id;
+        }
+        // Reconfiguring resets the handle to the registered default
+        output.color_space = image.color_space();
+        Ok(())
+    }
+}
+
+impl OperationFactory for ImageFile {
+    const LIBRARY: &'static str = "core";
+    const OPERATOR: &'static str = "image_file";
+    const LABEL: &'static str = "Image File";
+
+    fn build() -> Result<Box<dyn Operation>> {
+        Ok(Box::new(ImageFile::default()))
+    }
+}
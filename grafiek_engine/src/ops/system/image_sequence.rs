@@ -0,0 +1,437 @@
+use std::collections::hash_map::Entry;
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::sync::mpsc;
+
+use crate::error::{Error, Result};
+use crate::file_watch::FileWatch;
+use crate::registry::{IntRange, SignatureRegistery, TextureMeta};
+use crate::traits::{OpPath, Operation, OperationFactory};
+use crate::value::{Config, Inputs, Outputs, OutputsExt};
+use crate::{
+    ConfigSchema, EnumSchema, ExecutionContext, ImageData, SPECK, TextureHandle, TextureId,
+};
+
+/// What happens when the timeline runs past either end of the sequence.
+#[derive(EnumSchema, Default, Copy, Clone, PartialEq)]
+pub enum SequenceMode {
+    /// Keep showing the first or last frame
+    #[default]
+    Hold = 0,
+    Loop,
+    /// Play back and forth
+    PingPong,
+}
+
+#[derive(ConfigSchema)]
+struct SequenceConfig {
+    /// Path to the frames with the frame number written as a run of `#`,
+    /// `frame_####.png` reads `frame_0001.png`, `frame_0002.png` and so on.
+    #[on_node_body]
+    #[label("files")]
+    pattern: String,
+
+    #[on_node_body]
+    mode: SequenceMode,
+
+    /// Frames added to the timeline frame before picking a file
+    offset: i32,
+
+    /// Frames decoded ahead of the current one in the background
+    #[meta(IntRange { min: 0, max: 64, step: 1 })]
+    #[default(4)]
+    prefetch: i32,
+
+    /// Decoded frames kept in memory
+    #[meta(IntRange { min: 1, max: 256, step: 1 })]
+    #[default(16)]
+    #[label("cache size")]
+    cache_size: i32,
+}
+
+/// A numbered file name split around its run of `#`.
+#[derive(Clone, PartialEq)]
+pub(crate) struct Pattern {
+    dir: PathBuf,
+    prefix: String,
+    suffix: String,
+    width: usize,
+}
+
+impl Pattern {
+    pub(crate) fn parse(pattern: &str) -> Option<Self> {
+        let path = Path::new(pattern);
+        let name = path.file_name()?.to_str()?;
+        let end = name.rfind('#')? + 1;
+        let start = name[..end].trim_end_matches('#').len();
+        Some(Self {
+            dir: path.parent().map(Path::to_path_buf).unwrap_or_default(),
+            prefix: name[..start].to_owned(),
+            suffix: name[end..].to_owned(),
+            width: end - start,
+        })
+    }
+
+    pub(crate) fn path(&self, number: u64) -> PathBuf {
+        let name = format!(
+            "{}{number:0width$}{}",
+            self.prefix,
+            self.suffix,
+            width = self.width
+        );
+        self.dir.join(name)
+    }
+
+    /// The frame number of `name`, if it belongs to the sequence.
+    fn number(&self, name: &str) -> Option<u64> {
+        let digits = name
+            .strip_prefix(&self.prefix)?
+            .strip_suffix(&self.suffix)?;
+        if digits.len() < self.width || !digits.bytes().all(|b| b.is_ascii_digit()) {
+            return None;
+        }
+        digits.parse().ok()
+    }
+
+    /// Directory holding the frames.
+    fn dir(&self) -> &Path {
+        match self.dir.as_os_str().is_empty() {
+            true => Path::new("."),
+            false => &self.dir,
+        }
+    }
+
+    /// First and last frame numbers on disk, None if no files match.
+    fn scan(&self) -> Result<Option<(u64, u64)>> {
+        let dir = self.dir();
+        let entries = std::fs::read_dir(dir).map_err(|source| Error::ReadFile {
+            path: dir.to_path_buf(),
+            source,
+        })?;
+
+        let numbers =
+            entries.filter_map(|e| e.ok()?.file_name().to_str().and_then(|n| self.number(n)));
+        Ok(numbers.fold(None, |range, n| match range {
+            None => Some((n, n)),
+            Some((first, last)) => Some((n.min(first), n.max(last))),
+        }))
+    }
+}
+
+impl SequenceMode {
+    /// Index into a sequence of `len` frames for timeline frame `t`.
+    fn index(self, t: i64, len: i64) -> i64 {
+        match self {
+            SequenceMode::Hold => t.clamp(0, len - 1),
+            SequenceMode::Loop => t.rem_euclid(len),
+            SequenceMode::PingPong if len == 1 => 0,
+            SequenceMode::PingPong => {
+                let period = 2 * (len - 1);
+                let t = t.rem_euclid(period);
+                if t < len { t } else { period - t }
+            }
+        }
+    }
+}
+
+/// A decoded frame and a watch on the file it was read from.
+struct Frame {
+    image: ImageData,
+    /// Started before decoding, so a frame re-rendered mid read is still caught
+    watch: FileWatch,
+}
+
+impl Frame {
+    fn open(path: PathBuf) -> Result<Self> {
+        let watch = FileWatch::new(&path);
+        let image = ImageData::open(path)?;
+        Ok(Self { image, watch })
+    }
+}
+
+type Loaded = (u64, Result<Frame>);
+
+/// Decodes frames on a worker thread, which exits once the loader is dropped.
+struct Loader {
+    #[cfg(not(target_arch = "wasm32"))]
+    requests: mpsc::Sender<(u64, PathBuf)>,
+    #[cfg(target_arch = "wasm32")]
+    loaded: mpsc::Sender<Loaded>,
+    results: mpsc::Receiver<Loaded>,
+}
+
+impl Loader {
+    #[cfg(not(target_arch = "wasm32"))]
+    fn spawn() -> Self {
+        let (requests, rx) = mpsc::channel::<(u64, PathBuf)>();
+        let (tx, results) = mpsc::channel();
+        std::thread::spawn(move || {
+            for (number, path) in rx {
+                if tx.send((number, Frame::open(path))).is_err() {
+                    break;
+                }
+            }
+        });
+        Self { requests, results }
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    fn request(&self, number: u64, path: PathBuf) {
+        let _ = self.requests.send((number, path));
+    }
+
+    #[cfg(target_arch = "wasm32")]
+    fn spawn() -> Self {
+        let (loaded, results) = mpsc::channel();
+        Self { loaded, results }
+    }
+
+    /// There are no threads to decode on, so this only decodes the frame in place.
+    #[cfg(target_arch = "wasm32")]
+    fn request(&self, number: u64, path: PathBuf) {
+        let _ = self.loaded.send((number, Frame::open(path)));
+    }
+}
+
+/// Reads a numbered image sequence, picking the frame from the timeline.
+#[derive(Default)]
+pub struct ImageSequence {
+    pattern: Option<Pattern>,
+    /// First and last frame numbers found on disk
+    range: Option<(u64, u64)>,
+    mode: SequenceMode,
+    offset: i64,
+    prefetch: usize,
+    cache_size: usize,
+    cache: HashMap<u64, Frame>,
+    /// Frames requested from the loader that haven't arrived yet
+    pending: HashSet<u64>,
+    loader: Option<Loader>,
+    /// Watches the directory so new frames are picked up, frames re-rendered
+    /// in place are caught by the watches on the cached frames
+    watch: Option<FileWatch>,
+    /// Frame number and texture the output was last uploaded with
+    uploaded: Option<(u64, TextureId)>,
+}
+
+impl ImageSequence {
+    /// Frame number on disk for timeline frame `frame`.
+    fn number(&self, frame: i64) -> u64 {
+        let (first, last) = self.range.unwrap_or_default();
+        let len = (last - first + 1) as i64;
+        first + self.mode.index(frame + self.offset, len) as u64
+    }
+
+    fn receive(&mut self, loaded: Loaded) -> Option<Error> {
+        let (number, result) = loaded;
+        self.pending.remove(&number);
+        match result {
+            Ok(frame) => {
+                self.cache.insert(number, frame);
+                None
+            }
+            Err(e) => Some(e),
+        }
+    }
+
+    /// Get frame `number`, waiting on the loader if it's already decoding it.
+    fn load(&mut self, pattern: &Pattern, number: u64) -> Result<&ImageData> {
+        while !self.cache.contains_key(&number) && self.pending.contains(&number) {
+            let Some(loaded) = self.loader.as_ref().and_then(|l| l.results.recv().ok()) else {
+                self.pending.clear();
+                break;
+            };
+            let wanted = loaded.0 == number;
+            if let Some(e) = self.receive(loaded)
+                && wanted
+            {
+                return Err(e);
+            }
+        }
+
+        let frame = match self.cache.entry(number) {
+            Entry::Occupied(entry) => entry.into_mut(),
+            Entry::Vacant(entry) => entry.insert(Frame::open(pattern.path(number))?),
+        };
+        Ok(&frame.image)
+    }
+
+    /// Frame numbers following `frame` on the timeline that are decoded ahead.
+    fn ahead(&self, frame: i64) -> Vec<u64> {
+        (1..=self.prefetch as i64)
+            .map(|ahead| self.number(frame + ahead))
+            .collect()
+    }
+
+    /// Request the frames following `frame` on the timeline from the loader.
+    fn prefetch(&mut self, pattern: &Pattern, frame: i64) {
+        let numbers = self.ahead(frame);
+        let loader = self.loader.get_or_insert_with(Loader::spawn);
+        for number in numbers {
+            if self.cache.contains_key(&number) || !self.pending.insert(number) {
+                continue;
+            }
+            loader.request(number, pattern.path(number));
+        }
+    }
+
+    /// Drop the frames furthest from timeline frame `frame` until the cache
+    /// fits. The current and prefetched frames go last, the prefetch is
+    /// clamped so they always fit.
+    fn evict(&mut self, frame: i64) {
+        let current = self.number(frame);
+        let ahead = self.ahead(frame);
+        while self.cache.len() > self.cache_size {
+            let furthest = self
+                .cache
+                .keys()
+                .copied()
+                .max_by_key(|n| {
+                    let kept = *n == current || ahead.contains(n);
+                    (!kept, n.abs_diff(current))
+                })
+                .expect("cache is not empty");
+            self.cache.remove(&furthest);
+        }
+    }
+}
+
+impl Operation for ImageSequence {
+    fn is_stateful(&self) -> bool {
+        false
+    }
+
+    fn op_path(&self) -> OpPath {
+        <Self as OperationFactory>::op_path()
+    }
+
+    fn needs_reconfigure(&mut self) -> bool {
+        let dir = self.watch.as_mut().is_some_and(FileWatch::changed);
+
+        // Only the frames re-rendered in place are decoded again
+        let stale: Vec<u64> = self
+            .cache
+            .iter_mut()
+            .filter_map(|(number, frame)| frame.watch.changed().then_some(*number))
+            .collect();
+        for number in &stale {
+            self.cache.remove(number);
+        }
+        if self
+            .uploaded
+            .is_some_and(|(number, _)| stale.contains(&number))
+        {
+            self.uploaded = None;
+        }
+
+        dir || !stale.is_empty()
+    }
+
+    fn setup(&mut self, _ctx: &mut ExecutionContext, registry: &mut SignatureRegistery) {
+        registry
+            .add_output::<TextureHandle>("image")
+            .default(SPECK)
+            .meta(TextureMeta {
+                preview: true,
+                allow_file: false,
+                ..Default::default()
+            })
+            .build();
+        registry.add_output::<i32>("frame").build();
+        registry.register_config::<SequenceConfig>();
+    }
+
+    fn configure(
+        &mut self,
+        _ctx: &ExecutionContext,
+        config: Config,
+        _registry: &mut SignatureRegistery,
+    ) -> Result<()> {
+        let cfg = SequenceConfig::try_extract(config)?;
+        self.mode = cfg.mode;
+        self.offset = cfg.offset as i64;
+        self.cache_size = cfg.cache_size.max(1) as usize;
+        // Leave room for the current frame, so prefetching never evicts it
+        self.prefetch = (cfg.prefetch.max(0) as usize).min(self.cache_size - 1);
+
+        let pattern = Pattern::parse(&cfg.pattern);
+        if pattern != self.pattern {
+            // Frames still decoding for the old pattern are dropped with the loader
+            self.cache.clear();
+            self.pending.clear();
+            self.loader = None;
+            self.uploaded = None;
+            self.pattern = pattern;
+        }
+
+        let Some(pattern) = &self.pattern else {
+            self.watch = None;
+            return match cfg.pattern.is_empty() {
+                true => Ok(()),
+                false => Err(Error::InvalidFramePattern(cfg.pattern)),
+            };
+        };
+
+        if self
+            .watch
+            .as_ref()
+            .is_none_or(|w| w.path() != pattern.dir())
+        {
+            self.watch = Some(FileWatch::new(pattern.dir()));
+        }
+
+        // Frames may have been added or removed, cached frames that are still
+        // on disk stay valid, their own watches catch re-renders
+        self.range = None;
+        self.range = Some(
+            pattern
+                .scan()?
+                .ok_or_else(|| Error::NoMatchingFrames(cfg.pattern.clone()))?,
+        );
+        Ok(())
+    }
+
+    fn execute(
+        &mut self,
+        ctx: &mut ExecutionContext,
+        _inputs: Inputs,
+        mut outputs: Outputs,
+    ) -> Result<()> {
+        let (Some(pattern), Some(_)) = (self.pattern.clone(), self.range) else {
+            return Ok(());
+        };
+
+        // Failed prefetches are retried when the frame comes up
+        while let Some(loaded) = self.loader.as_ref().and_then(|l| l.results.try_recv().ok()) {
+            self.receive(loaded);
+        }
+
+        let frame = ctx.timing().frame as i64;
+        let number = self.number(frame);
+        self.prefetch(&pattern, frame);
+
+        let output: &mut TextureHandle = outputs.extract(0)?;
+        let up_to_date = output
+            .id
+            .is_some_and(|id| self.uploaded == Some((number, id)));
+        if !up_to_date {
+            let image = self.load(&pattern, number)?;
+            ctx.upload_image(output, image)?;
+            self.uploaded = output.id.map(|id| (number, id));
+        }
+
+        *outputs.extract::<i32>(1)? = number as i32;
+        self.evict(frame);
+        Ok(())
+    }
+}
+
+impl OperationFactory for ImageSequence {
+    const LIBRARY: &'static str = "core";
+    const OPERATOR: &'static str = "image_sequence";
+    const LABEL: &'static str = "Image Sequence";
+
+    fn build() -> Result<Box<dyn Operation>> {
+        Ok(Box::new(ImageSequence::default()))
+    }
+}
@@ -61,6 +61,7 @@ impl Operation for Input {
                     .meta(TextureMeta {
                         preview: true,
                         allow_file: true,
+                        ..Default::default()
                     })
                     .build();
             }
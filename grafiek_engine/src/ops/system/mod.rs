@@ -1,2 +1,5 @@
+pub mod file_output;
+pub mod image_file;
+pub mod image_sequence;
 pub mod input;
 pub mod output;
@@ -0,0 +1,86 @@
+use crate::ConfigSchema;
+use crate::ExecutionContext;
+use crate::error::Result;
+use crate::registry::{FloatRange, SignatureRegistery};
+use crate::traits::{OpPath, Operation, OperationFactory};
+use crate::value::{Config, Inputs, Outputs, OutputsExt};
+
+/// Outputs the host's [crate::TimeInfo], scaled and shifted.
+pub struct Clock {
+    pub speed: f32,
+    pub offset: f32,
+}
+
+impl Default for Clock {
+    fn default() -> Self {
+        Self {
+            speed: 1.0,
+            offset: 0.0,
+        }
+    }
+}
+
+#[derive(ConfigSchema)]
+struct ClockConfig {
+    /// Multiplies time and delta, negative runs backwards
+    #[meta(FloatRange { min: -10.0, max: 10.0, step: 0.01 })]
+    #[default(1.0)]
+    speed: f32,
+
+    /// Seconds added to the scaled time
+    #[meta(FloatRange { min: f32::MIN, max: f32::MAX, step: 0.1 })]
+    offset: f32,
+}
+
+impl Operation for Clock {
+    // Computed from the host's timing every frame, nothing is kept between frames
+    fn is_stateful(&self) -> bool {
+        false
+    }
+
+    fn op_path(&self) -> OpPath {
+        <Self as OperationFactory>::op_path()
+    }
+
+    fn setup(&mut self, _ctx: &mut ExecutionContext, registry: &mut SignatureRegistery) {
+        registry.add_output::<f32>("time").build();
+        registry.add_output::<f32>("delta").build();
+        registry.add_output::<i32>("frame").build();
+        registry.register_config::<ClockConfig>();
+    }
+
+    fn configure(
+        &mut self,
+        _ctx: &ExecutionContext,
+        config: Config,
+        _registry: &mut SignatureRegistery,
+    ) -> Result<()> {
+        let cfg = ClockConfig::try_extract(config)?;
+        self.speed = cfg.speed;
+        self.offset = cfg.offset;
+        Ok(())
+    }
+
+    fn execute(
+        &mut self,
+        ctx: &mut ExecutionContext,
+        _inputs: Inputs,
+        mut outputs: Outputs,
+    ) -> Result<()> {
+        let timing = *ctx.timing();
+        *outputs.extract::<f32>(0)? = timing.time * self.speed + self.offset;
+        *outputs.extract::<f32>(1)? = timing.delta * self.speed;
+        *outputs.extract::<i32>(2)? = timing.frame.min(i32::MAX as u64) as i32;
+        Ok(())
+    }
+}
+
+impl OperationFactory for Clock {
+    const LIBRARY: &'static str = "time";
+    const OPERATOR: &'static str = "clock";
+    const LABEL: &'static str = "Clock";
+
+    fn build() -> Result<Box<dyn Operation>> {
+        Ok(Box::new(Clock::default()))
+    }
+}
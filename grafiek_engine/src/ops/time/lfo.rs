@@ -0,0 +1,145 @@
+use std::f32::consts::TAU;
+
+use crate::ConfigSchema;
+use crate::EnumSchema;
+use crate::ExecutionContext;
+use crate::Rng;
+use crate::error::Result;
+use crate::registry::{FloatRange, IntRange, SignatureRegistery};
+use crate::traits::{OpPath, Operation, OperationFactory};
+use crate::value::{Config, Inputs, InputsExt, Outputs, OutputsExt};
+
+/// A low frequency oscillator driven by the host's time, swinging between
+/// `-amplitude` and `amplitude`. Frequency is in cycles per second and phase in cycles.
+#[derive(Default)]
+pub struct Lfo {
+    pub shape: LfoShape,
+}
+
+#[derive(EnumSchema, Default, Copy, Clone)]
+pub enum LfoShape {
+    #[default]
+    Sine = 0,
+    Triangle,
+    /// Rises through each cycle and drops back
+    Saw,
+    Square,
+    /// A new random value each cycle, drawn from the engine's seed and the `seed` input
+    RandomHold,
+}
+
+#[derive(ConfigSchema)]
+struct LfoConfig {
+    #[label("")]
+    #[on_node_body]
+    shape: LfoShape,
+}
+
+impl LfoShape {
+    /// The wave at `cycles` since time zero, in `-1..=1`. Every shape starts a
+    /// cycle where the sine does, rising through zero. Random hold draws each
+    /// cycle's value from `rng`.
+    pub fn sample(self, cycles: f32, rng: &Rng) -> f32 {
+        let phase = cycles.rem_euclid(1.0);
+        match self {
+            LfoShape::Sine => (phase * TAU).sin(),
+            LfoShape::Triangle => 1.0 - 4.0 * ((phase + 0.25).rem_euclid(1.0) - 0.5).abs(),
+            LfoShape::Saw => 2.0 * phase - 1.0,
+            LfoShape::Square if phase < 0.5 => 1.0,
+            LfoShape::Square => -1.0,
+            LfoShape::RandomHold => {
+                let cycle = cycles.floor() as i64 as u64;
+                rng.derive(cycle).next_f32() * 2.0 - 1.0
+            }
+        }
+    }
+}
+
+impl Operation for Lfo {
+    // A function of the host's time, random hold included, so the same frame
+    // always gives the same value
+    fn is_stateful(&self) -> bool {
+        false
+    }
+
+    fn op_path(&self) -> OpPath {
+        <Self as OperationFactory>::op_path()
+    }
+
+    fn setup(&mut self, _ctx: &mut ExecutionContext, registry: &mut SignatureRegistery) {
+        registry
+            .add_input::<f32>("frequency")
+            .meta(FloatRange {
+                min: 0.0,
+                max: 100.0,
+                step: 0.01,
+            })
+            .default(1.0)
+            .build();
+        registry
+            .add_input::<f32>("phase")
+            .meta(FloatRange {
+                min: 0.0,
+                max: 1.0,
+                step: 0.01,
+            })
+            .build();
+        registry
+            .add_input::<f32>("amplitude")
+            .meta(FloatRange {
+                min: 0.0,
+                max: f32::MAX,
+                step: 0.1,
+            })
+            .default(1.0)
+            .build();
+        registry
+            .add_input::<i32>("seed")
+            .meta(IntRange {
+                min: i32::MIN,
+                max: i32::MAX,
+                step: 1,
+            })
+            .build();
+        registry.add_output::<f32>("value").build();
+        registry.register_config::<LfoConfig>();
+    }
+
+    fn configure(
+        &mut self,
+        _ctx: &ExecutionContext,
+        config: Config,
+        _registry: &mut SignatureRegistery,
+    ) -> Result<()> {
+        let cfg = LfoConfig::try_extract(config)?;
+        self.shape = cfg.shape;
+        Ok(())
+    }
+
+    fn execute(
+        &mut self,
+        ctx: &mut ExecutionContext,
+        inputs: Inputs,
+        mut outputs: Outputs,
+    ) -> Result<()> {
+        let frequency: f32 = inputs.extract(0)?;
+        let phase: f32 = inputs.extract(1)?;
+        let amplitude: f32 = inputs.extract(2)?;
+        let seed: i32 = inputs.extract(3)?;
+
+        let cycles = ctx.time() * frequency + phase;
+        let rng = ctx.rng(seed as u32 as u64);
+        *outputs.extract::<f32>(0)? = self.shape.sample(cycles, &rng) * amplitude;
+        Ok(())
+    }
+}
+
+impl OperationFactory for Lfo {
+    const LIBRARY: &'static str = "time";
+    const OPERATOR: &'static str = "lfo";
+    const LABEL: &'static str = "LFO";
+
+    fn build() -> Result<Box<dyn Operation>> {
+        Ok(Box::new(Lfo::default()))
+    }
+}
@@ -0,0 +1,5 @@
+pub mod clock;
+pub mod lfo;
+
+pub use clock::Clock;
+pub use lfo::{Lfo, LfoShape};
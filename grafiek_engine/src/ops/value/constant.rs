@@ -0,0 +1,144 @@
+use crate::error::Result;
+use crate::registry::{FloatRange, IntRange, SignatureRegistery};
+use crate::traits::{OpPath, Operation, OperationFactory};
+use crate::value::{Config, Inputs, InputsExt, Outputs, OutputsExt};
+use crate::{ConfigSchema, EnumSchema, ExecutionContext, Value};
+
+const F32_META: FloatRange = FloatRange {
+    min: f32::MIN,
+    max: f32::MAX,
+    step: 0.1,
+};
+
+/// A fixed value set on the node itself. Unlike [crate::ops::Input] it is
+/// not published as one of the graph's inputs.
+#[derive(Clone, Default)]
+pub struct Constant {
+    value: Option<Value>,
+}
+
+#[derive(EnumSchema, Default, Copy, Clone, PartialEq)]
+pub enum ConstantType {
+    #[default]
+    Float = 0,
+    Int,
+    Bool,
+    String,
+}
+
+#[derive(ConfigSchema)]
+struct ConstantConfig {
+    #[on_node_body]
+    #[label("type")]
+    value_type: ConstantType,
+}
+
+/// Index of the `value` config slot, registered after [ConstantConfig].
+const VALUE_SLOT: usize = 1;
+
+impl Operation for Constant {
+    fn is_stateful(&self) -> bool {
+        false
+    }
+
+    fn op_path(&self) -> OpPath {
+        <Self as OperationFactory>::op_path()
+    }
+
+    fn setup(&mut self, _ctx: &mut ExecutionContext, registry: &mut SignatureRegistery) {
+        registry.add_output::<f32>("value").build();
+        registry.register_config::<ConstantConfig>();
+        registry
+            .add_config::<f32>("value")
+            .meta(F32_META)
+            .show_on_node_body(true)
+            .build();
+    }
+
+    fn configure(
+        &mut self,
+        _ctx: &ExecutionContext,
+        config: Config,
+        registry: &mut SignatureRegistery,
+    ) -> Result<()> {
+        let cfg = ConstantConfig::try_extract(config.clone())?;
+
+        registry.clear_outputs();
+        registry.truncate_config(VALUE_SLOT);
+
+        // Switching type leaves the old value in place until the slots are synced,
+        // so anything that doesn't match falls back to the default
+        let value = match cfg.value_type {
+            ConstantType::Float => {
+                let v = config.extract::<f32>(VALUE_SLOT).unwrap_or_default();
+                registry
+                    .add_config::<f32>("value")
+                    .meta(F32_META)
+                    .show_on_node_body(true)
+                    .build();
+                registry.add_output::<f32>("value").default(v).build();
+                Value::F32(v)
+            }
+            ConstantType::Int => {
+                let v = config.extract::<i32>(VALUE_SLOT).unwrap_or_default();
+                registry
+                    .add_config::<i32>("value")
+                    .meta(IntRange::default())
+                    .show_on_node_body(true)
+                    .build();
+                registry.add_output::<i32>("value").default(v).build();
+                Value::I32(v)
+            }
+            ConstantType::Bool => {
+                let v = config.extract::<bool>(VALUE_SLOT).unwrap_or_default();
+                registry
+                    .add_config::<bool>("value")
+                    .show_on_node_body(true)
+                    .build();
+                registry.add_output::<bool>("value").default(v).build();
+                Value::Bool(v)
+            }
+            ConstantType::String => {
+                let v = config.extract::<String>(VALUE_SLOT).unwrap_or_default();
+                registry
+                    .add_config::<String>("value")
+                    .show_on_node_body(true)
+                    .build();
+                registry
+                    .add_output::<String>("value")
+                    .default(v.clone())
+                    .build();
+                Value::String(v)
+            }
+        };
+        self.value = Some(value);
+
+        Ok(())
+    }
+
+    fn execute(
+        &mut self,
+        _ctx: &mut ExecutionContext,
+        _inputs: Inputs,
+        mut outputs: Outputs,
+    ) -> Result<()> {
+        match &self.value {
+            Some(Value::F32(v)) => *outputs.extract::<f32>(0)? = *v,
+            Some(Value::I32(v)) => *outputs.extract::<i32>(0)? = *v,
+            Some(Value::Bool(v)) => *outputs.extract::<bool>(0)? = *v,
+            Some(Value::String(v)) => v.clone_into(outputs.extract::<String>(0)?),
+            _ => {}
+        }
+        Ok(())
+    }
+}
+
+impl OperationFactory for Constant {
+    const LIBRARY: &'static str = "value";
+    const OPERATOR: &'static str = "constant";
+    const LABEL: &'static str = "Constant";
+
+    fn build() -> Result<Box<dyn Operation>> {
+        Ok(Box::new(Constant::default()))
+    }
+}
@@ -0,0 +1,116 @@
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::hash::{DefaultHasher, Hash, Hasher};
+use std::rc::Rc;
+
+use tweak_shader::RenderContext;
+
+/// A compiled shader shared between every node running the same source.
+/// Nodes set every uniform and texture before each render, binding the speck
+/// for textures they can't resolve, so sharing is safe. Stateful shaders are
+/// never shared.
+///
+/// Being `Rc<RefCell<_>>` this makes [crate::Engine] `!Send`, the engine has
+/// to stay on the thread that created it.
+pub type SharedPipeline = Rc<RefCell<RenderContext>>;
+
+/// Identifies a compiled pipeline by its fully expanded source and target format.
+/// Only the source's hash is kept, so the cache checks the full source on a hit.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct PipelineKey {
+    pub source_hash: u64,
+    pub format: wgpu::TextureFormat,
+}
+
+impl PipelineKey {
+    pub fn new(source: &str, format: wgpu::TextureFormat) -> Self {
+        let mut hasher = DefaultHasher::new();
+        source.hash(&mut hasher);
+        Self {
+            source_hash: hasher.finish(),
+            format,
+        }
+    }
+}
+
+/// Counters for profiling how often compiles are avoided.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct PipelineCacheStats {
+    /// Lookups answered without compiling
+    pub hits: u64,
+    /// Lookups that compiled a new pipeline
+    pub misses: u64,
+    /// Pipelines currently held by the cache
+    pub entries: usize,
+}
+
+/// A cached pipeline and the source it was compiled from.
+struct Entry {
+    source: String,
+    pipeline: SharedPipeline,
+}
+
+/// Compiled shader pipelines keyed by source and format.
+#[derive(Default)]
+pub struct PipelineCache {
+    pipelines: HashMap<PipelineKey, Entry>,
+    hits: u64,
+    misses: u64,
+}
+
+impl std::fmt::Debug for PipelineCache {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("PipelineCache")
+            .field("stats", &self.stats())
+            .finish()
+    }
+}
+
+impl PipelineCache {
+    /// Look up the pipeline compiled from `source`, counting a hit if found.
+    /// A different source whose hash collides with it is not a hit.
+    pub fn get(&mut self, key: PipelineKey, source: &str) -> Option<SharedPipeline> {
+        let entry = self.pipelines.get(&key).filter(|e| e.source == source)?;
+        self.hits += 1;
+        Some(entry.pipeline.clone())
+    }
+
+    /// Store a pipeline freshly compiled from `source`, counting a miss.
+    pub fn insert(
+        &mut self,
+        key: PipelineKey,
+        source: String,
+        ctx: RenderContext,
+    ) -> SharedPipeline {
+        self.misses += 1;
+        let stateful = ctx.is_stateful();
+        let pipeline = Rc::new(RefCell::new(ctx));
+        if !stateful {
+            let entry = Entry {
+                source,
+                pipeline: pipeline.clone(),
+            };
+            self.pipelines.insert(key, entry);
+        }
+        pipeline
+    }
+
+    /// Record a lookup a node answered itself by keeping its current pipeline.
+    pub(crate) fn record_hit(&mut self) {
+        self.hits += 1;
+    }
+
+    /// Drop every pipeline no node is using anymore.
+    pub fn trim(&mut self) {
+        self.pipelines
+            .retain(|_, e| Rc::strong_count(&e.pipeline) > 1);
+    }
+
+    pub fn stats(&self) -> PipelineCacheStats {
+        PipelineCacheStats {
+            hits: self.hits,
+            misses: self.misses,
+            entries: self.pipelines.len(),
+        }
+    }
+}
@@ -0,0 +1,48 @@
+/// A small, fast generator (splitmix64) giving the same sequence for the same
+/// seed on every platform. Not suitable for anything security related.
+#[derive(Debug, Clone)]
+pub struct Rng {
+    state: u64,
+}
+
+impl Rng {
+    pub fn new(seed: u64) -> Self {
+        Self { state: seed }
+    }
+
+    /// A generator for each of several keys under one seed, such as a frame
+    /// number. Nearby keys give unrelated sequences.
+    pub fn derive(&self, key: u64) -> Self {
+        let mut rng = Self::new(self.state ^ key.wrapping_mul(0xd6e8_feb8_6659_fd93));
+        rng.next_u64();
+        rng
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^ (z >> 31)
+    }
+
+    /// Uniform in `0..1`.
+    pub fn next_f32(&mut self) -> f32 {
+        (self.next_u64() >> 40) as f32 / (1u64 << 24) as f32
+    }
+
+    /// Uniform in `min..=max`, either order.
+    pub fn range_i32(&mut self, min: i32, max: i32) -> i32 {
+        let (lo, hi) = (min.min(max) as i64, min.max(max) as i64);
+        let span = (hi - lo + 1) as u64;
+        (lo + (self.next_u64() % span) as i64) as i32
+    }
+
+    /// Normally distributed with mean 0 and standard deviation 1.
+    pub fn normal(&mut self) -> f32 {
+        // Box-Muller, keeping u1 away from zero for the log
+        let u1 = 1.0 - self.next_f32();
+        let u2 = self.next_f32();
+        (-2.0 * u1.ln()).sqrt() * (std::f32::consts::TAU * u2).cos()
+    }
+}
@@ -1,5 +1,5 @@
 use crate::gpu_pool::TextureId;
-use crate::value::{TextureFormat, TextureHandle};
+use crate::value::{ColorSpace, TextureFormat, TextureHandle};
 
 /// 1x1 black texture.
 pub const SPECK: TextureHandle = TextureHandle {
@@ -7,6 +7,8 @@ pub const SPECK: TextureHandle = TextureHandle {
     width: 1,
     height: 1,
     fmt: TextureFormat::RGBAu8,
+    color_space: ColorSpace::Linear,
+    mipmaps: false,
 };
 
 /// 1x1 white texture.
@@ -15,6 +17,8 @@ pub const FLECK: TextureHandle = TextureHandle {
     width: 1,
     height: 1,
     fmt: TextureFormat::RGBAu8,
+    color_space: ColorSpace::Linear,
+    mipmaps: false,
 };
 
 /// 1x1 transparent texture.
@@ -23,6 +27,8 @@ pub const TRANSPARENT_SPECK: TextureHandle = TextureHandle {
     width: 1,
     height: 1,
     fmt: TextureFormat::RGBAu8,
+    color_space: ColorSpace::Linear,
+    mipmaps: false,
 };
 
 /// 2x2 black/magenta check pattern.
@@ -31,6 +37,8 @@ pub const CHECK: TextureHandle = TextureHandle {
     width: 2,
     height: 2,
     fmt: TextureFormat::RGBAu8,
+    color_space: ColorSpace::Linear,
+    mipmaps: false,
 };
 
 pub(crate) const CHECK_DATA: [u8; 16] = [
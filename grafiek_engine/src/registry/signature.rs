@@ -99,6 +99,16 @@ impl SignatureRegistery {
         self.config.clear();
     }
 
+    /// Drop input slots past `len`, for operations with inputs that depend on their config.
+    pub fn truncate_inputs(&mut self, len: usize) {
+        self.inputs.truncate(len);
+    }
+
+    /// Drop config slots past `len`, for operations that append their own after a schema.
+    pub fn truncate_config(&mut self, len: usize) {
+        self.config.truncate(len);
+    }
+
     pub fn clear(&mut self) {
         self.inputs.clear();
         self.outputs.clear();
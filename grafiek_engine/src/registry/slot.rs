@@ -4,7 +4,7 @@ use std::marker::PhantomData;
 use derive_more::From;
 use serde::{Deserialize, Serialize};
 
-use crate::{AsValueType, TextureHandle, ValueType};
+use crate::{AsValueType, EnumSchema, TextureHandle, ValueType};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct CommonMetadata {
@@ -67,7 +67,7 @@ pub struct Angle {
     pub unit: AngleUnit,
 }
 
-#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Default, Serialize, Deserialize, EnumSchema)]
 pub enum AngleUnit {
     #[default]
     Radians,
@@ -120,12 +120,26 @@ pub struct StringMeta {
 }
 impl MetadataFor<String> for StringMeta {}
 
+/// How a texture input wants to be sampled.
+///
+/// Filter mode, wrap mode and anisotropy aren't configurable: tweak_shader
+/// binds its own default sampler for every input and has no way to hand it
+/// one built from this metadata.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, Default)]
+pub struct SamplerMeta {
+    /// Give the texture a mip chain before it's sampled.
+    pub mipmaps: bool,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
 pub struct TextureMeta {
     /// Show this image on the node body, or somewhere else in the application.
     pub preview: bool,
     /// Allow a file picker to be used in assigning this data.
     pub allow_file: bool,
+    /// Only meaningful on inputs.
+    #[serde(default)]
+    pub sampler: SamplerMeta,
 }
 impl MetadataFor<TextureHandle> for TextureMeta {}
 
@@ -254,6 +268,14 @@ impl<'a> SlotBuilder<'a, TextureHandle> {
         self.default = Some(tex);
         self
     }
+
+    /// Set the format for the texture output.
+    pub fn format(mut self, fmt: crate::TextureFormat) -> Self {
+        let mut tex = self.default.take().unwrap_or_default();
+        tex.fmt = fmt;
+        self.default = Some(tex);
+        self
+    }
 }
 
 impl<'a, T: crate::AsValueType> SlotBuilder<'a, T> {
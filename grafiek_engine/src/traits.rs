@@ -1,6 +1,9 @@
 use std::any::Any;
+use std::ops::Range;
+use std::rc::Rc;
 
 use crate::error::Result;
+use crate::node::DirtyFlag;
 use crate::registry::SignatureRegistery;
 use crate::value::{Config, Inputs, Outputs};
 use crate::{AsValueType, ExecutionContext, ValueType};
@@ -56,10 +59,46 @@ pub trait Operation: Any {
     /// Get the type name for this operation (used for serialization)
     fn op_path(&self) -> OpPath;
 
+    /// Conditional operations, like a switch, return the inputs that decide
+    /// which of their other inputs get read. The engine evaluates these first
+    /// and then asks [Operation::selected_inputs] which others it needs.
+    fn condition_inputs(&self) -> Range<usize> {
+        0..0
+    }
+
+    /// Given the [Operation::condition_inputs] in `inputs`, the inputs read by
+    /// the next [Operation::execute], None for all of them. Nodes that only
+    /// feed the other inputs are not executed, unless they are stateful.
+    fn selected_inputs(&self, _inputs: Inputs) -> Option<Vec<usize>> {
+        None
+    }
+
+    /// Polled by the engine for changes made outside of the graph, such as
+    /// watched files on disk. Returning true reconfigures the node with its
+    /// current config.
+    fn needs_reconfigure(&mut self) -> bool {
+        false
+    }
+
+    /// Handed the node's reconfigure flag before [Operation::setup]. Background
+    /// work can set it from any thread to have the node reconfigured on the
+    /// next [crate::Engine::poll].
+    fn set_reconfigure_flag(&mut self, _flag: DirtyFlag) {}
+
+    /// True while background work started by [Operation::configure], such as
+    /// a shader compile, is still running.
+    fn is_compiling(&self) -> bool {
+        false
+    }
+
     /// Called when node is removed from graph - make sure to clean up
     /// any resources you left in the execution context
     fn teardown(&mut self, _ctx: &mut ExecutionContext) {}
 
+    /// Forget the state kept between executions, called on stateful operations
+    /// by [crate::Engine::reset].
+    fn reset(&mut self) {}
+
     /// Optional debug print for dumping internal state
     /// Default implementation does nothing
     fn debug_print(&self, _writer: &mut dyn std::io::Write) -> Result<()> {
@@ -160,15 +199,22 @@ pub trait OperationFactory: 'static {
 }
 
 /// Hand build vtable for constructing Operators
-#[derive(Debug, Clone)]
+#[derive(Clone)]
 pub(crate) struct OperationFactoryEntry {
-    pub build: fn() -> Result<Box<dyn Operation>>,
+    pub build: Rc<dyn Fn() -> Result<Box<dyn Operation>>>,
 }
 
 impl OperationFactoryEntry {
     pub fn new<T: OperationFactory>() -> Self {
         Self {
-            build: || T::build(),
+            build: Rc::new(|| T::build()),
+        }
+    }
+
+    /// Build entry for operators that only exist at runtime, such as user shaders.
+    pub fn from_fn(build: impl Fn() -> Result<Box<dyn Operation>> + 'static) -> Self {
+        Self {
+            build: Rc::new(build),
         }
     }
 }
@@ -3,6 +3,7 @@ use serde::{Deserialize, Serialize};
 use std::fmt;
 use thiserror::Error;
 
+use crate::EnumSchema;
 use crate::gpu_pool::TextureId;
 
 /// Maximum number of input/output slots per node
@@ -211,6 +212,61 @@ pub enum TextureFormat {
     BGRA8,
 }
 
+impl TextureFormat {
+    pub const ALL: [TextureFormat; 4] = [
+        TextureFormat::RGBAu8,
+        TextureFormat::RGBAu16,
+        TextureFormat::RGBAF32,
+        TextureFormat::BGRA8,
+    ];
+
+    pub fn to_wgpu(self) -> wgpu::TextureFormat {
+        match self {
+            TextureFormat::RGBAu8 => wgpu::TextureFormat::Rgba8Unorm,
+            TextureFormat::RGBAu16 => wgpu::TextureFormat::Rgba16Unorm,
+            TextureFormat::RGBAF32 => wgpu::TextureFormat::Rgba32Float,
+            TextureFormat::BGRA8 => wgpu::TextureFormat::Bgra8Unorm,
+        }
+    }
+
+    pub fn bytes_per_pixel(self) -> u32 {
+        match self {
+            TextureFormat::RGBAu8 | TextureFormat::BGRA8 => 4,
+            TextureFormat::RGBAu16 => 8,
+            TextureFormat::RGBAF32 => 16,
+        }
+    }
+}
+
+/// How the values stored in a texture map to light. Shader nodes convert their
+/// inputs to [ColorSpace::Linear] before blending unless told otherwise.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default, Serialize, Deserialize, EnumSchema)]
+pub enum ColorSpace {
+    /// sRGB transfer curve with Rec. 709 primaries, what most image files hold
+    Srgb,
+    /// Linear light with Rec. 709 primaries
+    #[default]
+    Linear,
+    /// sRGB transfer curve with Display P3 primaries
+    DisplayP3,
+    /// Linear light with Rec. 2020 primaries
+    LinearRec2020,
+}
+
+impl ColorSpace {
+    pub const ALL: [ColorSpace; 4] = [
+        ColorSpace::Srgb,
+        ColorSpace::Linear,
+        ColorSpace::DisplayP3,
+        ColorSpace::LinearRec2020,
+    ];
+
+    /// True if values are encoded with the sRGB transfer curve rather than stored as linear light.
+    pub fn is_srgb_encoded(self) -> bool {
+        matches!(self, ColorSpace::Srgb | ColorSpace::DisplayP3)
+    }
+}
+
 /// Handle to a texture stored in the engine's texture pool.
 /// The actual texture data is reference-counted by the engine.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
@@ -219,6 +275,11 @@ pub struct TextureHandle {
     pub(crate) width: u32,
     pub(crate) height: u32,
     pub(crate) fmt: TextureFormat,
+    #[serde(default)]
+    pub(crate) color_space: ColorSpace,
+    /// Allocate a full mip chain, see [crate::ExecutionContext::generate_mipmaps]
+    #[serde(default)]
+    pub(crate) mipmaps: bool,
 }
 
 const DEFAULT_DIM: u32 = 512;
@@ -229,6 +290,8 @@ impl Default for TextureHandle {
             width: DEFAULT_DIM,
             height: DEFAULT_DIM,
             fmt: TextureFormat::default(),
+            color_space: ColorSpace::default(),
+            mipmaps: false,
         }
     }
 }
@@ -251,6 +314,16 @@ impl TextureHandle {
             width,
             height,
             fmt,
+            color_space: ColorSpace::default(),
+            mipmaps: false,
+        }
+    }
+
+    /// The same texture request tagged with another colour space.
+    pub fn with_color_space(self, color_space: ColorSpace) -> Self {
+        Self {
+            color_space,
+            ..self
         }
     }
 
@@ -271,6 +344,22 @@ impl TextureHandle {
         self.fmt
     }
 
+    pub fn color_space(&self) -> ColorSpace {
+        self.color_space
+    }
+
+    pub fn mipmaps(&self) -> bool {
+        self.mipmaps
+    }
+
+    /// Levels in the texture's mip chain, down to 1x1 when it has mipmaps.
+    pub fn mip_level_count(&self) -> u32 {
+        match self.mipmaps {
+            true => u32::BITS - self.width.max(self.height).max(1).leading_zeros(),
+            false => 1,
+        }
+    }
+
     pub fn structurally_identical(&self, other: &Self) -> bool {
         self.width == other.width && self.height == other.height && self.fmt == other.fmt
     }
@@ -1,15 +1,22 @@
-use grafiek_engine::{Engine, EngineDescriptor};
+use grafiek_engine::{Engine, EngineDescriptor, TextureFormat};
 use wgpu::{self, ExperimentalFeatures};
 
 #[allow(dead_code)]
 pub fn engine() -> Engine {
+    engine_with_format(TextureFormat::RGBAu8).unwrap()
+}
+
+/// None if the test device can't use `default_format`.
+#[allow(dead_code)]
+pub fn engine_with_format(default_format: TextureFormat) -> Option<Engine> {
     let (device, queue) = setup_wgpu();
     Engine::init(EngineDescriptor {
         device,
         queue,
+        default_format: Some(default_format),
         on_message: None,
     })
-    .unwrap()
+    .ok()
 }
 
 pub fn setup_wgpu() -> (wgpu::Device, wgpu::Queue) {
@@ -42,7 +49,8 @@ pub fn setup_wgpu() -> (wgpu::Device, wgpu::Queue) {
                 label: None,
                 required_features: wgpu::Features::PUSH_CONSTANTS
                     | wgpu::Features::TEXTURE_ADAPTER_SPECIFIC_FORMAT_FEATURES
-                    | wgpu::Features::CLEAR_TEXTURE,
+                    | wgpu::Features::CLEAR_TEXTURE
+                    | (adapter.features() & wgpu::Features::TEXTURE_FORMAT_16BIT_NORM),
                 required_limits,
                 memory_hints: wgpu::MemoryHints::Performance,
                 experimental_features: ExperimentalFeatures::disabled(),
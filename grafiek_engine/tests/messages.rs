@@ -2,9 +2,10 @@ mod common;
 
 use std::sync::mpsc::{self, Receiver, Sender};
 
+use grafiek_engine::error::Error;
 use grafiek_engine::history::{Event, Message};
 use grafiek_engine::ops::Input;
-use grafiek_engine::{Engine, EngineDescriptor, ValueMut};
+use grafiek_engine::{Engine, EngineDescriptor, Value, ValueMut};
 
 struct TestMessages {
     rx: Receiver<Message>,
@@ -33,6 +34,7 @@ fn input_node_emits_dirty_on_edit() {
     let mut engine = Engine::init(EngineDescriptor {
         device,
         queue,
+        default_format: None,
         on_message: Some(Box::new(move |msg| {
             tx.send(msg).unwrap();
         })),
@@ -63,6 +65,7 @@ fn input_node_no_dirty_when_value_unchanged() {
     let mut engine = Engine::init(EngineDescriptor {
         device,
         queue,
+        default_format: None,
         on_message: Some(Box::new(move |msg| {
             tx.send(msg).unwrap();
         })),
@@ -94,6 +97,7 @@ fn connect_emits_dirty() {
     let mut engine = Engine::init(EngineDescriptor {
         device,
         queue,
+        default_format: None,
         on_message: Some(Box::new(move |msg| {
             tx.send(msg).unwrap();
         })),
@@ -121,3 +125,295 @@ fn connect_emits_dirty() {
         msgs
     );
 }
+
+#[test]
+fn async_compile_swaps_when_ready() {
+    let (device, queue) = common::setup_wgpu();
+    let (messages, tx) = TestMessages::new();
+
+    let mut engine = Engine::init(EngineDescriptor {
+        device,
+        queue,
+        default_format: None,
+        on_message: Some(Box::new(move |msg| {
+            tx.send(msg).unwrap();
+        })),
+    })
+    .unwrap();
+    engine.set_async_compile(true);
+
+    let custom = engine.instance_node("shader", "custom").unwrap();
+    let source_slot = engine
+        .get_node(custom)
+        .unwrap()
+        .configs()
+        .position(|(def, _)| def.name() == "source")
+        .unwrap();
+    let input_names = |engine: &Engine| -> Vec<String> {
+        engine
+            .get_node(custom)
+            .unwrap()
+            .inputs()
+            .map(|(def, _)| def.name().to_string())
+            .collect()
+    };
+    let before = input_names(&engine);
+    messages.clear();
+
+    let source = r#"#version 450
+#pragma input(float, name="brightness", default=1.0, min=0.0, max=1.0)
+layout(set = 0, binding = 0) uniform Inputs {
+    float brightness;
+};
+
+layout(location = 0) out vec4 out_color;
+
+void main() {
+    out_color = vec4(vec3(brightness), 1.0);
+}
+"#;
+    engine
+        .edit_node_config(custom, source_slot, |_, value| {
+            if let ValueMut::String(s) = value {
+                *s = source.to_string();
+            }
+        })
+        .unwrap();
+
+    // The previous pipeline stays in place until the engine is polled after the compile
+    assert!(engine.get_node(custom).unwrap().is_compiling());
+    assert_eq!(input_names(&engine), before);
+    assert!(
+        messages.drain().iter().any(
+            |m| matches!(m, Message::Event(Event::CompileStarted { node }) if *node == custom)
+        )
+    );
+
+    let deadline = std::time::Instant::now() + std::time::Duration::from_secs(30);
+    while engine.get_node(custom).unwrap().is_compiling() {
+        assert!(
+            std::time::Instant::now() < deadline,
+            "compile never finished"
+        );
+        std::thread::sleep(std::time::Duration::from_millis(5));
+        engine.poll();
+    }
+
+    assert_eq!(input_names(&engine), ["brightness"]);
+    assert!(messages.drain().iter().any(|m| matches!(
+        m,
+        Message::Event(Event::CompileFinished { node, success: true }) if *node == custom
+    )));
+}
+
+#[test]
+fn resized_textures_invalidate_old_handles() {
+    let (device, queue) = common::setup_wgpu();
+    let (messages, tx) = TestMessages::new();
+
+    let mut engine = Engine::init(EngineDescriptor {
+        device,
+        queue,
+        default_format: None,
+        on_message: Some(Box::new(move |msg| {
+            tx.send(msg).unwrap();
+        })),
+    })
+    .unwrap();
+    engine.set_texture_debug(true);
+
+    let first = engine.instance_node("shader", "grayscale").unwrap();
+    let second = engine.instance_node("shader", "grayscale").unwrap();
+    engine.connect(first, second, 0, 0).unwrap();
+    engine.execute();
+
+    let output = |engine: &Engine| match engine.get_node(first).unwrap().output(0).unwrap().1 {
+        grafiek_engine::Value::Texture(handle) => *handle,
+        other => panic!("expected texture, got {other:?}"),
+    };
+    let old = output(&engine);
+
+    // The output stops matching its 1x1 input and is reallocated at 512x512
+    let match_dims = engine
+        .get_node(first)
+        .unwrap()
+        .configs()
+        .position(|(def, _)| def.name() == "match input dimensions")
+        .unwrap();
+    engine
+        .edit_node_config(first, match_dims, |_, value| {
+            if let ValueMut::Bool(v) = value {
+                *v = false;
+            }
+        })
+        .unwrap();
+
+    let new = output(&engine);
+    assert_eq!(new.id().unwrap().stable_id, old.id().unwrap().stable_id);
+    assert_eq!(
+        new.id().unwrap().generation,
+        old.id().unwrap().generation + 1
+    );
+    assert!(engine.get_texture(&old).is_none());
+    assert!(engine.get_texture(&new).is_some());
+
+    // Upstream nodes execute first, so the second node never sees the stale handle
+    messages.clear();
+    engine.execute();
+    assert!(!engine.node_has_errors(second));
+    assert!(
+        !messages
+            .drain()
+            .iter()
+            .any(|m| matches!(m, Message::Event(Event::StaleTexture { .. })))
+    );
+}
+
+#[test]
+fn stale_handles_are_reported() {
+    let (device, queue) = common::setup_wgpu();
+    let (messages, tx) = TestMessages::new();
+
+    let mut engine = Engine::init(EngineDescriptor {
+        device,
+        queue,
+        default_format: None,
+        on_message: Some(Box::new(move |msg| {
+            tx.send(msg).unwrap();
+        })),
+    })
+    .unwrap();
+    engine.set_texture_debug(true);
+
+    let first = engine.instance_node("shader", "grayscale").unwrap();
+    let holder = engine.instance_node("shader", "grayscale").unwrap();
+    engine.execute();
+
+    let old = match engine.get_node(first).unwrap().output(0).unwrap().1 {
+        grafiek_engine::Value::Texture(handle) => *handle,
+        other => panic!("expected texture, got {other:?}"),
+    };
+
+    // The output stops matching its 1x1 input and is reallocated at 512x512
+    let match_dims = engine
+        .get_node(first)
+        .unwrap()
+        .configs()
+        .position(|(def, _)| def.name() == "match input dimensions")
+        .unwrap();
+    engine
+        .edit_node_config(first, match_dims, |_, value| {
+            if let ValueMut::Bool(v) = value {
+                *v = false;
+            }
+        })
+        .unwrap();
+    assert!(matches!(
+        engine.try_get_texture(&old),
+        Err(Error::StaleTexture { .. })
+    ));
+
+    // A node still holding the old handle is reported before it executes
+    engine
+        .edit_node_input(holder, 0, |_, value| {
+            if let ValueMut::Texture(handle) = value {
+                *handle = old;
+            }
+        })
+        .unwrap();
+    messages.clear();
+    engine.execute();
+    assert!(messages.drain().iter().any(|m| matches!(
+        m,
+        Message::Event(Event::StaleTexture { node, slot: 0, .. }) if *node == holder
+    )));
+    assert!(engine.node_has_errors(holder));
+}
+
+#[test]
+fn switch_skips_unselected_branch() {
+    let (device, queue) = common::setup_wgpu();
+    let (messages, tx) = TestMessages::new();
+
+    let mut engine = Engine::init(EngineDescriptor {
+        device,
+        queue,
+        default_format: None,
+        on_message: Some(Box::new(move |msg| {
+            tx.send(msg).unwrap();
+        })),
+    })
+    .unwrap();
+
+    let first = engine.instance_node("shader", "grayscale").unwrap();
+    let second = engine.instance_node("shader", "grayscale").unwrap();
+    let switch = engine.instance_node("logic", "switch").unwrap();
+    engine.connect(first, switch, 0, 1).unwrap();
+    engine.connect(second, switch, 0, 2).unwrap();
+
+    let executed = |messages: &TestMessages| -> Vec<_> {
+        messages
+            .drain()
+            .into_iter()
+            .filter_map(|m| match m {
+                Message::Event(Event::NodeExecuted { node }) => Some(node),
+                _ => None,
+            })
+            .collect()
+    };
+
+    messages.clear();
+    engine.execute();
+    let nodes = executed(&messages);
+    assert!(nodes.contains(&first) && nodes.contains(&switch));
+    assert!(!nodes.contains(&second));
+
+    // The newly selected branch runs in the same frame as the change
+    engine
+        .edit_node_input(switch, 0, |_, value| {
+            if let ValueMut::I32(v) = value {
+                *v = 1;
+            }
+        })
+        .unwrap();
+    messages.clear();
+    engine.execute();
+    let nodes = executed(&messages);
+    assert!(nodes.contains(&second) && nodes.contains(&switch));
+    assert!(!nodes.contains(&first));
+    assert!(!engine.node_has_errors(switch));
+}
+
+#[test]
+fn stateful_nodes_run_behind_unselected_branch() {
+    let (device, queue) = common::setup_wgpu();
+    let (messages, tx) = TestMessages::new();
+
+    let mut engine = Engine::init(EngineDescriptor {
+        device,
+        queue,
+        default_format: None,
+        on_message: Some(Box::new(move |msg| {
+            tx.send(msg).unwrap();
+        })),
+    })
+    .unwrap();
+
+    let arithmetic = engine.instance_node("math", "arithmetic").unwrap();
+    let accumulator = engine.instance_node("signal", "accumulator").unwrap();
+    let switch = engine.instance_node("logic", "switch").unwrap();
+    engine.connect(arithmetic, switch, 0, 1).unwrap();
+    engine.connect(accumulator, switch, 0, 2).unwrap();
+
+    // The accumulator keeps counting while the switch reads the other branch
+    for _ in 0..3 {
+        messages.clear();
+        engine.execute();
+        assert!(messages.drain().iter().any(|m| matches!(
+            m,
+            Message::Event(Event::NodeExecuted { node }) if *node == accumulator
+        )));
+    }
+    let total = engine.get_node(accumulator).unwrap().output(0).unwrap().1;
+    assert!(matches!(total, Value::F32(t) if *t == 3.0));
+}
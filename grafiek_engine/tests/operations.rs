@@ -1,7 +1,20 @@
 mod common;
 
-use grafiek_engine::ops::{ArithOp, Arithmetic, Input, Output};
-use grafiek_engine::{Value, ValueMut};
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use grafiek_engine::error::Error;
+use grafiek_engine::ops::{
+    ArithOp, Arithmetic, BitDepth, CompareOp, ConstantType, Distribution, EaseCurve, EaseDirection,
+    FileFormat, GateOp, Input, IntOp, LfoShape, Output, SequenceMode, SwitchMode, TrigOp,
+    WriteMode,
+};
+use grafiek_engine::traits::{OpPath, Operation};
+use grafiek_engine::{
+    Angle, AngleUnit, ColorSpace, Config, ExecutionContext, ExtendedMetadata, ImageData, Inputs,
+    InputsExt, Outputs, SPECK, SignatureRegistery, TextureFormat, TextureHandle, TextureOwner,
+    TimeInfo, Value, ValueMut, ValueType,
+};
 
 #[test]
 fn init() {
@@ -105,6 +118,614 @@ fn add_with_node_inputs() {
     }
 }
 
+#[test]
+fn integer_ops_stay_exact() {
+    let mut engine = common::engine();
+
+    let int = engine.instance_node("math", "integer").unwrap();
+    let set_op = |engine: &mut grafiek_engine::Engine, op: IntOp| {
+        let slot = config_index(engine, int, "operation");
+        engine
+            .edit_node_config(int, slot, |_, value| {
+                if let ValueMut::I32(v) = value {
+                    *v = op as i32;
+                }
+            })
+            .unwrap();
+    };
+    let set_input = |engine: &mut grafiek_engine::Engine, slot: usize, to: i32| {
+        engine
+            .edit_node_input(int, slot, |_, value| {
+                if let ValueMut::I32(v) = value {
+                    *v = to;
+                }
+            })
+            .unwrap();
+    };
+    let result = |engine: &grafiek_engine::Engine| match engine.get_node(int).unwrap().output(0) {
+        Some((_, Value::I32(v))) => *v,
+        _ => panic!("expected I32"),
+    };
+
+    // Not representable as an f32
+    set_input(&mut engine, 0, 16_777_217);
+    set_input(&mut engine, 1, 2);
+    engine.execute();
+    assert_eq!(result(&engine), 16_777_219);
+
+    // Euclidean, the quotient leaves a remainder that's never negative
+    set_op(&mut engine, IntOp::Divide);
+    for (dividend, divisor, quotient) in [(7, 2, 3), (7, -2, -3), (-7, 2, -4), (-7, -2, 4)] {
+        set_input(&mut engine, 0, dividend);
+        set_input(&mut engine, 1, divisor);
+        engine.execute();
+        assert_eq!(result(&engine), quotient);
+    }
+
+    set_op(&mut engine, IntOp::Modulo);
+    assert_eq!(input_names(&engine, int), ["dividend", "divisor"]);
+    set_input(&mut engine, 0, -1);
+    set_input(&mut engine, 1, 4);
+    engine.execute();
+    assert_eq!(result(&engine), 3);
+
+    set_input(&mut engine, 1, 0);
+    engine.execute();
+    assert!(engine.node_has_errors(int));
+
+    set_op(&mut engine, IntOp::Wrap);
+    assert_eq!(input_names(&engine, int), ["value", "min", "max"]);
+    for (value, wrapped) in [(12, 6), (-1, 5), (5, 5)] {
+        set_input(&mut engine, 0, value);
+        set_input(&mut engine, 1, 2);
+        set_input(&mut engine, 2, 8);
+        engine.execute();
+        assert_eq!(result(&engine), wrapped);
+    }
+
+    set_op(&mut engine, IntOp::ShiftLeft);
+    set_input(&mut engine, 0, 3);
+    set_input(&mut engine, 1, 4);
+    engine.execute();
+    assert_eq!(result(&engine), 48);
+}
+
+#[test]
+fn remap_and_ease() {
+    let mut engine = common::engine();
+
+    let set_input = |engine: &mut grafiek_engine::Engine, node, slot: usize, to: f32| {
+        engine
+            .edit_node_input(node, slot, |_, value| {
+                if let ValueMut::F32(v) = value {
+                    *v = to;
+                }
+            })
+            .unwrap();
+    };
+    let set_config = |engine: &mut grafiek_engine::Engine, node, name: &str, to: Value| {
+        let slot = config_index(engine, node, name);
+        engine
+            .edit_node_config(node, slot, |_, value| match (value, &to) {
+                (ValueMut::I32(v), Value::I32(to)) => *v = *to,
+                (ValueMut::Bool(v), Value::Bool(to)) => *v = *to,
+                _ => panic!("unexpected config type"),
+            })
+            .unwrap();
+    };
+    let result =
+        |engine: &grafiek_engine::Engine, node| match engine.get_node(node).unwrap().output(0) {
+            Some((_, Value::F32(v))) => *v,
+            _ => panic!("expected F32"),
+        };
+
+    // 5 in 0..10 lands halfway along a reversed 100..0
+    let remap = engine.instance_node("math", "remap").unwrap();
+    set_input(&mut engine, remap, 0, 5.0);
+    set_input(&mut engine, remap, 2, 10.0);
+    set_input(&mut engine, remap, 3, 100.0);
+    set_input(&mut engine, remap, 4, 0.0);
+    engine.execute();
+    assert!((result(&engine, remap) - 50.0).abs() < 1e-4);
+
+    set_input(&mut engine, remap, 0, 20.0);
+    engine.execute();
+    assert!((result(&engine, remap) + 100.0).abs() < 1e-4);
+    set_config(&mut engine, remap, "clamp", Value::Bool(true));
+    engine.execute();
+    assert!(result(&engine, remap).abs() < 1e-4);
+
+    let ease = engine.instance_node("math", "ease").unwrap();
+    for curve in [EaseCurve::Cubic, EaseCurve::Elastic, EaseCurve::Bounce] {
+        for direction in [EaseDirection::In, EaseDirection::Out, EaseDirection::InOut] {
+            set_config(&mut engine, ease, "curve", Value::I32(curve as i32));
+            set_config(&mut engine, ease, "direction", Value::I32(direction as i32));
+            for (t, expected) in [(0.0, 0.0), (1.0, 1.0)] {
+                set_input(&mut engine, ease, 0, t);
+                engine.execute();
+                assert!((result(&engine, ease) - expected).abs() < 1e-4);
+            }
+        }
+    }
+
+    // Cubic in-out passes through the middle
+    set_config(
+        &mut engine,
+        ease,
+        "curve",
+        Value::I32(EaseCurve::Cubic as i32),
+    );
+    set_input(&mut engine, ease, 0, 0.25);
+    engine.execute();
+    assert!((result(&engine, ease) - 0.0625).abs() < 1e-4);
+}
+
+#[test]
+fn clock_and_lfo_follow_timing() {
+    let mut engine = common::engine();
+
+    let clock = engine.instance_node("time", "clock").unwrap();
+    let lfo = engine.instance_node("time", "lfo").unwrap();
+    let set_config = |engine: &mut grafiek_engine::Engine, node, name: &str, to: Value| {
+        let slot = config_index(engine, node, name);
+        engine
+            .edit_node_config(node, slot, |_, value| match (value, &to) {
+                (ValueMut::F32(v), Value::F32(to)) => *v = *to,
+                (ValueMut::I32(v), Value::I32(to)) => *v = *to,
+                _ => panic!("unexpected config type"),
+            })
+            .unwrap();
+    };
+    let output = |engine: &grafiek_engine::Engine, node, slot| {
+        engine
+            .get_node(node)
+            .unwrap()
+            .output(slot)
+            .unwrap()
+            .1
+            .clone()
+    };
+    let at_time = |engine: &mut grafiek_engine::Engine, time: f32| {
+        engine.set_timing(TimeInfo {
+            time,
+            delta: 0.5,
+            frame: 7,
+        });
+        engine.execute();
+    };
+
+    set_config(&mut engine, clock, "speed", Value::F32(2.0));
+    set_config(&mut engine, clock, "offset", Value::F32(1.0));
+    at_time(&mut engine, 3.0);
+    assert_eq!(output(&engine, clock, 0), Value::F32(7.0));
+    assert_eq!(output(&engine, clock, 1), Value::F32(1.0));
+    assert_eq!(output(&engine, clock, 2), Value::I32(7));
+
+    // One cycle a second, a quarter of the way through
+    set_config(&mut engine, lfo, "shape", Value::I32(LfoShape::Saw as i32));
+    at_time(&mut engine, 2.25);
+    assert_eq!(output(&engine, lfo, 0), Value::F32(-0.5));
+
+    set_config(
+        &mut engine,
+        lfo,
+        "shape",
+        Value::I32(LfoShape::Square as i32),
+    );
+    at_time(&mut engine, 2.75);
+    assert_eq!(output(&engine, lfo, 0), Value::F32(-1.0));
+
+    // Random hold keeps its value through a cycle and repeats it on replay
+    set_config(
+        &mut engine,
+        lfo,
+        "shape",
+        Value::I32(LfoShape::RandomHold as i32),
+    );
+    let held: Vec<_> = [2.1, 2.9, 3.1, 2.5]
+        .into_iter()
+        .map(|time| {
+            at_time(&mut engine, time);
+            output(&engine, lfo, 0)
+        })
+        .collect();
+    assert_eq!(held[0], held[1]);
+    assert_ne!(held[1], held[2]);
+    assert_eq!(held[0], held[3]);
+
+    // Each engine seed and seed input gives its own sequence
+    engine.set_seed(7);
+    at_time(&mut engine, 2.1);
+    assert_ne!(output(&engine, lfo, 0), held[0]);
+    engine.set_seed(0);
+    engine
+        .edit_node_input(lfo, 3, |_, value| {
+            if let ValueMut::I32(v) = value {
+                *v = 3;
+            }
+        })
+        .unwrap();
+    at_time(&mut engine, 2.1);
+    assert_ne!(output(&engine, lfo, 0), held[0]);
+}
+
+#[test]
+fn signal_state_and_reset() {
+    let mut engine = common::engine();
+
+    let counter = engine.instance_node("signal", "counter").unwrap();
+    let hold = engine.instance_node("signal", "sample_hold").unwrap();
+    let smooth = engine.instance_node("signal", "smooth").unwrap();
+    assert!(engine.get_node(counter).unwrap().is_stateful());
+
+    let set_input = |engine: &mut grafiek_engine::Engine, node, slot: usize, to: Value| {
+        engine
+            .edit_node_input(node, slot, |_, value| match (value, &to) {
+                (ValueMut::F32(v), Value::F32(to)) => *v = *to,
+                (ValueMut::Bool(v), Value::Bool(to)) => *v = *to,
+                _ => panic!("unexpected input type"),
+            })
+            .unwrap();
+    };
+    let output = |engine: &grafiek_engine::Engine, node| {
+        engine.get_node(node).unwrap().output(0).unwrap().1.clone()
+    };
+    let step = |engine: &mut grafiek_engine::Engine| {
+        engine.set_timing(TimeInfo {
+            delta: 0.25,
+            ..Default::default()
+        });
+        engine.execute();
+    };
+
+    // Each rise of the trigger counts once however long it stays high
+    for (trigger, value) in [(true, 1.0), (true, 2.0), (false, 3.0), (true, 4.0)] {
+        set_input(&mut engine, counter, 0, Value::Bool(trigger));
+        set_input(&mut engine, hold, 0, Value::F32(value));
+        set_input(&mut engine, hold, 1, Value::Bool(trigger));
+        set_input(&mut engine, smooth, 0, Value::F32(value));
+        step(&mut engine);
+    }
+    assert_eq!(output(&engine, counter), Value::I32(2));
+    assert_eq!(output(&engine, hold), Value::F32(4.0));
+    let Value::F32(smoothed) = output(&engine, smooth) else {
+        panic!("expected F32");
+    };
+    assert!(smoothed > 1.0 && smoothed < 4.0);
+
+    set_input(&mut engine, counter, 2, Value::Bool(true));
+    step(&mut engine);
+    assert_eq!(output(&engine, counter), Value::I32(0));
+    set_input(&mut engine, counter, 2, Value::Bool(false));
+
+    // The trigger is still high, after a reset that counts as a new rise
+    engine.reset();
+    set_input(&mut engine, hold, 0, Value::F32(5.0));
+    set_input(&mut engine, smooth, 0, Value::F32(5.0));
+    step(&mut engine);
+    assert_eq!(output(&engine, counter), Value::I32(1));
+    assert_eq!(output(&engine, hold), Value::F32(5.0));
+    assert_eq!(output(&engine, smooth), Value::F32(5.0));
+
+    // Feedback shaders are stateful and rebuild their targets on reset
+    let feedback = engine.instance_node("shader", "custom").unwrap();
+    let source_slot = config_index(&engine, feedback, "source");
+    let source = r#"#version 450
+#include "sampler.glsl"
+
+#pragma target(name="feedback", persistent, screen)
+layout(set = 0, binding = 1) uniform texture2D feedback;
+
+layout(location = 0) out vec4 out_color;
+
+void main() {
+    ivec2 size = textureSize(sampler2D(feedback, default_sampler), 0);
+    vec2 uv = gl_FragCoord.xy / vec2(size);
+    out_color = texture(sampler2D(feedback, default_sampler), uv) + vec4(0.25);
+}
+"#;
+    engine
+        .edit_node_config(feedback, source_slot, |_, value| {
+            if let ValueMut::String(s) = value {
+                *s = source.to_string();
+            }
+        })
+        .unwrap();
+    assert!(engine.get_node(feedback).unwrap().is_stateful());
+    step(&mut engine);
+    engine.reset();
+    step(&mut engine);
+    assert!(!engine.node_has_errors(feedback));
+}
+
+#[test]
+fn trig_in_degrees() {
+    let mut engine = common::engine();
+
+    let trig = engine.instance_node("math", "trig").unwrap();
+    let set_config = |engine: &mut grafiek_engine::Engine, name: &str, to: i32| {
+        let slot = config_index(engine, trig, name);
+        engine
+            .edit_node_config(trig, slot, |_, value| {
+                if let ValueMut::I32(v) = value {
+                    *v = to;
+                }
+            })
+            .unwrap();
+    };
+    let set_input = |engine: &mut grafiek_engine::Engine, slot: usize, to: f32| {
+        engine
+            .edit_node_input(trig, slot, |_, value| {
+                if let ValueMut::F32(v) = value {
+                    *v = to;
+                }
+            })
+            .unwrap();
+    };
+    let result = |engine: &grafiek_engine::Engine| match engine.get_node(trig).unwrap().output(0) {
+        Some((_, Value::F32(v))) => *v,
+        _ => panic!("expected F32"),
+    };
+
+    set_config(&mut engine, "unit", AngleUnit::Degrees as i32);
+    let node = engine.get_node(trig).unwrap();
+    match node.input(0) {
+        Some((def, _)) => assert!(matches!(
+            def.extended(),
+            ExtendedMetadata::Angle(Angle {
+                unit: AngleUnit::Degrees,
+                ..
+            })
+        )),
+        None => panic!("expected an angle input"),
+    }
+
+    set_input(&mut engine, 0, 90.0);
+    engine.execute();
+    assert!((result(&engine) - 1.0).abs() < 1e-6);
+
+    set_config(&mut engine, "operation", TrigOp::Atan2 as i32);
+    assert_eq!(input_names(&engine, trig), ["y", "x"]);
+    set_input(&mut engine, 0, 1.0);
+    set_input(&mut engine, 1, 1.0);
+    engine.execute();
+    assert!((result(&engine) - 45.0).abs() < 1e-4);
+}
+
+#[test]
+fn constant_is_not_a_graph_input() {
+    let mut engine = common::engine();
+
+    let constant = engine.instance_node("value", "constant").unwrap();
+    let output = engine.add_node(Box::new(Output)).unwrap();
+    engine.connect(constant, output, 0, 0).unwrap();
+
+    let type_slot = config_index(&engine, constant, "type");
+    engine
+        .edit_node_config(constant, type_slot, |_, value| {
+            if let ValueMut::I32(v) = value {
+                *v = ConstantType::Int as i32;
+            }
+        })
+        .unwrap();
+
+    let value_slot = config_index(&engine, constant, "value");
+    engine
+        .edit_node_config(constant, value_slot, |_, value| {
+            if let ValueMut::I32(v) = value {
+                *v = 42;
+            }
+        })
+        .unwrap();
+
+    engine.execute();
+    match engine.result(0) {
+        Some(Value::I32(v)) => assert_eq!(*v, 42),
+        other => panic!("expected I32, got {other:?}"),
+    }
+    assert_eq!(engine.inputs().count(), 0);
+    assert!(engine.edit_graph_input(constant, |_, _| ()).is_err());
+}
+
+#[test]
+fn expression_inputs_and_errors() {
+    let mut engine = common::engine();
+
+    let expression = engine.instance_node("math", "expression").unwrap();
+    let slot = config_index(&engine, expression, "expression");
+    let set_expression = |engine: &mut grafiek_engine::Engine, text: &str| {
+        let _ = engine.edit_node_config(expression, slot, |_, value| {
+            if let ValueMut::String(s) = value {
+                *s = text.to_string();
+            }
+        });
+    };
+    let set_input = |engine: &mut grafiek_engine::Engine, slot: usize, to: Value| {
+        engine
+            .edit_node_input(expression, slot, |_, value| match (value, to) {
+                (ValueMut::F32(v), Value::F32(to)) => *v = to,
+                (ValueMut::I32(v), Value::I32(to)) => *v = to,
+                _ => panic!("unexpected input type"),
+            })
+            .unwrap();
+    };
+    let result = |engine: &grafiek_engine::Engine| {
+        engine
+            .get_node(expression)
+            .unwrap()
+            .output(0)
+            .unwrap()
+            .1
+            .clone()
+    };
+
+    set_expression(&mut engine, "a * sin(b) + c^2");
+    assert!(!engine.node_has_errors(expression));
+    assert_eq!(input_names(&engine, expression), ["a", "b", "c"]);
+    set_input(&mut engine, 0, Value::F32(2.0));
+    set_input(&mut engine, 1, Value::F32(std::f32::consts::FRAC_PI_2));
+    set_input(&mut engine, 2, Value::F32(3.0));
+    engine.execute();
+    match result(&engine) {
+        Value::F32(v) => assert!((v - 11.0).abs() < 1e-5),
+        other => panic!("expected F32, got {other:?}"),
+    }
+
+    // Ints divide as ints, comparisons give bools
+    set_expression(&mut engine, "n: int / 2 == 3");
+    set_input(&mut engine, 0, Value::I32(7));
+    engine.execute();
+    assert_eq!(result(&engine), Value::Bool(true));
+
+    set_expression(&mut engine, "a +\n  * b");
+    let errors = engine.node_errors(expression).unwrap();
+    let script = errors[0].as_script_error().expect("a script error");
+    assert_eq!((script.errors[0].line, script.errors[0].column), (2, 3));
+    // The last good inputs are kept while the expression is broken
+    assert_eq!(input_names(&engine, expression), ["n"]);
+
+    // Deep nesting is an error rather than a stack overflow
+    for deep in ["(".repeat(10_000) + "1", "-".repeat(10_000) + "1"] {
+        set_expression(&mut engine, &deep);
+        let errors = engine.node_errors(expression).unwrap();
+        let script = errors[0].as_script_error().expect("a script error");
+        assert_eq!(script.errors[0].message, "expression nested too deeply");
+    }
+
+    // Long chains of operators don't nest
+    set_expression(&mut engine, &("1".to_owned() + &"+1".repeat(10_000)));
+    assert!(!engine.node_has_errors(expression));
+
+    // Int division is Euclidean, matching math/integer
+    for (text, expected) in [("n: int / 2", -4), ("n: int % 2", 1), ("n: int / -2", 4)] {
+        set_expression(&mut engine, text);
+        set_input(&mut engine, 0, Value::I32(-7));
+        engine.execute();
+        assert_eq!(result(&engine), Value::I32(expected));
+    }
+
+    // Overflowing division wraps like the other int operators
+    set_expression(&mut engine, "n: int % -1 + n / -1");
+    set_input(&mut engine, 0, Value::I32(i32::MIN));
+    engine.execute();
+    assert!(!engine.node_has_errors(expression));
+    assert_eq!(result(&engine), Value::I32(i32::MIN));
+}
+
+#[test]
+fn compare_and_gate() {
+    let mut engine = common::engine();
+
+    let compare = engine.instance_node("logic", "compare").unwrap();
+    let slot = config_index(&engine, compare, "operation");
+    engine
+        .edit_node_config(compare, slot, |_, value| {
+            if let ValueMut::I32(v) = value {
+                *v = CompareOp::LessEqual as i32;
+            }
+        })
+        .unwrap();
+    engine
+        .edit_node_input(compare, 0, |_, value| {
+            if let ValueMut::F32(v) = value {
+                *v = 1.000001;
+            }
+        })
+        .unwrap();
+    engine
+        .edit_node_input(compare, 1, |_, value| {
+            if let ValueMut::F32(v) = value {
+                *v = 1.0;
+            }
+        })
+        .unwrap();
+
+    let gate = engine.instance_node("logic", "gate").unwrap();
+    let slot = config_index(&engine, gate, "operation");
+    engine
+        .edit_node_config(gate, slot, |_, value| {
+            if let ValueMut::I32(v) = value {
+                *v = GateOp::Not as i32;
+            }
+        })
+        .unwrap();
+    assert_eq!(input_names(&engine, gate), ["a"]);
+    engine.connect(compare, gate, 0, 0).unwrap();
+
+    engine.execute();
+    let output = |node| engine.get_node(node).unwrap().output(0).unwrap().1.clone();
+    // Within epsilon counts as equal
+    assert_eq!(output(compare), Value::Bool(true));
+    assert_eq!(output(gate), Value::Bool(false));
+}
+
+#[test]
+fn switch_follows_connected_type() {
+    let mut engine = common::engine();
+
+    let a = engine.add_node(Box::new(Input)).unwrap();
+    let b = engine.add_node(Box::new(Input)).unwrap();
+    let switch = engine.instance_node("logic", "switch").unwrap();
+    let output = engine.add_node(Box::new(Output)).unwrap();
+
+    let value_type = |engine: &grafiek_engine::Engine| {
+        engine
+            .get_node(switch)
+            .unwrap()
+            .output(0)
+            .unwrap()
+            .0
+            .value_type()
+    };
+    assert_eq!(value_type(&engine), ValueType::Any);
+
+    engine.connect(a, switch, 0, 1).unwrap();
+    engine.connect(b, switch, 0, 2).unwrap();
+    engine.connect(switch, output, 0, 0).unwrap();
+    assert_eq!(value_type(&engine), ValueType::F32);
+
+    for (input, to) in [(a, 1.0), (b, 2.0)] {
+        engine
+            .edit_graph_input(input, |_, value| {
+                if let ValueMut::F32(v) = value {
+                    *v = to;
+                }
+            })
+            .unwrap();
+    }
+
+    engine.execute();
+    assert_eq!(engine.result(0), Some(&Value::F32(1.0)));
+
+    engine
+        .edit_node_input(switch, 0, |_, value| {
+            if let ValueMut::I32(v) = value {
+                *v = 1;
+            }
+        })
+        .unwrap();
+    engine.execute();
+    assert_eq!(engine.result(0), Some(&Value::F32(2.0)));
+
+    // Bool mode picks between `false` and `true`
+    let slot = config_index(&engine, switch, "mode");
+    engine
+        .edit_node_config(switch, slot, |_, value| {
+            if let ValueMut::I32(v) = value {
+                *v = SwitchMode::Bool as i32;
+            }
+        })
+        .unwrap();
+    assert_eq!(input_names(&engine, switch), ["condition", "false", "true"]);
+    engine.execute();
+    assert_eq!(engine.result(0), Some(&Value::F32(1.0)));
+
+    engine.disconnect(a, switch, 0, 1).unwrap();
+    assert_eq!(value_type(&engine), ValueType::F32);
+    engine.disconnect(b, switch, 0, 2).unwrap();
+    assert_eq!(value_type(&engine), ValueType::Any);
+}
+
 #[test]
 fn shader_error_captured() {
     let mut engine = common::engine();
@@ -124,3 +745,1181 @@ fn shader_error_captured() {
     let errors = engine.node_errors(grayscale).unwrap();
     assert!(!errors.is_empty());
 }
+
+#[test]
+fn failed_configure_is_not_retried_on_poll() {
+    let mut engine = common::engine();
+
+    let grayscale = engine.instance_node("shader", "grayscale").unwrap();
+    let source = config_index(&engine, grayscale, "source");
+    let _ = engine.edit_node_config(grayscale, source, |_, value| {
+        if let ValueMut::String(s) = value {
+            *s = "this is not valid glsl!".to_string();
+        }
+    });
+    assert!(engine.node_has_errors(grayscale));
+
+    engine.poll();
+    assert!(!engine.poll());
+    assert!(engine.node_has_errors(grayscale));
+}
+
+#[test]
+fn custom_shader_reflects_inputs() {
+    let mut engine = common::engine();
+
+    let custom = engine.instance_node("shader", "custom").unwrap();
+
+    assert!(engine.node_errors(custom).is_none());
+    let node = engine.get_node(custom).unwrap();
+    assert!(node.inputs().any(|(def, _)| def.name() == "amount"));
+}
+
+#[test]
+fn noise_templates_compile() {
+    let mut engine = common::engine();
+
+    for op in [
+        "value_noise",
+        "perlin_noise",
+        "simplex_noise",
+        "worley_noise",
+        "fbm",
+    ] {
+        let node = engine.instance_node("shader", op).unwrap();
+        engine.execute();
+        assert!(!engine.node_has_errors(node), "{op} failed");
+        assert_eq!(input_names(&engine, node)[..2], ["size", "seed"]);
+    }
+}
+
+#[test]
+fn engine_seed_pragma_errors_are_located() {
+    let mut engine = common::engine();
+
+    let custom = engine.instance_node("shader", "custom").unwrap();
+    let source_slot = config_index(&engine, custom, "source");
+    let source = r#"#version 450
+#pragma input(int, name="seed", default=0, min=0, max=10)
+#pragma engine_seed()
+layout(set = 0, binding = 0) uniform Inputs {
+    int seed;
+};
+
+layout(location = 0) out vec4 out_color;
+
+void main() {
+    out_color = vec4(float(seed) / 10.0);
+}
+"#;
+    let _ = engine.edit_node_config(custom, source_slot, |_, value| {
+        if let ValueMut::String(s) = value {
+            *s = source.to_string();
+        }
+    });
+    let errors = engine.node_errors(custom).unwrap();
+    let script = errors[0].as_script_error().expect("a script error");
+    assert_eq!((script.errors[0].line, script.errors[0].column), (3, 1));
+
+    // Naming the input compiles, the directive is blanked before tweak_shader sees it
+    let fixed = source.replace("engine_seed()", "engine_seed(seed)");
+    let _ = engine.edit_node_config(custom, source_slot, |_, value| {
+        if let ValueMut::String(s) = value {
+            *s = fixed;
+        }
+    });
+    engine.execute();
+    assert!(!engine.node_has_errors(custom));
+    assert_eq!(input_names(&engine, custom), ["seed"]);
+
+    // Naming something other than an int input points at the directive too
+    let float_input = source
+        .replace("engine_seed()", "engine_seed(seed)")
+        .replace(
+            "input(int, name=\"seed\", default=0, min=0, max=10)",
+            "input(float, name=\"seed\", default=0.0, min=0.0, max=10.0)",
+        )
+        .replace("int seed;", "float seed;")
+        .replace("float(seed)", "seed");
+    for broken in [
+        source.replace("engine_seed()", "engine_seed(missing)"),
+        float_input,
+    ] {
+        let _ = engine.edit_node_config(custom, source_slot, |_, value| {
+            if let ValueMut::String(s) = value {
+                *s = broken;
+            }
+        });
+        let errors = engine.node_errors(custom).unwrap();
+        let script = errors[0].as_script_error().expect("a script error");
+        assert_eq!((script.errors[0].line, script.errors[0].column), (3, 1));
+    }
+}
+
+#[test]
+fn random_values_follow_seeds() {
+    let mut engine = common::engine();
+
+    let random = engine.instance_node("random", "value").unwrap();
+    let set_config = |engine: &mut grafiek_engine::Engine, name: &str, to: Value| {
+        let slot = config_index(engine, random, name);
+        engine
+            .edit_node_config(random, slot, |_, value| match (value, &to) {
+                (ValueMut::I32(v), Value::I32(to)) => *v = *to,
+                (ValueMut::Bool(v), Value::Bool(to)) => *v = *to,
+                _ => panic!("unexpected config type"),
+            })
+            .unwrap();
+    };
+    let draw = |engine: &mut grafiek_engine::Engine, frame| {
+        engine.set_timing(TimeInfo {
+            frame,
+            ..Default::default()
+        });
+        engine.execute();
+        engine
+            .get_node(random)
+            .unwrap()
+            .output(0)
+            .unwrap()
+            .1
+            .clone()
+    };
+
+    let first = draw(&mut engine, 0);
+    assert_eq!(draw(&mut engine, 1), first);
+    let Value::F32(v) = first else {
+        panic!("expected F32");
+    };
+    assert!((0.0..1.0).contains(&v));
+
+    engine.set_seed(7);
+    let reseeded = draw(&mut engine, 0);
+    assert_ne!(reseeded, first);
+    engine.set_seed(0);
+    assert_eq!(draw(&mut engine, 0), first);
+
+    set_config(&mut engine, "reseed each frame", Value::Bool(true));
+    assert_ne!(draw(&mut engine, 1), draw(&mut engine, 2));
+    assert_eq!(draw(&mut engine, 3), draw(&mut engine, 3));
+
+    set_config(
+        &mut engine,
+        "distribution",
+        Value::I32(Distribution::Integer as i32),
+    );
+    engine
+        .edit_node_input(random, 2, |_, value| {
+            if let ValueMut::I32(v) = value {
+                *v = 3;
+            }
+        })
+        .unwrap();
+    for frame in 0..32 {
+        let Value::I32(v) = draw(&mut engine, frame) else {
+            panic!("expected I32");
+        };
+        assert!((0..=3).contains(&v));
+    }
+}
+
+#[test]
+fn save_shader_as_user_operator() {
+    let dir = std::env::temp_dir().join(format!("grafiek_user_library_{}", std::process::id()));
+    let mut engine = common::engine();
+    engine.set_user_library(&dir).unwrap();
+
+    let custom = engine.instance_node("shader", "custom").unwrap();
+
+    assert!(engine.save_shader_operator(custom, "not a name").is_err());
+
+    let path = engine.save_shader_operator(custom, "tint").unwrap();
+    assert_eq!(path.library, "user");
+    assert!(dir.join("tint.glsl").exists());
+
+    let tint = engine.instance_node("user", "tint").unwrap();
+    assert_eq!(input_names(&engine, custom), input_names(&engine, tint));
+
+    // A fresh engine picks the saved operator up from disk
+    let mut other = common::engine();
+    assert_eq!(other.set_user_library(&dir).unwrap(), 1);
+    assert!(other.iter_category("user").any(|o| o == "tint"));
+
+    // A directory that can't be read leaves the previous operators in place
+    let broken = dir.join("broken");
+    std::fs::create_dir_all(broken.join("unreadable.glsl")).unwrap();
+    assert!(other.set_user_library(&broken).is_err());
+    assert!(other.iter_category("user").any(|o| o == "tint"));
+    assert_eq!(other.user_library(), Some(dir.as_path()));
+
+    // Switching directories drops the operators of the old one
+    let empty = dir.join("empty");
+    assert_eq!(other.set_user_library(&empty).unwrap(), 0);
+    assert_eq!(other.iter_category("user").count(), 0);
+    assert!(other.instance_node("user", "tint").is_err());
+
+    // Nodes running a file save the file rather than their stale source
+    let file = dir.join("running.txt");
+    std::fs::write(&file, shader_with_input("gain")).unwrap();
+    let file_slot = config_index(&engine, custom, "file");
+    engine
+        .edit_node_config(custom, file_slot, |_, value| {
+            if let ValueMut::String(s) = value {
+                *s = file.to_string_lossy().into_owned();
+            }
+        })
+        .unwrap();
+    engine.save_shader_operator(custom, "from_file").unwrap();
+    assert_eq!(
+        std::fs::read_to_string(dir.join("from_file.glsl")).unwrap(),
+        shader_with_input("gain")
+    );
+
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn shader_includes() {
+    let dir = std::env::temp_dir().join(format!("grafiek_includes_{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    std::fs::write(dir.join("broken.glsl"), "// fine\nfloat broken( {\n").unwrap();
+
+    let mut engine = common::engine();
+    engine.add_shader_include_path(&dir);
+
+    let custom = engine.instance_node("shader", "custom").unwrap();
+    let source_slot = config_index(&engine, custom, "source");
+    let set_source = |engine: &mut grafiek_engine::Engine, include: &str| {
+        let source = shader_with_input("amount").replacen(
+            "#version 450\n",
+            &format!("#version 450\n#include \"{include}\"\n"),
+            1,
+        );
+        let _ = engine.edit_node_config(custom, source_slot, |_, value| {
+            if let ValueMut::String(s) = value {
+                *s = source;
+            }
+        });
+    };
+
+    set_source(&mut engine, "noise.glsl");
+    assert!(!engine.node_has_errors(custom));
+
+    // A missing include points at its directive
+    set_source(&mut engine, "missing.glsl");
+    let errors = engine.node_errors(custom).unwrap();
+    let script = errors[0].as_script_error().expect("a script error");
+    assert_eq!((script.errors[0].line, script.errors[0].column), (2, 1));
+
+    // Errors inside an include point at the included file
+    set_source(&mut engine, "broken.glsl");
+    let errors = engine.node_errors(custom).unwrap();
+    let located = errors
+        .iter()
+        .filter_map(|e| e.as_script_error())
+        .flat_map(|e| e.errors.iter())
+        .next()
+        .unwrap();
+    assert_eq!(located.file.as_deref(), Some("broken.glsl"));
+    assert_eq!(located.line, 2);
+
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn shader_pipelines_are_cached() {
+    let mut engine = common::engine();
+
+    let first = engine.instance_node("shader", "grayscale").unwrap();
+    let compiled = engine.pipeline_stats().misses;
+    assert_eq!(compiled, 1);
+
+    // Another node of the same type shares the pipeline
+    engine.instance_node("shader", "grayscale").unwrap();
+    assert_eq!(engine.pipeline_stats().misses, compiled);
+
+    // Changes that keep the source and format don't recompile
+    let hits = engine.pipeline_stats().hits;
+    let preview = config_index(&engine, first, "preview");
+    engine
+        .edit_node_config(first, preview, |_, value| {
+            if let ValueMut::Bool(b) = value {
+                *b = false;
+            }
+        })
+        .unwrap();
+    let stats = engine.pipeline_stats();
+    assert_eq!(stats.misses, compiled);
+    assert!(stats.hits > hits);
+    assert_eq!(stats.entries, 1);
+}
+
+#[test]
+fn shader_output_formats() {
+    for format in TextureFormat::ALL {
+        let Some(mut engine) = common::engine_with_format(format) else {
+            eprintln!("skipping unsupported format {format:?}");
+            continue;
+        };
+
+        // The image input defaults to an 8 bit texture, so other formats go through a conversion
+        let grayscale = engine.instance_node("shader", "grayscale").unwrap();
+        engine.execute();
+        assert!(!engine.node_has_errors(grayscale), "{format:?}");
+
+        let node = engine.get_node(grayscale).unwrap();
+        let Value::Texture(handle) = node.output(0).unwrap().1 else {
+            panic!("expected a texture output");
+        };
+        assert_eq!(handle.fmt(), format);
+        assert_eq!(
+            engine.get_texture(handle).unwrap().format(),
+            format.to_wgpu()
+        );
+    }
+}
+
+#[test]
+fn shader_format_overrides_default() {
+    let mut engine = common::engine();
+
+    let grayscale = engine.instance_node("shader", "grayscale").unwrap();
+    let format = config_index(&engine, grayscale, "format");
+    engine
+        .edit_node_config(grayscale, format, |_, value| {
+            if let ValueMut::I32(v) = value {
+                *v = 2;
+            }
+        })
+        .unwrap();
+    engine.execute();
+    assert!(!engine.node_has_errors(grayscale));
+
+    let node = engine.get_node(grayscale).unwrap();
+    let Value::Texture(handle) = node.output(0).unwrap().1 else {
+        panic!("expected a texture output");
+    };
+    assert_eq!(handle.fmt(), TextureFormat::RGBAF32);
+}
+
+#[test]
+fn shader_inputs_convert_to_linear_light() {
+    let mut engine = common::engine();
+
+    let input = engine.instance_node("core", "input").unwrap();
+    engine
+        .edit_node_config(input, 0, |_, value| {
+            if let ValueMut::I32(v) = value {
+                *v = 2;
+            }
+        })
+        .unwrap();
+    engine
+        .upload_texture_in(input, 0, 2, 2, ColorSpace::Srgb, &[188; 16])
+        .unwrap();
+
+    let grayscale = engine.instance_node("shader", "grayscale").unwrap();
+    engine.connect(input, grayscale, 0, 0).unwrap();
+
+    let color_space_of = |engine: &grafiek_engine::Engine, node| match engine
+        .get_node(node)
+        .unwrap()
+        .output(0)
+        .unwrap()
+        .1
+    {
+        Value::Texture(handle) => handle.color_space(),
+        other => panic!("expected texture, got {other:?}"),
+    };
+
+    engine.execute();
+    assert!(!engine.node_has_errors(grayscale));
+    assert_eq!(color_space_of(&engine, input), ColorSpace::Srgb);
+    assert_eq!(color_space_of(&engine, grayscale), ColorSpace::Linear);
+
+    // With linear light off the input passes through in its own space
+    let linear_light = config_index(&engine, grayscale, "linear light");
+    engine
+        .edit_node_config(grayscale, linear_light, |_, value| {
+            if let ValueMut::Bool(v) = value {
+                *v = false;
+            }
+        })
+        .unwrap();
+    engine.execute();
+    assert!(!engine.node_has_errors(grayscale));
+    assert_eq!(color_space_of(&engine, grayscale), ColorSpace::Srgb);
+}
+
+#[test]
+fn upload_wide_images() {
+    let mut engine = common::engine();
+
+    let input = engine.instance_node("core", "input").unwrap();
+    engine
+        .edit_node_config(input, 0, |_, value| {
+            if let ValueMut::I32(v) = value {
+                *v = 2;
+            }
+        })
+        .unwrap();
+
+    let handle_of = |engine: &grafiek_engine::Engine| match engine
+        .get_node(input)
+        .unwrap()
+        .output(0)
+        .unwrap()
+        .1
+    {
+        Value::Texture(handle) => *handle,
+        other => panic!("expected texture, got {other:?}"),
+    };
+
+    // Data that doesn't cover the image is rejected
+    assert!(matches!(
+        engine.upload_texture_in(input, 0, 4, 4, ColorSpace::Srgb, &[0; 16]),
+        Err(Error::TextureDataSize {
+            expected: 64,
+            actual: 16
+        })
+    ));
+
+    let pixels: Vec<u8> = [0.5f32, 2.0, 8.0, 1.0]
+        .repeat(4)
+        .iter()
+        .flat_map(|c| c.to_ne_bytes())
+        .collect();
+    let image = ImageData::new(2, 2, TextureFormat::RGBAF32, ColorSpace::Linear, pixels).unwrap();
+    engine.upload_image(input, 0, &image).unwrap();
+    let handle = handle_of(&engine);
+    assert_eq!(handle.fmt(), TextureFormat::RGBAF32);
+    assert_eq!(handle.color_space(), ColorSpace::Linear);
+    assert!(engine.get_texture(&handle).is_some());
+
+    // Rows padded out to 256 bytes, with 16 bit data widened where it isn't supported
+    let image = ImageData::with_row_stride(
+        3,
+        2,
+        TextureFormat::RGBAu16,
+        ColorSpace::Srgb,
+        256,
+        vec![255; 256 + 3 * 8],
+    )
+    .unwrap();
+    engine.upload_image(input, 0, &image).unwrap();
+    let handle = handle_of(&engine);
+    assert_eq!((handle.width(), handle.height()), (3, 2));
+    assert!(matches!(
+        handle.fmt(),
+        TextureFormat::RGBAu16 | TextureFormat::RGBAF32
+    ));
+    assert!(engine.get_texture(&handle).is_some());
+
+    // Rows wider than a u32 stride are rejected instead of wrapping
+    assert!(matches!(
+        ImageData::new(
+            u32::MAX / 4,
+            1,
+            TextureFormat::RGBAF32,
+            ColorSpace::Linear,
+            vec![]
+        ),
+        Err(Error::InvalidImageLayout { .. })
+    ));
+
+    assert!(matches!(
+        ImageData::decode(b"not an image"),
+        Err(Error::DecodeImage(_))
+    ));
+}
+
+#[test]
+fn convert_color_space() {
+    let mut engine = common::engine();
+
+    let grayscale = engine.instance_node("shader", "grayscale").unwrap();
+    let convert = engine.instance_node("core", "color_space").unwrap();
+    engine.connect(grayscale, convert, 0, 0).unwrap();
+
+    for space in ColorSpace::ALL {
+        engine
+            .edit_node_config(convert, 0, |_, value| {
+                if let ValueMut::I32(v) = value {
+                    *v = space as i32;
+                }
+            })
+            .unwrap();
+        engine.execute();
+        assert!(!engine.node_has_errors(convert), "{space:?}");
+
+        let node = engine.get_node(convert).unwrap();
+        let Value::Texture(handle) = node.output(0).unwrap().1 else {
+            panic!("expected a texture output");
+        };
+        assert_eq!(handle.color_space(), space);
+        assert!(engine.get_texture(handle).is_some());
+    }
+}
+
+/// Reads its input back to the CPU on every execute.
+#[derive(Default)]
+struct Readback {
+    image: Rc<RefCell<Option<ImageData>>>,
+}
+
+impl Operation for Readback {
+    fn is_stateful(&self) -> bool {
+        false
+    }
+
+    fn op_path(&self) -> OpPath {
+        OpPath {
+            library: "test".into(),
+            operator: "readback".into(),
+        }
+    }
+
+    fn setup(&mut self, _ctx: &mut ExecutionContext, registry: &mut SignatureRegistery) {
+        registry
+            .add_input::<TextureHandle>("image")
+            .default(SPECK)
+            .build();
+    }
+
+    fn configure(
+        &mut self,
+        _ctx: &ExecutionContext,
+        _config: Config,
+        _registry: &mut SignatureRegistery,
+    ) -> Result<(), Error> {
+        Ok(())
+    }
+
+    fn execute(
+        &mut self,
+        ctx: &mut ExecutionContext,
+        inputs: Inputs,
+        _outputs: Outputs,
+    ) -> Result<(), Error> {
+        let input: TextureHandle = inputs.extract(0)?;
+        *self.image.borrow_mut() = Some(ctx.read_texture(&input)?);
+        Ok(())
+    }
+}
+
+#[test]
+fn white_stays_white_across_color_spaces() {
+    let mut engine = common::engine();
+
+    let input = engine.instance_node("core", "input").unwrap();
+    engine
+        .edit_node_config(input, 0, |_, value| {
+            if let ValueMut::I32(v) = value {
+                *v = 2;
+            }
+        })
+        .unwrap();
+    let convert = engine.instance_node("core", "color_space").unwrap();
+    let readback = Readback::default();
+    let image = readback.image.clone();
+    let readback = engine.add_node(Box::new(readback)).unwrap();
+    engine.connect(input, convert, 0, 0).unwrap();
+    engine.connect(convert, readback, 0, 0).unwrap();
+
+    let white: Vec<u8> = [1.0f32; 4].iter().flat_map(|c| c.to_ne_bytes()).collect();
+    let mut convert_white = |from: ColorSpace, to: ColorSpace| {
+        let upload = ImageData::new(1, 1, TextureFormat::RGBAF32, from, white.clone()).unwrap();
+        engine.upload_image(input, 0, &upload).unwrap();
+        engine
+            .edit_node_config(convert, 0, |_, value| {
+                if let ValueMut::I32(v) = value {
+                    *v = to as i32;
+                }
+            })
+            .unwrap();
+        engine.execute();
+        assert!(!engine.node_has_errors(convert), "{from:?} to {to:?}");
+
+        let pixel = image.borrow_mut().take().unwrap().to_float().unwrap();
+        for c in pixel.data().chunks_exact(4) {
+            let c = f32::from_ne_bytes(c.try_into().unwrap());
+            assert!((c - 1.0).abs() < 1e-4, "{from:?} to {to:?} gave {c}");
+        }
+    };
+
+    // Through linear Rec. 709 both ways, so each decode and encode is checked
+    for space in ColorSpace::ALL
+        .into_iter()
+        .filter(|&s| s != ColorSpace::Linear)
+    {
+        convert_white(space, ColorSpace::Linear);
+        convert_white(ColorSpace::Linear, space);
+    }
+}
+
+#[test]
+fn texture_pool_recycles_and_evicts() {
+    let mut engine = common::engine();
+
+    // Not executed, so the output keeps its configured 512x512 rather than matching the input
+    let first = engine.instance_node("shader", "grayscale").unwrap();
+    let stats = engine.texture_stats();
+    let node_bytes = stats.bytes_per_owner[&TextureOwner::Node(first)];
+    assert_eq!(node_bytes, 512 * 512 * 4);
+    assert!(stats.peak_bytes >= stats.live_bytes);
+
+    // The deleted node's output is handed to the next node of the same shape
+    engine.delete_node(first).unwrap();
+    let stats = engine.texture_stats();
+    assert_eq!(stats.free_textures, 1);
+    assert!(
+        !stats
+            .bytes_per_owner
+            .contains_key(&TextureOwner::Node(first))
+    );
+
+    let second = engine.instance_node("shader", "grayscale").unwrap();
+    let stats = engine.texture_stats();
+    assert_eq!(stats.recycled, 1);
+    assert_eq!(stats.free_textures, 0);
+
+    engine.delete_node(second).unwrap();
+    engine.set_texture_budget(Some(0));
+    let stats = engine.texture_stats();
+    assert_eq!(stats.free_textures, 0);
+    assert_eq!(stats.free_bytes, 0);
+    assert_eq!(stats.evicted, 1);
+}
+
+/// Keeps the first texture handle it's given and uploads over it once the
+/// input moves on, like a node that missed its input being resized.
+#[derive(Default)]
+struct StaleHolder {
+    held: Option<TextureHandle>,
+}
+
+impl Operation for StaleHolder {
+    fn is_stateful(&self) -> bool {
+        true
+    }
+
+    fn op_path(&self) -> OpPath {
+        OpPath {
+            library: "test".into(),
+            operator: "stale_holder".into(),
+        }
+    }
+
+    fn setup(&mut self, _ctx: &mut ExecutionContext, registry: &mut SignatureRegistery) {
+        registry
+            .add_input::<TextureHandle>("image")
+            .default(SPECK)
+            .build();
+    }
+
+    fn configure(
+        &mut self,
+        _ctx: &ExecutionContext,
+        _config: Config,
+        _registry: &mut SignatureRegistery,
+    ) -> Result<(), Error> {
+        Ok(())
+    }
+
+    fn execute(
+        &mut self,
+        ctx: &mut ExecutionContext,
+        inputs: Inputs,
+        _outputs: Outputs,
+    ) -> Result<(), Error> {
+        let input: TextureHandle = inputs.extract(0)?;
+        let held = self.held.get_or_insert(input);
+        if held.id() != input.id() {
+            let image =
+                ImageData::new(1, 1, TextureFormat::RGBAu8, ColorSpace::Linear, vec![0; 4])?;
+            ctx.upload_image(held, &image)?;
+        }
+        Ok(())
+    }
+}
+
+#[test]
+fn stale_release_keeps_the_live_texture() {
+    let mut engine = common::engine();
+
+    let shader = engine.instance_node("shader", "grayscale").unwrap();
+    let holder = engine.add_node(Box::new(StaleHolder::default())).unwrap();
+    engine.connect(shader, holder, 0, 0).unwrap();
+    engine.execute();
+
+    // The output stops matching its 1x1 input and is reallocated at 512x512
+    let match_dims = config_index(&engine, shader, "match input dimensions");
+    engine
+        .edit_node_config(shader, match_dims, |_, value| {
+            if let ValueMut::Bool(v) = value {
+                *v = false;
+            }
+        })
+        .unwrap();
+
+    // Uploading over the stale handle must not free the texture that replaced it
+    engine.execute();
+    let Value::Texture(live) = engine.get_node(shader).unwrap().output(0).unwrap().1 else {
+        panic!("expected a texture output");
+    };
+    assert!(engine.get_texture(live).is_some());
+    assert!(!engine.node_has_errors(shader));
+
+    // Deleting the node releases its textures whatever their generation
+    engine.delete_node(shader).unwrap();
+    let stats = engine.texture_stats();
+    assert!(
+        !stats
+            .bytes_per_owner
+            .contains_key(&TextureOwner::Node(shader))
+    );
+}
+
+#[test]
+fn shader_sampling_directives() {
+    let mut engine = common::engine();
+
+    let input = engine.instance_node("core", "input").unwrap();
+    engine
+        .edit_node_config(input, 0, |_, value| {
+            if let ValueMut::I32(v) = value {
+                *v = 2;
+            }
+        })
+        .unwrap();
+    engine
+        .upload_texture(input, 0, 64, 64, &[255; 64 * 64 * 4])
+        .unwrap();
+
+    let custom = engine.instance_node("shader", "custom").unwrap();
+    engine.connect(input, custom, 0, 0).unwrap();
+    let source_slot = config_index(&engine, custom, "source");
+    let set_source = |engine: &mut grafiek_engine::Engine, sampling: &str| {
+        let source = format!(
+            r#"#version 450
+#include "sampler.glsl"
+
+#pragma input(image, name=image)
+#pragma sampling({sampling})
+layout(set = 0, binding = 1) uniform texture2D image;
+
+layout(location = 0) out vec4 out_color;
+
+void main() {{
+    out_color = texture(sampler2D(image, default_sampler), frag_uv(ivec2(16)) * 4.0);
+}}
+"#
+        );
+        let _ = engine.edit_node_config(custom, source_slot, |_, value| {
+            if let ValueMut::String(s) = value {
+                *s = source;
+            }
+        });
+    };
+
+    set_source(&mut engine, "image, mipmaps");
+    engine.execute();
+    assert!(!engine.node_has_errors(custom));
+
+    let node = engine.get_node(custom).unwrap();
+    let (def, _) = node.inputs().next().unwrap();
+    let ExtendedMetadata::Texture(meta) = def.extended() else {
+        panic!("expected texture metadata");
+    };
+    assert!(meta.sampler.mipmaps);
+
+    set_source(&mut engine, "image, mipmaps=false");
+    engine.execute();
+    assert!(!engine.node_has_errors(custom));
+
+    // Settings the default sampler can't honour point at the directive, as do bad ones
+    for sampling in ["image, wrap=repeat", "image, mipmaps=sometimes"] {
+        set_source(&mut engine, sampling);
+        let errors = engine.node_errors(custom).unwrap();
+        let located = errors
+            .iter()
+            .filter_map(|e| e.as_script_error())
+            .flat_map(|e| e.errors.iter())
+            .next()
+            .unwrap();
+        assert_eq!(located.file, None);
+        assert_eq!((located.line, located.column), (5, 1));
+    }
+}
+
+fn shader_with_input(name: &str) -> String {
+    format!(
+        r#"#version 450
+#pragma input(float, name="{name}", default=1.0, min=0.0, max=1.0)
+layout(set = 0, binding = 0) uniform Inputs {{
+    float {name};
+}};
+
+layout(location = 0) out vec4 out_color;
+
+void main() {{
+    out_color = vec4(vec3({name}), 1.0);
+}}
+"#
+    )
+}
+
+fn config_index(
+    engine: &grafiek_engine::Engine,
+    node: grafiek_engine::NodeIndex,
+    name: &str,
+) -> usize {
+    engine
+        .get_node(node)
+        .unwrap()
+        .configs()
+        .position(|(def, _)| def.name() == name)
+        .unwrap()
+}
+
+fn input_names(engine: &grafiek_engine::Engine, node: grafiek_engine::NodeIndex) -> Vec<String> {
+    engine
+        .get_node(node)
+        .unwrap()
+        .inputs()
+        .map(|(def, _)| def.name().to_string())
+        .collect()
+}
+
+#[test]
+fn shader_file_hot_reload() {
+    use std::time::{Duration, SystemTime};
+
+    let path = std::env::temp_dir().join(format!("grafiek_hot_reload_{}.glsl", std::process::id()));
+    let write = |source: &str, age: u64| {
+        std::fs::write(&path, source).unwrap();
+        // Bump the mtime explicitly, filesystem timestamps can be coarse
+        let file = std::fs::File::options().write(true).open(&path).unwrap();
+        file.set_modified(SystemTime::now() + Duration::from_secs(age))
+            .unwrap();
+    };
+    write(&shader_with_input("first"), 0);
+
+    let mut engine = common::engine();
+    let custom = engine.instance_node("shader", "custom").unwrap();
+    let file_slot = config_index(&engine, custom, "file");
+
+    engine
+        .edit_node_config(custom, file_slot, |_, value| {
+            if let ValueMut::String(s) = value {
+                *s = path.to_string_lossy().into_owned();
+            }
+        })
+        .unwrap();
+
+    assert!(engine.node_errors(custom).is_none());
+    assert_eq!(input_names(&engine, custom), ["first"]);
+    assert!(!engine.poll());
+
+    write(&shader_with_input("second"), 10);
+    assert!(engine.poll());
+    assert_eq!(input_names(&engine, custom), ["second"]);
+
+    // A broken edit reports errors and keeps the last good shader
+    write("this is not valid glsl!", 20);
+    assert!(engine.poll());
+    assert!(engine.node_has_errors(custom));
+    assert_eq!(input_names(&engine, custom), ["second"]);
+
+    write(&shader_with_input("third"), 30);
+    assert!(engine.poll());
+    assert!(!engine.node_has_errors(custom));
+    assert_eq!(input_names(&engine, custom), ["third"]);
+
+    std::fs::remove_file(&path).unwrap();
+}
+
+#[test]
+fn image_file_loads_and_reloads() {
+    use std::time::{Duration, SystemTime};
+
+    let path = std::env::temp_dir().join(format!("grafiek_image_file_{}.ppm", std::process::id()));
+    let write = |width: usize, height: usize, age: u64| {
+        let mut data = format!("P6\n{width} {height}\n255\n").into_bytes();
+        data.extend(std::iter::repeat_n(128, width * height * 3));
+        std::fs::write(&path, data).unwrap();
+        let file = std::fs::File::options().write(true).open(&path).unwrap();
+        file.set_modified(SystemTime::now() + Duration::from_secs(age))
+            .unwrap();
+    };
+
+    let mut engine = common::engine();
+    let node = engine.instance_node("core", "image_file").unwrap();
+    let handle_of =
+        |engine: &grafiek_engine::Engine| match engine.get_node(node).unwrap().output(0).unwrap().1
+        {
+            Value::Texture(handle) => *handle,
+            other => panic!("expected texture, got {other:?}"),
+        };
+
+    // A missing file is a node error
+    engine
+        .edit_node_config(node, 0, |_, value| {
+            if let ValueMut::String(s) = value {
+                *s = path.to_string_lossy().into_owned();
+            }
+        })
+        .unwrap();
+    assert!(engine.node_has_errors(node));
+
+    write(2, 2, 0);
+    assert!(engine.poll());
+    engine.execute();
+    assert!(!engine.node_has_errors(node));
+    let handle = handle_of(&engine);
+    assert_eq!((handle.width(), handle.height()), (2, 2));
+    assert_eq!(handle.color_space(), ColorSpace::Srgb);
+    assert!(engine.get_texture(&handle).is_some());
+
+    write(3, 1, 10);
+    assert!(engine.poll());
+    engine.execute();
+    let handle = handle_of(&engine);
+    assert_eq!((handle.width(), handle.height()), (3, 1));
+    assert!(engine.get_texture(&handle).is_some());
+
+    // So is a corrupt one
+    std::fs::write(&path, b"P6\nnonsense").unwrap();
+    let file = std::fs::File::options().write(true).open(&path).unwrap();
+    file.set_modified(SystemTime::now() + Duration::from_secs(20))
+        .unwrap();
+    assert!(engine.poll());
+    assert!(engine.node_has_errors(node));
+
+    std::fs::remove_file(&path).unwrap();
+}
+
+#[test]
+fn image_sequence_follows_timeline() {
+    use std::time::{Duration, SystemTime};
+
+    let dir = std::env::temp_dir().join(format!("grafiek_sequence_{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    // Each frame is as wide as its number
+    for n in 1..=3usize {
+        let mut data = format!("P6\n{n} 1\n255\n").into_bytes();
+        data.extend(std::iter::repeat_n(255, n * 3));
+        std::fs::write(dir.join(format!("frame_{n:04}.ppm")), data).unwrap();
+    }
+
+    let mut engine = common::engine();
+    let node = engine.instance_node("core", "image_sequence").unwrap();
+    let set = |engine: &mut grafiek_engine::Engine, slot: usize, to: Value| {
+        engine
+            .edit_node_config(node, slot, |_, value| match (value, to) {
+                (ValueMut::String(s), Value::String(to)) => *s = to,
+                (ValueMut::I32(v), Value::I32(to)) => *v = to,
+                _ => panic!("mismatched config"),
+            })
+            .unwrap();
+    };
+    let widths = |engine: &mut grafiek_engine::Engine| -> Vec<u32> {
+        (0..6)
+            .map(|frame| {
+                engine.set_timing(TimeInfo {
+                    frame,
+                    ..Default::default()
+                });
+                engine.execute();
+                assert!(!engine.node_has_errors(node));
+                let node = engine.get_node(node).unwrap();
+                let Value::Texture(handle) = node.output(0).unwrap().1 else {
+                    panic!("expected a texture output");
+                };
+                let Value::I32(number) = node.output(1).unwrap().1 else {
+                    panic!("expected a frame number");
+                };
+                assert_eq!(handle.width(), *number as u32);
+                handle.width()
+            })
+            .collect()
+    };
+
+    let pattern = dir.join("frame_####.ppm").to_string_lossy().into_owned();
+    set(&mut engine, 0, Value::String(pattern));
+    assert_eq!(widths(&mut engine), [1, 2, 3, 3, 3, 3]);
+
+    set(&mut engine, 1, Value::I32(SequenceMode::Loop as i32));
+    assert_eq!(widths(&mut engine), [1, 2, 3, 1, 2, 3]);
+
+    set(&mut engine, 1, Value::I32(SequenceMode::PingPong as i32));
+    assert_eq!(widths(&mut engine), [1, 2, 3, 2, 1, 2]);
+
+    set(&mut engine, 2, Value::I32(1));
+    assert_eq!(widths(&mut engine), [2, 3, 2, 1, 2, 3]);
+
+    // A frame added to the directory extends the sequence
+    let mut data = b"P6\n4 1\n255\n".to_vec();
+    data.extend(std::iter::repeat_n(255, 4 * 3));
+    std::fs::write(dir.join("frame_0004.ppm"), data).unwrap();
+    assert!(engine.poll());
+    assert_eq!(widths(&mut engine), [2, 3, 4, 3, 2, 1]);
+
+    // A cached frame re-rendered in place is read again, though the directory
+    // itself doesn't change
+    let path = dir.join("frame_0002.ppm");
+    let mut data = b"P6\n5 1\n255\n".to_vec();
+    data.extend(std::iter::repeat_n(255, 5 * 3));
+    std::fs::write(&path, data).unwrap();
+    let file = std::fs::File::options().write(true).open(&path).unwrap();
+    file.set_modified(SystemTime::now() + Duration::from_secs(20))
+        .unwrap();
+    assert!(engine.poll());
+    engine.set_timing(TimeInfo::default());
+    engine.execute();
+    let Value::Texture(handle) = engine.get_node(node).unwrap().output(0).unwrap().1 else {
+        panic!("expected a texture output");
+    };
+    assert_eq!(handle.width(), 5);
+
+    let missing = dir.join("other_####.ppm").to_string_lossy().into_owned();
+    set(&mut engine, 0, Value::String(missing.clone()));
+    assert!(matches!(
+        engine.node_errors(node).unwrap(),
+        [Error::NoMatchingFrames(pattern)] if *pattern == missing
+    ));
+
+    set(&mut engine, 0, Value::String("frames.ppm".into()));
+    assert!(matches!(
+        engine.node_errors(node).unwrap(),
+        [Error::InvalidFramePattern(_)]
+    ));
+
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn file_output_writes_images_and_values() {
+    let dir = std::env::temp_dir().join(format!("grafiek_file_output_{}", std::process::id()));
+    let set = |engine: &mut grafiek_engine::Engine, node, slot: usize, to: Value| {
+        engine
+            .edit_node_config(node, slot, |_, value| match (value, to) {
+                (ValueMut::String(s), Value::String(to)) => *s = to,
+                (ValueMut::I32(v), Value::I32(to)) => *v = to,
+                _ => panic!("mismatched config"),
+            })
+            .unwrap();
+    };
+    let at_frame = |engine: &mut grafiek_engine::Engine, frame| {
+        engine.set_timing(TimeInfo {
+            frame,
+            ..Default::default()
+        });
+        engine.execute();
+    };
+
+    let mut engine = common::engine();
+    let grayscale = engine.instance_node("shader", "grayscale").unwrap();
+    let sink = engine.instance_node("core", "file_output").unwrap();
+    engine.connect(grayscale, sink, 0, 0).unwrap();
+
+    // The frame number fills the run of #, the format adds its extension
+    let path = dir.join("images/out_####").to_string_lossy().into_owned();
+    set(&mut engine, sink, 0, Value::String(path));
+    set(&mut engine, sink, 1, Value::I32(FileFormat::Png as i32));
+    set(&mut engine, sink, 2, Value::I32(BitDepth::Sixteen as i32));
+    at_frame(&mut engine, 3);
+    assert!(!engine.node_has_errors(sink));
+    let written = ImageData::open(dir.join("images/out_0003.png")).unwrap();
+    let Value::Texture(rendered) = engine.get_node(grayscale).unwrap().output(0).unwrap().1 else {
+        panic!("expected a texture output");
+    };
+    assert_eq!(
+        (written.width(), written.height()),
+        (rendered.width(), rendered.height())
+    );
+    assert_eq!(written.format(), TextureFormat::RGBAu16);
+
+    // Textures can't be written as text
+    set(&mut engine, sink, 1, Value::I32(FileFormat::Csv as i32));
+    at_frame(&mut engine, 4);
+    assert!(engine.node_has_errors(sink));
+
+    let mut engine = common::engine();
+    let input = engine.instance_node("core", "input").unwrap();
+    let sink = engine.instance_node("core", "file_output").unwrap();
+    engine.connect(input, sink, 0, 0).unwrap();
+
+    let csv = dir.join("values.csv");
+    set(
+        &mut engine,
+        sink,
+        0,
+        Value::String(csv.to_string_lossy().into_owned()),
+    );
+    at_frame(&mut engine, 0);
+    at_frame(&mut engine, 1);
+    assert!(!engine.node_has_errors(sink));
+    let rows = std::fs::read_to_string(&csv).unwrap();
+    assert_eq!(rows.lines().count(), 3);
+    assert!(rows.starts_with("frame,time,value\n"));
+
+    // Replaying after a reset starts the file over instead of appending
+    engine.reset();
+    at_frame(&mut engine, 0);
+    let rows = std::fs::read_to_string(&csv).unwrap();
+    assert_eq!(rows, "frame,time,value\n0,0,0\n");
+
+    // Waits for the write input to turn on
+    std::fs::remove_file(&csv).unwrap();
+    set(
+        &mut engine,
+        sink,
+        3,
+        Value::I32(WriteMode::OnTrigger as i32),
+    );
+    at_frame(&mut engine, 2);
+    assert!(!engine.node_has_errors(sink));
+    assert!(!csv.exists());
+
+    // Writes once per rising edge, after a reset a held trigger counts as one
+    engine
+        .edit_node_input(sink, 1, |_, value| {
+            if let ValueMut::Bool(v) = value {
+                *v = true;
+            }
+        })
+        .unwrap();
+    at_frame(&mut engine, 3);
+    assert!(csv.exists());
+    std::fs::remove_file(&csv).unwrap();
+    at_frame(&mut engine, 4);
+    assert!(!csv.exists());
+    assert!(engine.get_node(sink).unwrap().is_stateful());
+    engine.reset();
+    at_frame(&mut engine, 5);
+    assert!(csv.exists());
+
+    // Non-finite values are written as null to keep the JSON valid
+    let json = dir.join("value.json");
+    set(
+        &mut engine,
+        sink,
+        0,
+        Value::String(json.to_string_lossy().into_owned()),
+    );
+    set(&mut engine, sink, 3, Value::I32(WriteMode::Always as i32));
+    engine
+        .edit_graph_input(input, |_, value| {
+            if let ValueMut::F32(v) = value {
+                *v = f32::NAN;
+            }
+        })
+        .unwrap();
+    at_frame(&mut engine, 6);
+    assert!(!engine.node_has_errors(sink));
+    let written = std::fs::read_to_string(&json).unwrap();
+    assert!(written.contains("\"value\": null"), "{written}");
+
+    std::fs::remove_dir_all(&dir).unwrap();
+}